@@ -5,7 +5,44 @@
 // Repository: https://github.com/686f6c61/Xcom-mac-silicon
 //
 // Build script - Genera código necesario para Tauri en tiempo de compilación.
+//
+// Además deja en el entorno de compilación el commit (`XMAC_GIT_COMMIT`) y la
+// fecha (`XMAC_BUILD_TIMESTAMP`, segundos Unix) que muestra `get_app_info`.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Commit abreviado de HEAD, si se compila desde un clon de git
+fn git_commit() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--short", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!commit.is_empty()).then_some(commit)
+}
+
+/// Fecha de compilación; respeta `SOURCE_DATE_EPOCH` para builds reproducibles
+fn build_timestamp() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0))
+}
 
 fn main() {
+    println!("cargo:rustc-env=XMAC_GIT_COMMIT={}", git_commit().unwrap_or_else(|| "unknown".to_string()));
+    println!("cargo:rustc-env=XMAC_BUILD_TIMESTAMP={}", build_timestamp());
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    // Recompilar al cambiar de commit
+    for path in ["../.git/HEAD", "../.git/index"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+
     tauri_build::build()
 }
@@ -0,0 +1,165 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Detección de la cuenta con sesión iniciada a partir de la URL
+//
+// Por CORS no se puede ejecutar JS en x.com y leer el resultado, así que se
+// usa la URL de la ventana principal: en la página de un perfil
+// (`x.com/<username>`) el handle está en la ruta. Se comprueba al terminar
+// de cargar cada página y cuando `login-detector.js` avisa de que ha cambiado
+// la ruta (X es una SPA y la mayoría de navegaciones no recargan la página).
+//
+// Un perfil abierto no tiene por qué ser el de la sesión, así que el evento
+// `new-account-detected` es solo una sugerencia: el frontend decide si
+// preguntar al usuario antes de asociar la sesión a esa cuenta.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+
+use crate::accounts::{validate_username, AccountsManager};
+
+/// Evento emitido al ver un handle que no está en la lista de cuentas
+/// (payload: `NewAccountDetectedPayload`)
+pub const NEW_ACCOUNT_DETECTED_EVENT: &str = "new-account-detected";
+
+/// Hosts de X cuyas URLs se analizan (y sus subdominios `www.` y `mobile.`)
+const X_HOSTS: &[&str] = &["x.com", "twitter.com"];
+
+/// Primeros segmentos de ruta de X que no son perfiles
+const RESERVED_PATHS: &[&str] = &[
+    "home",
+    "explore",
+    "notifications",
+    "messages",
+    "search",
+    "settings",
+    "compose",
+    "login",
+    "logout",
+    "signup",
+    "account",
+    "i",
+    "intent",
+    "share",
+    "hashtag",
+    "tos",
+    "privacy",
+    "jobs",
+    "download",
+];
+
+/// Pestañas de un perfil (`x.com/<username>/<pestaña>`)
+const PROFILE_TABS: &[&str] = &["with_replies", "media", "likes", "highlights", "articles"];
+
+/// Payload de `new-account-detected`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct NewAccountDetectedPayload {
+    pub username: String,
+}
+
+/// Handle del perfil que muestra `url`, si es la página de un perfil de X
+///
+/// `x.com/alice` y sus pestañas (`x.com/alice/media`) son de `alice`; las
+/// rutas propias de X (`x.com/home`, `x.com/i/...`) y las publicaciones no
+/// cuentan.
+pub fn handle_from_url(url: &Url) -> Option<String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").or_else(|| host.strip_prefix("mobile.")).unwrap_or(&host);
+    if !X_HOSTS.contains(&host) {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    let handle = segments.next()?;
+    match segments.next() {
+        None => {}
+        Some(tab) if PROFILE_TABS.contains(&tab) && segments.next().is_none() => {}
+        Some(_) => return None,
+    }
+
+    if RESERVED_PATHS.iter().any(|reserved| reserved.eq_ignore_ascii_case(handle)) {
+        return None;
+    }
+
+    validate_username(handle).ok()
+}
+
+/// Handle del perfil abierto en la ventana principal, si lo hay
+///
+/// Si no está en la lista de cuentas emite `new-account-detected`.
+pub fn detect_logged_in_user<R: Runtime>(app: &AppHandle<R>) -> Result<Option<String>, String> {
+    let window = app
+        .get_webview_window(crate::window::MAIN_WINDOW)
+        .ok_or("Main window not found")?;
+    let url = window.url().map_err(|e| format!("Failed to read URL: {}", e))?;
+
+    let Some(username) = handle_from_url(&url) else {
+        return Ok(None);
+    };
+
+    let known = app
+        .state::<AccountsManager>()
+        .list_accounts(true)?
+        .iter()
+        .any(|account| account.username.eq_ignore_ascii_case(&username));
+
+    if !known {
+        tracing::info!("Detected account not in the list: {}", username);
+        let payload = NewAccountDetectedPayload {
+            username: username.clone(),
+        };
+        if let Err(e) = app.emit(NEW_ACCOUNT_DETECTED_EVENT, payload) {
+            tracing::error!("Failed to emit {}: {}", NEW_ACCOUNT_DETECTED_EVENT, e);
+        }
+    }
+
+    Ok(Some(username))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(url: &str) -> Option<String> {
+        handle_from_url(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_profile_urls() {
+        assert_eq!(handle("https://x.com/username").as_deref(), Some("username"));
+        assert_eq!(handle("https://x.com/alice_99/").as_deref(), Some("alice_99"));
+        assert_eq!(handle("https://x.com/alice?lang=es").as_deref(), Some("alice"));
+        assert_eq!(handle("https://twitter.com/alice").as_deref(), Some("alice"));
+        assert_eq!(handle("https://mobile.x.com/alice").as_deref(), Some("alice"));
+        assert_eq!(handle("https://x.com/alice/media").as_deref(), Some("alice"));
+        assert_eq!(handle("https://x.com/alice/with_replies").as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_non_profile_urls() {
+        for url in [
+            "https://x.com/home",
+            "https://x.com/",
+            "https://x.com/explore",
+            "https://x.com/Notifications",
+            "https://x.com/i/bookmarks",
+            "https://x.com/settings/account",
+            "https://x.com/alice/status/1234567890",
+            "https://x.com/alice/media/extra",
+            "https://x.com/this_name_is_too_long",
+            "https://x.com/not-valid",
+            "https://example.com/alice",
+            "https://notx.com/alice",
+            "tauri://localhost/alice",
+        ] {
+            assert_eq!(handle(url), None, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Información de la compilación para la ventana "Acerca de"
+//
+// El commit y la fecha los fija build.rs; si se compila fuera de un clon de
+// git, el commit es `unknown`.
+
+use serde::Serialize;
+
+/// Commit abreviado desde el que se compiló
+const GIT_COMMIT: &str = env!("XMAC_GIT_COMMIT");
+/// Momento de la compilación (segundos Unix)
+const BUILD_TIMESTAMP: &str = env!("XMAC_BUILD_TIMESTAMP");
+
+/// Versión y origen de la compilación en ejecución
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct AppInfo {
+    pub version: String,
+    pub commit: String,
+    /// Fecha de compilación (ISO 8601, UTC)
+    pub build_date: Option<String>,
+    pub tauri_version: String,
+}
+
+impl AppInfo {
+    /// Información de la compilación actual
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: GIT_COMMIT.to_string(),
+            build_date: build_date(BUILD_TIMESTAMP),
+            tauri_version: tauri::VERSION.to_string(),
+        }
+    }
+}
+
+/// Convierte el timestamp de build.rs en fecha ISO 8601
+fn build_date(timestamp: &str) -> Option<String> {
+    let seconds = timestamp.parse().ok()?;
+    chrono::DateTime::from_timestamp(seconds, 0).map(|date| date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_info_matches_build() {
+        let info = AppInfo::current();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.commit.is_empty());
+        assert!(info.build_date.is_some());
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["tauri_version"], tauri::VERSION);
+        assert!(json["commit"].is_string());
+        assert!(json["build_date"].is_string());
+    }
+
+    #[test]
+    fn test_build_date() {
+        assert_eq!(build_date("0").as_deref(), Some("1970-01-01T00:00:00Z"));
+        assert_eq!(build_date("1700000000").as_deref(), Some("2023-11-14T22:13:20Z"));
+        assert_eq!(build_date("not a number"), None);
+    }
+}
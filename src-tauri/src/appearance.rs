@@ -0,0 +1,228 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Apariencia (sistema, clara u oscura)
+//
+// X sigue `prefers-color-scheme`, así que basta con fijar el tema de las
+// ventanas (`set_theme`): con "sistema" se deja en `None` y macOS decide.
+// Como refuerzo, por si la preferencia guardada en X no está sincronizada con
+// la del dispositivo, también se escribe la cookie `night_mode` que usa X; se
+// aplica en la siguiente carga de la página.
+
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, Theme};
+
+use crate::accounts::AccountsManager;
+use crate::error::AppError;
+use crate::settings::SettingsUpdate;
+
+/// Evento con el tema efectivo (`light` o `dark`) cada vez que cambia
+pub const APPEARANCE_CHANGED_EVENT: &str = "appearance-changed";
+/// Valor de `night_mode` de X para el tema oscuro ("Apagar las luces")
+const X_NIGHT_MODE_DARK: u8 = 2;
+
+/// Apariencia elegida por el usuario
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    /// La del sistema (y sus cambios)
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    /// Nombre en la configuración y en los comandos
+    pub fn code(self) -> &'static str {
+        match self {
+            Appearance::System => "system",
+            Appearance::Light => "light",
+            Appearance::Dark => "dark",
+        }
+    }
+
+    /// Interpreta `system`, `light` o `dark` (sin distinguir mayúsculas)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "system" => Some(Appearance::System),
+            "light" => Some(Appearance::Light),
+            "dark" => Some(Appearance::Dark),
+            _ => None,
+        }
+    }
+
+    /// Siguiente apariencia al alternar el tema desde el menú
+    /// (sistema → oscura → clara → sistema)
+    pub fn next(self) -> Self {
+        match self {
+            Appearance::System => Appearance::Dark,
+            Appearance::Dark => Appearance::Light,
+            Appearance::Light => Appearance::System,
+        }
+    }
+
+    /// Tema de las ventanas (`None`: el del sistema)
+    pub fn theme(self) -> Option<Theme> {
+        match self {
+            Appearance::System => None,
+            Appearance::Light => Some(Theme::Light),
+            Appearance::Dark => Some(Theme::Dark),
+        }
+    }
+}
+
+/// Apariencia aplicada
+static CURRENT: Lazy<RwLock<Appearance>> = Lazy::new(|| RwLock::new(Appearance::default()));
+
+/// Apariencia aplicada ahora
+pub fn current() -> Appearance {
+    *CURRENT.read().unwrap()
+}
+
+/// Nombre de un tema para el frontend
+fn theme_code(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// JS que fija la cookie `night_mode` de X (solo en páginas de X)
+fn night_mode_js(theme: Theme) -> String {
+    let night_mode = if theme == Theme::Dark { X_NIGHT_MODE_DARK } else { 0 };
+    format!(
+        "if (/(^|\\.)(x|twitter)\\.com$/.test(location.hostname)) {{ \
+            document.cookie = 'night_mode={}; path=/; max-age=31536000; secure; samesite=lax'; \
+        }}",
+        night_mode
+    )
+}
+
+/// Aplica `appearance` a todas las ventanas (también a las que se abran después)
+pub fn apply<R: Runtime>(app: &AppHandle<R>, appearance: Appearance) {
+    *CURRENT.write().unwrap() = appearance;
+    app.set_theme(appearance.theme());
+    propagate(app);
+}
+
+/// Avisa a cada ventana del tema efectivo: al frontend con
+/// `APPEARANCE_CHANGED_EVENT` y a X con la cookie `night_mode`
+fn propagate<R: Runtime>(app: &AppHandle<R>) {
+    let appearance = current();
+
+    for window in app.webview_windows().into_values() {
+        let Some(theme) = appearance.theme().or_else(|| window.theme().ok()) else {
+            continue;
+        };
+
+        if let Err(e) = window.eval(night_mode_js(theme)) {
+            tracing::debug!("Failed to sync X theme in {}: {}", window.label(), e);
+        }
+        if let Err(e) = window.emit_to(window.label(), APPEARANCE_CHANGED_EVENT, theme_code(theme)) {
+            tracing::error!("Failed to emit {}: {}", APPEARANCE_CHANGED_EVENT, e);
+        }
+    }
+}
+
+/// Cambio del tema del sistema (`WindowEvent::ThemeChanged`)
+///
+/// Solo se propaga con "sistema": con claro u oscuro el tema de las ventanas
+/// no depende del sistema.
+pub fn on_theme_changed<R: Runtime>(app: &AppHandle<R>, theme: Theme) {
+    if current() == Appearance::System {
+        tracing::info!("System theme changed to {}", theme_code(theme));
+        propagate(app);
+    }
+}
+
+/// Apariencia guardada con `save_appearance` (por defecto, la del sistema)
+pub fn load_appearance(manager: &AccountsManager) -> Appearance {
+    manager.settings().appearance
+}
+
+/// Guarda la apariencia para los próximos arranques
+pub fn save_appearance(manager: &AccountsManager, appearance: Appearance) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        appearance: Some(appearance),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// Guarda y aplica `appearance`, y actualiza las marcas del menú
+pub fn set_appearance<R: Runtime>(
+    app: &AppHandle<R>,
+    manager: &AccountsManager,
+    appearance: Appearance,
+) -> Result<(), AppError> {
+    save_appearance(manager, appearance)?;
+    apply(app, appearance);
+    crate::menu::builder::rebuild_menu(app).map_err(|e| AppError::Internal(format!("Failed to rebuild menu: {}", e)))?;
+
+    tracing::info!("Appearance set to {}", appearance.code());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+
+    #[test]
+    fn test_parse_appearance() {
+        assert_eq!(Appearance::parse("system"), Some(Appearance::System));
+        assert_eq!(Appearance::parse(" Dark "), Some(Appearance::Dark));
+        assert_eq!(Appearance::parse("LIGHT"), Some(Appearance::Light));
+        assert_eq!(Appearance::parse("dim"), None);
+        assert_eq!(Appearance::parse(""), None);
+
+        for appearance in [Appearance::System, Appearance::Light, Appearance::Dark] {
+            assert_eq!(Appearance::parse(appearance.code()), Some(appearance));
+        }
+    }
+
+    #[test]
+    fn test_theme_cycle() {
+        assert_eq!(Appearance::System.next(), Appearance::Dark);
+        assert_eq!(Appearance::Dark.next(), Appearance::Light);
+        assert_eq!(Appearance::Light.next(), Appearance::System);
+
+        // Tres pasos vuelven al principio
+        let mut appearance = Appearance::default();
+        for _ in 0..3 {
+            appearance = appearance.next();
+        }
+        assert_eq!(appearance, Appearance::System);
+    }
+
+    #[test]
+    fn test_window_theme() {
+        assert_eq!(Appearance::System.theme(), None);
+        assert_eq!(Appearance::Light.theme(), Some(Theme::Light));
+        assert_eq!(Appearance::Dark.theme(), Some(Theme::Dark));
+    }
+
+    #[test]
+    fn test_night_mode_js() {
+        assert!(night_mode_js(Theme::Dark).contains("'night_mode=2;"));
+        assert!(night_mode_js(Theme::Light).contains("'night_mode=0;"));
+        // Solo en las páginas de X
+        assert!(night_mode_js(Theme::Dark).starts_with("if (/(^|\\.)(x|twitter)\\.com$/.test(location.hostname))"));
+    }
+
+    #[test]
+    fn test_appearance_is_persisted() {
+        let manager = AccountsManager::new(Box::leak(Box::new(InMemoryStore::new())));
+        assert_eq!(load_appearance(&manager), Appearance::System);
+
+        save_appearance(&manager, Appearance::Dark).unwrap();
+        assert_eq!(load_appearance(&manager), Appearance::Dark);
+    }
+}
@@ -0,0 +1,354 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Copias de seguridad encriptadas de todas las cuentas (exportar/importar)
+
+use argon2::Argon2;
+use base64::Engine;
+use rand::{rngs::OsRng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::{self, AccountInfo, AccountsList, AccountsManager, Credentials};
+use crate::error::AppError;
+use crate::{decrypt_data, encrypt_data};
+
+/// Identificador del formato de archivo de copia de seguridad
+const BACKUP_FORMAT: &str = "xmac-accounts-backup";
+/// Versión actual del formato de archivo
+const BACKUP_VERSION: u32 = 1;
+/// Tamaño del salt aleatorio para derivar la clave desde la passphrase
+const SALT_SIZE: usize = 16;
+
+/// Archivo de copia de seguridad tal como se escribe en disco
+///
+/// La cabecera (formato, versión, salt) va en claro; `data` contiene el
+/// `BackupPayload` encriptado con AES-256-GCM.
+#[derive(Serialize, Deserialize)]
+struct BackupFile {
+    format: String,
+    version: u32,
+    salt: String,
+    data: String,
+}
+
+/// Contenido de la copia de seguridad (sensible, siempre encriptado en disco)
+#[derive(Serialize, Deserialize)]
+struct BackupPayload {
+    accounts: Vec<AccountInfo>,
+    active_username: Option<String>,
+    credentials: Vec<Credentials>,
+}
+
+/// Resultado de importar una copia de seguridad
+#[derive(Serialize, Debug, Default)]
+pub struct ImportSummary {
+    /// Usernames importados (nuevos o sobrescritos)
+    pub imported: Vec<String>,
+    /// Usernames omitidos por existir ya localmente
+    pub skipped: Vec<String>,
+}
+
+/// Deriva la clave AES-256 del archivo desde la passphrase usando Argon2id
+fn derive_backup_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Error deriving backup key: {}", e))?;
+
+    Ok(key)
+}
+
+/// Encripta el payload con una clave derivada de la passphrase y un salt nuevo
+fn seal_backup(payload: &BackupPayload, passphrase: &str) -> Result<String, String> {
+    let mut salt = [0u8; SALT_SIZE];
+    OsRng.fill(&mut salt);
+
+    let key = derive_backup_key(passphrase, &salt)?;
+
+    let json = serde_json::to_string(payload)
+        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+
+    let file = BackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        salt: base64::engine::general_purpose::STANDARD.encode(salt),
+        data: encrypt_data(&json, &key)?,
+    };
+
+    serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize backup: {}", e))
+}
+
+/// Valida la cabecera y desencripta el payload de una copia de seguridad
+fn open_backup(contents: &str, passphrase: &str) -> Result<BackupPayload, String> {
+    let file: BackupFile = serde_json::from_str(contents)
+        .map_err(|e| format!("Invalid backup file: {}", e))?;
+
+    if file.format != BACKUP_FORMAT {
+        return Err("Invalid backup file: unknown format".to_string());
+    }
+
+    if file.version > BACKUP_VERSION {
+        return Err(format!("Unsupported backup version: {}", file.version));
+    }
+
+    let salt = base64::engine::general_purpose::STANDARD
+        .decode(&file.salt)
+        .map_err(|e| format!("Invalid backup file: {}", e))?;
+
+    let key = derive_backup_key(passphrase, &salt)?;
+
+    // AES-GCM falla la verificación del tag si la passphrase no es la misma
+    let json = decrypt_data(&file.data, &key)
+        .map_err(|_| "Incorrect backup passphrase".to_string())?;
+
+    serde_json::from_str(&json).map_err(|e| format!("Invalid backup contents: {}", e))
+}
+
+/// Mezcla las cuentas de la copia con la lista local
+///
+/// Retorna las credenciales a escribir y el resumen de la importación.
+fn merge_backup(
+    accounts_list: &mut AccountsList,
+    payload: BackupPayload,
+    overwrite: bool,
+    limit: usize,
+) -> (Vec<Credentials>, ImportSummary) {
+    let mut summary = ImportSummary::default();
+    let mut to_store = Vec::new();
+
+    let mut next_order = accounts_list.accounts.iter()
+        .map(|a| a.order + 1)
+        .max()
+        .unwrap_or(0);
+
+    for mut account in payload.accounts {
+        let existing = accounts_list.accounts.iter()
+            .position(|a| a.username == account.username);
+
+        match existing {
+            Some(_) if !overwrite => {
+                summary.skipped.push(account.username);
+                continue;
+            }
+            Some(index) => {
+                // Conservar la posición local de la cuenta sobrescrita
+                account.order = accounts_list.accounts[index].order;
+                accounts_list.accounts[index] = account.clone();
+            }
+            None if accounts_list.accounts.len() >= limit => {
+                tracing::warn!("Account limit reached, skipping import of {}", account.username);
+                summary.skipped.push(account.username);
+                continue;
+            }
+            None => {
+                account.order = next_order;
+                next_order += 1;
+                accounts_list.accounts.push(account.clone());
+            }
+        }
+
+        if let Some(creds) = payload.credentials.iter().find(|c| c.username == account.username) {
+            to_store.push(creds.clone());
+        }
+
+        summary.imported.push(account.username);
+    }
+
+    // Respetar la cuenta activa de la copia solo si no hay ninguna local
+    if accounts_list.active_username.is_none() {
+        accounts_list.active_username = payload.active_username
+            .filter(|u| summary.imported.contains(u))
+            .or_else(|| accounts_list.accounts.first().map(|a| a.username.clone()));
+    }
+
+    (to_store, summary)
+}
+
+/// Exporta todas las cuentas y sus credenciales a un archivo encriptado
+pub fn export_accounts(manager: &AccountsManager, path: &str, passphrase: &str) -> Result<usize, String> {
+    if passphrase.is_empty() {
+        return Err("Backup passphrase cannot be empty".to_string());
+    }
+
+    let store = manager.store();
+    let accounts_list = manager.snapshot()?;
+
+    let mut credentials = Vec::new();
+    for account in &accounts_list.accounts {
+        match accounts::load_credentials(store, &account.username)? {
+            Some(creds) => credentials.push(creds),
+            None => tracing::warn!("No credentials found for account: {}", account.username),
+        }
+    }
+
+    let payload = BackupPayload {
+        accounts: accounts_list.accounts,
+        active_username: accounts_list.active_username,
+        credentials,
+    };
+
+    let contents = seal_backup(&payload, passphrase)?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write backup file: {}", e))?;
+
+    tracing::info!("Exported {} accounts to backup", payload.accounts.len());
+
+    Ok(payload.accounts.len())
+}
+
+/// Importa las cuentas de un archivo encriptado, mezclándolas con las existentes
+pub fn import_accounts(
+    manager: &AccountsManager,
+    path: &str,
+    passphrase: &str,
+    overwrite: bool,
+) -> Result<ImportSummary, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read backup file: {}", e))?;
+
+    let payload = open_backup(&contents, passphrase)?;
+
+    let limit = manager.settings().max_accounts;
+    let summary = manager.update(|store, accounts_list| -> Result<_, AppError> {
+        let (to_store, summary) = merge_backup(accounts_list, payload, overwrite, limit);
+
+        for creds in &to_store {
+            accounts::store_credentials(store, creds)?;
+        }
+
+        accounts::save_accounts_list(store, accounts_list)?;
+        Ok(summary)
+    })?;
+
+    tracing::info!(
+        "Imported {} accounts from backup ({} skipped)",
+        summary.imported.len(),
+        summary.skipped.len()
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::DEFAULT_MAX_ACCOUNTS;
+
+    fn test_account(username: &str, order: i32) -> AccountInfo {
+        AccountInfo {
+            username: username.to_string(),
+            uuid: format!("uuid-{}", username),
+            created_at: 1234567890,
+            last_used: 1234567890,
+            last_login: 0,
+            display_name: None,
+            avatar_url: None,
+            order,
+            needs_relogin: false,
+            pinned: false,
+            archived: false,
+            label: None,
+            color: None,
+            proxy_url: None,
+            session_valid: None,
+            last_validated: 0,
+        }
+    }
+
+    fn test_credentials(username: &str, token: &str) -> Credentials {
+        Credentials {
+            username: username.to_string(),
+            uuid: format!("uuid-{}", username),
+            token: Some(token.to_string()),
+            session_data: None,
+            created_at: 1234567890,
+            last_used: 1234567890,
+        }
+    }
+
+    fn test_payload() -> BackupPayload {
+        BackupPayload {
+            accounts: vec![test_account("alice", 0), test_account("bob", 1)],
+            active_username: Some("bob".to_string()),
+            credentials: vec![test_credentials("alice", "a-token"), test_credentials("bob", "b-token")],
+        }
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let sealed = seal_backup(&test_payload(), "correct horse").unwrap();
+        let opened = open_backup(&sealed, "correct horse").unwrap();
+
+        assert_eq!(opened.accounts.len(), 2);
+        assert_eq!(opened.active_username.as_deref(), Some("bob"));
+        assert_eq!(opened.credentials[0].token.as_deref(), Some("a-token"));
+    }
+
+    #[test]
+    fn test_backup_wrong_passphrase() {
+        let sealed = seal_backup(&test_payload(), "correct horse").unwrap();
+        let result = open_backup(&sealed, "battery staple");
+
+        assert_eq!(result.err().as_deref(), Some("Incorrect backup passphrase"));
+    }
+
+    #[test]
+    fn test_backup_does_not_contain_plaintext() {
+        let sealed = seal_backup(&test_payload(), "correct horse").unwrap();
+
+        assert!(!sealed.contains("a-token"));
+        assert!(!sealed.contains("alice"));
+    }
+
+    #[test]
+    fn test_merge_skips_duplicates_without_overwrite() {
+        let mut list = AccountsList {
+            accounts: vec![test_account("alice", 0)],
+            active_username: Some("alice".to_string()),
+            ..Default::default()
+        };
+
+        let (to_store, summary) = merge_backup(&mut list, test_payload(), false, DEFAULT_MAX_ACCOUNTS);
+
+        assert_eq!(summary.imported, vec!["bob".to_string()]);
+        assert_eq!(summary.skipped, vec!["alice".to_string()]);
+        assert_eq!(to_store.len(), 1);
+        assert_eq!(list.accounts.len(), 2);
+        assert_eq!(list.active_username.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_merge_overwrites_duplicates() {
+        let mut list = AccountsList {
+            accounts: vec![test_account("alice", 5)],
+            active_username: None,
+            ..Default::default()
+        };
+
+        let (to_store, summary) = merge_backup(&mut list, test_payload(), true, DEFAULT_MAX_ACCOUNTS);
+
+        assert_eq!(summary.imported.len(), 2);
+        assert!(summary.skipped.is_empty());
+        assert_eq!(to_store.len(), 2);
+        assert_eq!(list.accounts[0].order, 5);
+        assert_eq!(list.active_username.as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn test_merge_respects_account_limit() {
+        let mut list = AccountsList {
+            accounts: vec![test_account("carol", 0)],
+            ..Default::default()
+        };
+
+        let (to_store, summary) = merge_backup(&mut list, test_payload(), false, 2);
+
+        assert_eq!(summary.imported, vec!["alice".to_string()]);
+        assert_eq!(summary.skipped, vec!["bob".to_string()]);
+        assert_eq!(to_store.len(), 1);
+        assert_eq!(list.accounts.len(), 2);
+    }
+}
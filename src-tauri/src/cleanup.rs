@@ -0,0 +1,289 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Limpieza de entradas huérfanas del Keychain
+//
+// Los nombres de las entradas son hashes Argon2id, así que una entrada que ya
+// no corresponde a ninguna cuenta (p. ej. tras un fallo entre guardar la lista
+// y las credenciales) no la vuelve a leer ni a borrar nadie. Aquí se comparan
+// todas las entradas del servicio con las que la aplicación espera encontrar.
+//
+// La enumeración no es fiable en todos los almacenes (el índice de `keyring`
+// solo conoce lo escrito desde que existe, y el Keychain puede omitir entradas
+// sin permiso de lectura), así que además se prueban por nombre las entradas
+// que la aplicación pudo dejar: credenciales de cuentas que solo siguen en la
+// copia de seguridad de la lista, partes sobrantes, entradas temporales de la
+// rotación y la cuenta provisional de la migración de v0.3.0.
+
+use std::collections::{BTreeSet, HashSet};
+
+use serde::Serialize;
+
+use crate::accounts::{
+    accounts_list_backup_key, accounts_list_key, credentials_key, credentials_part_key, decrypt_accounts_list,
+    key_secret_key, stored_part_count, AccountsList, AccountsManager, LEGACY_CREDENTIALS_ENTRY,
+    LEGACY_PLACEHOLDER_USERNAME,
+};
+use crate::error::AppError;
+use crate::hash_key;
+use crate::rotation::temp_key;
+use crate::secrets::SecretStore;
+
+/// Resultado de `cleanup_keychain`
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct CleanupReport {
+    /// Nombres (hasheados) de las entradas huérfanas encontradas
+    pub orphaned: Vec<String>,
+    /// Entradas eliminadas (0 en modo preview)
+    pub removed: usize,
+    /// Solo se han listado las entradas, sin borrar nada
+    pub preview: bool,
+}
+
+/// Entradas propias de la aplicación que no dependen de las cuentas
+///
+/// La lista de cuentas y su copia de seguridad están siempre aquí: la
+/// limpieza nunca debe borrarlas. Cualquier entrada nueva que guarde la
+/// aplicación tiene que añadirse también.
+fn app_keys() -> Vec<String> {
+    vec![
+        accounts_list_key(),
+        accounts_list_backup_key(),
+        key_secret_key(),
+        hash_key(LEGACY_CREDENTIALS_ENTRY),
+        hash_key(crate::window_state::LEGACY_GEOMETRY_KEY),
+        hash_key(&crate::window_state::legacy_geometry_key(crate::window::ACCOUNTS_MANAGER_WINDOW)),
+    ]
+}
+
+/// Entradas que deberían existir para la lista de cuentas dada
+fn expected_keys(store: &dyn SecretStore, accounts_list: &AccountsList) -> HashSet<String> {
+    let mut keys: HashSet<String> = app_keys().into_iter().collect();
+
+    for account in &accounts_list.accounts {
+        keys.insert(credentials_key(&account.username));
+
+        for part in 0..stored_part_count(store, &account.username) {
+            keys.insert(credentials_part_key(&account.username, part));
+        }
+    }
+
+    keys
+}
+
+/// Nombres de cuenta que pueden tener entradas en el almacén: los de la
+/// lista, los de su copia de seguridad y la cuenta provisional de la migración
+fn known_usernames(store: &dyn SecretStore, accounts_list: &AccountsList) -> BTreeSet<String> {
+    let backup = store
+        .get(&accounts_list_backup_key())
+        .and_then(|data| decrypt_accounts_list(store, data).ok())
+        .unwrap_or_default();
+
+    accounts_list
+        .accounts
+        .iter()
+        .chain(&backup.accounts)
+        .map(|account| account.username.clone())
+        .chain([LEGACY_PLACEHOLDER_USERNAME.to_string()])
+        .collect()
+}
+
+/// Entradas que la aplicación pudo escribir, derivadas de los nombres de
+/// cuenta conocidos (no dependen de que el almacén sepa enumerarlas)
+fn candidate_keys(store: &dyn SecretStore, accounts_list: &AccountsList) -> Vec<String> {
+    let mut keys = vec![temp_key("accounts_list"), temp_key("key_secret")];
+
+    for username in known_usernames(store, accounts_list) {
+        let credentials = credentials_key(&username);
+        keys.push(temp_key(&format!("credentials_{}", credentials)));
+        keys.push(credentials);
+
+        // Las partes se prueban hasta la primera que falte, incluidas las que
+        // quedaron por encima del número de partes actual
+        for part in 0.. {
+            let key = credentials_part_key(&username, part);
+            if store.get(&key).is_none() && part >= stored_part_count(store, &username) {
+                break;
+            }
+            keys.push(temp_key(&format!("credentials_{}", key)));
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+/// Entradas del almacén que no corresponden a la aplicación ni a ninguna cuenta
+///
+/// Combina las que enumera el almacén con las candidatas derivadas de los
+/// nombres de cuenta que siguen existiendo.
+pub fn find_orphans(store: &dyn SecretStore, accounts_list: &AccountsList) -> Vec<String> {
+    let expected = expected_keys(store, accounts_list);
+
+    let probed = candidate_keys(store, accounts_list)
+        .into_iter()
+        .filter(|key| store.get(key).is_some());
+
+    let orphans: BTreeSet<String> = store
+        .list_keys()
+        .into_iter()
+        .chain(probed)
+        .filter(|key| !expected.contains(key))
+        .collect();
+
+    orphans.into_iter().collect()
+}
+
+/// Busca las entradas huérfanas y, salvo en modo `preview`, las elimina
+///
+/// Se ejecuta con la lista de cuentas bloqueada para que un alta simultánea
+/// no pierda sus credenciales recién escritas.
+pub fn cleanup_keychain(manager: &AccountsManager, preview: bool) -> Result<CleanupReport, AppError> {
+    manager.update(|store, accounts_list| -> Result<_, AppError> {
+        let orphaned = find_orphans(store, accounts_list);
+
+        if !preview {
+            for key in &orphaned {
+                store.delete(key);
+            }
+        }
+
+        tracing::info!(
+            "Keychain cleanup{}: {} orphaned entries",
+            if preview { " (preview)" } else { "" },
+            orphaned.len()
+        );
+
+        Ok(CleanupReport {
+            removed: if preview { 0 } else { orphaned.len() },
+            orphaned,
+            preview,
+        })
+    })
+}
+
+/// Nombres (hasheados) de las entradas huérfanas, sin borrar nada
+pub fn list_orphaned_keychain_entries(manager: &AccountsManager) -> Result<Vec<String>, AppError> {
+    cleanup_keychain(manager, true).map(|report| report.orphaned)
+}
+
+/// Elimina las entradas huérfanas y retorna cuántas se han borrado
+pub fn purge_orphaned_entries(manager: &AccountsManager) -> Result<usize, AppError> {
+    cleanup_keychain(manager, false).map(|report| report.removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::CREDENTIALS_CHUNK_SIZE;
+    use crate::secrets::InMemoryStore;
+
+    fn test_manager() -> (&'static InMemoryStore, AccountsManager) {
+        let store: &'static InMemoryStore = Box::leak(Box::new(InMemoryStore::new()));
+        (store, AccountsManager::new(store))
+    }
+
+    /// Almacén que no sabe enumerar sus entradas
+    #[derive(Default)]
+    struct BlindStore(InMemoryStore);
+
+    impl SecretStore for BlindStore {
+        fn get(&self, key: &str) -> Option<Vec<u8>> {
+            self.0.get(key)
+        }
+
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+            self.0.set(key, value)
+        }
+
+        fn delete(&self, key: &str) {
+            self.0.delete(key)
+        }
+
+        fn list_keys(&self) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_cleanup_removes_only_orphans() {
+        let (store, manager) = test_manager();
+        manager.add_account("alice", Some("t".to_string()), None).unwrap();
+        manager.add_account("bob", None, Some("x".repeat(CREDENTIALS_CHUNK_SIZE * 2))).unwrap();
+
+        // Credenciales sin cuenta y una entrada temporal abandonada
+        store.set(&credentials_key("ghost"), b"stale").unwrap();
+        store.set(&hash_key("rotating_accounts_list"), b"stale").unwrap();
+
+        let report = cleanup_keychain(&manager, false).unwrap();
+
+        assert_eq!(report.removed, 2);
+        assert!(store.get(&credentials_key("ghost")).is_none());
+        assert!(store.get(&accounts_list_key()).is_some());
+        assert!(store.get(&accounts_list_backup_key()).is_some());
+        assert!(crate::accounts::load_credentials(store, "bob").unwrap().is_some());
+
+        // Una segunda pasada ya no encuentra nada
+        assert_eq!(cleanup_keychain(&manager, false).unwrap().orphaned, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_cleanup_preview_deletes_nothing() {
+        let (store, manager) = test_manager();
+        manager.add_account("alice", None, None).unwrap();
+        store.set(&credentials_key("ghost"), b"stale").unwrap();
+        let before = store.snapshot();
+
+        let report = cleanup_keychain(&manager, true).unwrap();
+
+        assert_eq!(report.orphaned, vec![credentials_key("ghost")]);
+        assert_eq!(report.removed, 0);
+        assert!(report.preview);
+        assert_eq!(store.snapshot(), before);
+    }
+
+    #[test]
+    fn test_orphans_found_without_enumeration() {
+        let store: &'static BlindStore = Box::leak(Box::default());
+        let manager = AccountsManager::new(store);
+        manager.add_account("alice", None, Some("x".repeat(CREDENTIALS_CHUNK_SIZE * 2))).unwrap();
+        manager.add_account("carol", Some("t".to_string()), None).unwrap();
+        manager.remove_account("carol", true).unwrap();
+
+        // carol solo sigue en la copia de seguridad de la lista
+        store.set(&credentials_key("carol"), b"stale").unwrap();
+        // Parte sobrante por encima de las que usa alice
+        let extra = credentials_part_key("alice", stored_part_count(store, "alice"));
+        store.set(&extra, b"stale").unwrap();
+        // Restos de una rotación y de la migración
+        store.set(&temp_key("key_secret"), b"stale").unwrap();
+        store.set(&credentials_key(LEGACY_PLACEHOLDER_USERNAME), b"stale").unwrap();
+
+        let mut expected = vec![
+            credentials_key("carol"),
+            extra,
+            temp_key("key_secret"),
+            credentials_key(LEGACY_PLACEHOLDER_USERNAME),
+        ];
+        expected.sort();
+
+        assert_eq!(list_orphaned_keychain_entries(&manager).unwrap(), expected);
+        assert_eq!(purge_orphaned_entries(&manager).unwrap(), 4);
+        assert!(store.get(&credentials_key("carol")).is_none());
+        assert!(crate::accounts::load_credentials(store, "alice").unwrap().is_some());
+        assert!(list_orphaned_keychain_entries(&manager).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_placeholder_account_credentials_are_kept() {
+        let (store, manager) = test_manager();
+        manager.add_account(LEGACY_PLACEHOLDER_USERNAME, Some("t".to_string()), None).unwrap();
+
+        assert!(list_orphaned_keychain_entries(&manager).unwrap().is_empty());
+        assert_eq!(purge_orphaned_entries(&manager).unwrap(), 0);
+        assert!(crate::accounts::load_credentials(store, LEGACY_PLACEHOLDER_USERNAME).unwrap().is_some());
+    }
+}
@@ -0,0 +1,158 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Enlaces `xmac://` abiertos desde otras aplicaciones
+//
+// La app se registra (plugin deep-link) como manejador de su esquema propio
+// `xmac://` (`xmac://x.com/user/status/1`). Los enlaces `https://x.com/...` y
+// `https://twitter.com/...` no se pueden reclamar: macOS solo entrega a una
+// app los enlaces https de un dominio (Universal Links) si el propio dominio
+// la autoriza en su `apple-app-site-association`, y x.com no lo hace para
+// apps de terceros. Declarar Associated Domains sin esa autorización no
+// cambia nada y obliga a firmar con un perfil de aprovisionamiento, así que
+// esos enlaces los sigue abriendo el navegador.
+//
+// Cada enlace recibido se normaliza a `https://x.com/...` y se abre en la
+// ventana enfocada.
+//
+// Si la app no estaba abierta, el enlace llega antes de que la ventana
+// principal esté lista: se guarda y se abre al final del `setup`.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, Url};
+
+/// Esquema propio registrado en `tauri.conf.json`
+pub const APP_SCHEME: &str = "xmac";
+
+/// Hosts de X que se abren en la app
+const X_HOSTS: &[&str] = &[
+    "x.com",
+    "www.x.com",
+    "mobile.x.com",
+    "twitter.com",
+    "www.twitter.com",
+    "mobile.twitter.com",
+];
+
+/// Enlaces recibidos antes de que la ventana principal esté lista
+#[derive(Default)]
+pub struct DeepLinks(Mutex<Pending>);
+
+#[derive(Default)]
+struct Pending {
+    ready: bool,
+    urls: Vec<Url>,
+}
+
+/// Convierte un enlace de X en su URL de x.com
+///
+/// Acepta `http`, `https` y `xmac` con cualquiera de los hosts de X (también
+/// twitter.com); cualquier otro enlace devuelve `None`.
+pub fn normalize_url(url: &Url) -> Option<Url> {
+    if !matches!(url.scheme(), "http" | "https" | APP_SCHEME) {
+        return None;
+    }
+
+    let host = url.host_str()?.to_ascii_lowercase();
+    if !X_HOSTS.contains(&host.as_str()) {
+        return None;
+    }
+
+    let mut normalized = Url::parse("https://x.com").ok()?;
+    normalized.set_path(url.path());
+    normalized.set_query(url.query());
+    normalized.set_fragment(url.fragment());
+    Some(normalized)
+}
+
+/// Abre los enlaces recibidos, o los guarda si la app aún está arrancando
+///
+/// Si llegan varios a la vez solo se abre el último: todos irían a la misma
+/// ventana.
+pub fn handle_urls<R: Runtime>(app: &AppHandle<R>, urls: Vec<Url>) {
+    let urls: Vec<Url> = urls
+        .iter()
+        .filter_map(|url| {
+            let normalized = normalize_url(url);
+            if normalized.is_none() {
+                tracing::warn!("Ignoring deep link to {}", url);
+            }
+            normalized
+        })
+        .collect();
+
+    let Some(url) = urls.last() else {
+        return;
+    };
+
+    if let Some(state) = app.try_state::<DeepLinks>() {
+        let mut pending = state.0.lock().unwrap();
+        if !pending.ready {
+            tracing::info!("Queuing deep link until the main window is ready");
+            pending.urls.extend(urls);
+            return;
+        }
+    }
+
+    open(app, url);
+}
+
+/// Marca la ventana principal como lista y abre el último enlace pendiente
+pub fn mark_ready<R: Runtime>(app: &AppHandle<R>) {
+    let Some(state) = app.try_state::<DeepLinks>() else {
+        return;
+    };
+
+    let pending = {
+        let mut pending = state.0.lock().unwrap();
+        pending.ready = true;
+        std::mem::take(&mut pending.urls)
+    };
+
+    if let Some(url) = pending.last() {
+        open(app, url);
+    }
+}
+
+fn open<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    if let Err(e) = crate::menu::navigation::open_in_app(app, url.as_str()) {
+        tracing::error!("Failed to open deep link {}: {}", url, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(raw: &str) -> Option<String> {
+        normalize_url(&Url::parse(raw).unwrap()).map(String::from)
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize("https://x.com/alice/status/1").as_deref(),
+            Some("https://x.com/alice/status/1")
+        );
+        assert_eq!(
+            normalize("https://twitter.com/alice/status/1?s=20").as_deref(),
+            Some("https://x.com/alice/status/1?s=20")
+        );
+        assert_eq!(normalize("http://mobile.twitter.com/alice").as_deref(), Some("https://x.com/alice"));
+        assert_eq!(normalize("https://WWW.X.COM/home").as_deref(), Some("https://x.com/home"));
+        assert_eq!(normalize("xmac://x.com/i/bookmarks").as_deref(), Some("https://x.com/i/bookmarks"));
+        assert_eq!(normalize("xmac://twitter.com").as_deref(), Some("https://x.com/"));
+    }
+
+    #[test]
+    fn test_normalize_rejects_other_links() {
+        assert_eq!(normalize("https://example.com/alice"), None);
+        assert_eq!(normalize("https://x.com.evil.example/alice"), None);
+        assert_eq!(normalize("ftp://x.com/alice"), None);
+        assert_eq!(normalize("xmac:alice"), None);
+    }
+}
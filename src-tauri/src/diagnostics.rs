@@ -0,0 +1,242 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Informe de diagnóstico para adjuntar a un informe de error
+//
+// Reúne la versión de la app y del sistema, el estado de cada cuenta (fechas
+// y si sus credenciales se descifran) y las últimas líneas del log. Nunca
+// incluye tokens ni sesiones, y los nombres de usuario van enmascarados,
+// también dentro de las líneas del log.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::accounts::{AccountInfo, AccountsManager};
+use crate::app_info::AppInfo;
+
+/// Líneas del log que se incluyen
+const LOG_TAIL_LINES: usize = 200;
+/// Prefijo y extensión de los ficheros de log (ver `logging`)
+const LOG_FILE_PREFIX: &str = "xmac.";
+const LOG_FILE_SUFFIX: &str = ".log";
+
+/// Estado de una cuenta, sin datos que la identifiquen
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AccountDiagnostics {
+    /// Nombre de usuario enmascarado (`alice` → `a***e`)
+    pub username: String,
+    pub created_at: i64,
+    pub last_used: i64,
+    /// Si las credenciales guardadas se descifran correctamente
+    pub decryption_ok: bool,
+}
+
+/// Informe completo
+#[derive(Serialize, Debug)]
+pub struct Diagnostics {
+    pub app: AppInfo,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub os_version: Option<String>,
+    pub account_count: usize,
+    pub accounts: Vec<AccountDiagnostics>,
+    /// Últimas líneas del log más reciente
+    pub log_tail: Vec<String>,
+    /// Fallos al reunir el informe (el resto de campos sigue siendo válido)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Enmascara un nombre de usuario dejando solo la primera y la última letra
+pub fn mask_username(username: &str) -> String {
+    let chars: Vec<char> = username.chars().collect();
+    match chars.as_slice() {
+        [first, .., last] if chars.len() > 2 => format!("{}***{}", first, last),
+        _ => "***".to_string(),
+    }
+}
+
+/// Sustituye en `line` cada nombre de `usernames` por su versión enmascarada
+fn mask_line(line: &str, usernames: &[&str]) -> String {
+    usernames
+        .iter()
+        .filter(|username| !username.is_empty())
+        .fold(line.to_string(), |line, username| line.replace(username, &mask_username(username)))
+}
+
+/// Versión de macOS (`sw_vers -productVersion`)
+#[cfg(target_os = "macos")]
+fn os_version() -> Option<String> {
+    let output = std::process::Command::new("sw_vers").arg("-productVersion").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !version.is_empty()).then_some(version)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn os_version() -> Option<String> {
+    None
+}
+
+/// Últimas `max_lines` líneas del fichero de log más reciente de `directory`
+///
+/// Los ficheros llevan la fecha en el nombre (`xmac.2025-03-01.log`), así que
+/// el más reciente es el último en orden alfabético.
+pub fn log_tail(directory: &Path, max_lines: usize) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(directory)
+        .map_err(|e| format!("Failed to read log directory {}: {}", directory.display(), e))?;
+
+    let newest = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX))
+        })
+        .max();
+
+    let Some(path) = newest else {
+        return Ok(Vec::new());
+    };
+
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let text = String::from_utf8_lossy(&data);
+    let lines: Vec<&str> = text.lines().collect();
+
+    Ok(lines[lines.len().saturating_sub(max_lines)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Estado de `accounts` según el resultado de `verify_credentials`
+fn account_diagnostics(accounts: &[AccountInfo], verified: &[(String, bool)]) -> Vec<AccountDiagnostics> {
+    accounts
+        .iter()
+        .map(|account| AccountDiagnostics {
+            username: mask_username(&account.username),
+            created_at: account.created_at,
+            last_used: account.last_used,
+            decryption_ok: verified
+                .iter()
+                .any(|(username, ok)| *ok && *username == account.username),
+        })
+        .collect()
+}
+
+/// Genera el informe de diagnóstico en JSON
+///
+/// `log_dir` es el directorio de `logging::log_directory`; si no hay, el
+/// informe va sin log.
+pub fn generate_diagnostics(manager: &AccountsManager, log_dir: Option<&Path>) -> String {
+    let mut errors = Vec::new();
+
+    let accounts = manager.list_accounts(true).unwrap_or_else(|e| {
+        errors.push(format!("Failed to list accounts: {}", e));
+        Vec::new()
+    });
+    let verified = manager.verify_credentials().unwrap_or_else(|e| {
+        errors.push(format!("Failed to verify credentials: {}", e));
+        Vec::new()
+    });
+
+    let usernames: Vec<&str> = accounts.iter().map(|account| account.username.as_str()).collect();
+    let log_tail = match log_dir.map(|dir| log_tail(dir, LOG_TAIL_LINES)) {
+        Some(Ok(lines)) => lines.iter().map(|line| mask_line(line, &usernames)).collect(),
+        Some(Err(e)) => {
+            errors.push(e);
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+
+    let report = Diagnostics {
+        app: AppInfo::current(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        os_version: os_version(),
+        account_count: accounts.len(),
+        accounts: account_diagnostics(&accounts, &verified),
+        log_tail,
+        errors: errors.iter().map(|e| mask_line(e, &usernames)).collect(),
+    };
+
+    serde_json::to_string_pretty(&report).expect("diagnostics report serializes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+
+    fn test_manager() -> AccountsManager {
+        let store: &'static InMemoryStore = Box::leak(Box::new(InMemoryStore::new()));
+        AccountsManager::new(store)
+    }
+
+    #[test]
+    fn test_mask_username() {
+        assert_eq!(mask_username("alice"), "a***e");
+        assert_eq!(mask_username("bob"), "b***b");
+        assert_eq!(mask_username("jo"), "***");
+        assert_eq!(mask_username(""), "***");
+        assert_eq!(mask_username("ñandú"), "ñ***ú");
+    }
+
+    #[test]
+    fn test_report_has_no_secrets_or_usernames() {
+        let manager = test_manager();
+        manager
+            .add_account("alice_secret", Some("token-a-1234567890".to_string()), Some("session-a-abcdef".to_string()))
+            .unwrap();
+        manager.add_account("bob_hidden", Some("token-b-0987654321".to_string()), None).unwrap();
+
+        let report = generate_diagnostics(&manager, None);
+        for secret in ["token-a-1234567890", "session-a-abcdef", "token-b-0987654321", "alice_secret", "bob_hidden"] {
+            assert!(!report.contains(secret), "report leaks {:?}", secret);
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(json["account_count"], 2);
+        assert_eq!(json["app"]["version"], env!("CARGO_PKG_VERSION"));
+        let accounts = json["accounts"].as_array().unwrap();
+        assert!(accounts.iter().all(|a| a["decryption_ok"] == true));
+        assert!(accounts.iter().all(|a| a.get("token").is_none() && a.get("session_data").is_none()));
+        assert!(accounts.iter().any(|a| a["username"] == "a***t"));
+    }
+
+    #[test]
+    fn test_log_tail_reads_newest_file_and_masks_usernames() {
+        let dir = std::env::temp_dir().join(format!("xmac-diagnostics-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("xmac.2025-03-01.log"), "old line\n").unwrap();
+        std::fs::write(dir.join("xmac.2025-03-02.log"), "first\nSwitched to account alice_secret\nlast\n").unwrap();
+        std::fs::write(dir.join("other.log"), "ignored\n").unwrap();
+
+        assert_eq!(log_tail(&dir, 2).unwrap(), vec!["Switched to account alice_secret", "last"]);
+        assert_eq!(log_tail(&dir, 10).unwrap().len(), 3);
+
+        let manager = test_manager();
+        manager.add_account("alice_secret", Some("token-a".to_string()), None).unwrap();
+        let report = generate_diagnostics(&manager, Some(&dir));
+        assert!(!report.contains("alice_secret"));
+        assert!(report.contains("Switched to account a***t"));
+        assert!(!report.contains("old line"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_log_directory_is_reported() {
+        let dir = std::env::temp_dir().join("xmac-diagnostics-missing");
+        let report = generate_diagnostics(&test_manager(), Some(&dir));
+        let json: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(json["log_tail"].as_array().unwrap().len(), 0);
+        assert_eq!(json["errors"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,65 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Diálogos nativos de macOS
+//
+// Se muestran con `osascript` (`display alert`), incluido en macOS, para no
+// depender del frontend: el aviso aparece aunque la página de X no haya
+// cargado o la ventana con foco no tenga acceso a los comandos.
+
+use std::process::Command;
+
+/// Botón que cancela (y es el botón por defecto, el que pulsa Intro)
+const CANCEL_BUTTON: &str = "Cancelar";
+
+/// Script que muestra el aviso; el texto llega como argumentos para que no
+/// haga falta escaparlo
+const CONFIRM_SCRIPT: &[&str] = &[
+    "on run argv",
+    "display alert (item 1 of argv) message (item 2 of argv) as critical \
+     buttons {item 3 of argv, item 4 of argv} default button (item 3 of argv) \
+     cancel button (item 3 of argv)",
+    "end run",
+];
+
+/// Argumentos de `osascript` para un aviso de confirmación
+fn confirm_args(title: &str, message: &str, confirm_button: &str) -> Vec<String> {
+    CONFIRM_SCRIPT
+        .iter()
+        .flat_map(|line| ["-e".to_string(), line.to_string()])
+        .chain([title, message, CANCEL_BUTTON, confirm_button].map(str::to_string))
+        .collect()
+}
+
+/// Muestra un aviso de confirmación y espera la respuesta (bloqueante)
+///
+/// Retorna `true` solo si se pulsa `confirm_button`. Cancelar, cerrar el aviso
+/// o no poder mostrarlo cuentan como cancelación.
+pub fn confirm(title: &str, message: &str, confirm_button: &str) -> bool {
+    match Command::new("osascript").args(confirm_args(title, message, confirm_button)).output() {
+        // Con "cancel button", cancelar termina el script con error (-128)
+        Ok(output) => output.status.success(),
+        Err(e) => {
+            tracing::error!("Failed to show confirmation dialog: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_text_is_passed_as_arguments() {
+        let args = confirm_args("¿Eliminar?", "Con \"comillas\"", "Eliminar");
+
+        // El texto no forma parte del script
+        assert!(args.iter().all(|arg| !arg.contains("display alert") || !arg.contains("comillas")));
+        assert_eq!(&args[args.len() - 4..], ["¿Eliminar?", "Con \"comillas\"", CANCEL_BUTTON, "Eliminar"]);
+        assert_eq!(args.iter().filter(|arg| *arg == "-e").count(), CONFIRM_SCRIPT.len());
+    }
+}
@@ -0,0 +1,198 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Errores de la aplicación
+//
+// Los comandos Tauri devuelven `AppError`, que llega al frontend como
+// `{ "kind": "...", "message": "..." }` para poder distinguir, p. ej., una
+// cuenta inexistente de un Keychain inaccesible sin analizar el texto.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::accounts::ValidationError;
+
+/// Error devuelto por los comandos y por el gestor de cuentas
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// No existe ninguna cuenta con ese username
+    AccountNotFound(String),
+    /// Ya existe una cuenta con ese username
+    AccountExists(String),
+    /// La cuenta está archivada y no se puede activar
+    AccountArchived(String),
+    /// Se ha alcanzado el número máximo de cuentas configurado
+    AccountLimitReached(usize),
+    /// Operación irreversible sin la confirmación del usuario
+    ConfirmationRequired,
+    /// Datos de entrada rechazados antes de escribir en el Keychain
+    Validation(ValidationError),
+    /// Datos de entrada rechazados fuera de las cuentas (cada módulo define
+    /// su error junto a la validación y lo convierte a este)
+    InvalidInput(String),
+    /// Fallo al leer o escribir en el Keychain
+    KeychainAccess(String),
+    /// Fallo al derivar claves o encriptar
+    Encryption(String),
+    /// Datos corruptos o encriptados con otra clave
+    Decryption(String),
+    /// Fallo al (de)serializar JSON
+    Serialization(String),
+    /// Fallo en una petición HTTP
+    Network(String),
+    /// No hay conexión a internet
+    Offline,
+    /// Límite de peticiones de la API de GitHub agotado hasta ese momento
+    /// (segundos Unix)
+    RateLimited(i64),
+    /// Cualquier otro error (ventanas, WebView, archivos...)
+    Internal(String),
+}
+
+impl AppError {
+    /// Identificador estable del tipo de error para el frontend
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::AccountNotFound(_) => "account_not_found",
+            AppError::AccountExists(_) => "account_exists",
+            AppError::AccountArchived(_) => "account_archived",
+            AppError::AccountLimitReached(_) => "account_limit_reached",
+            AppError::ConfirmationRequired => "confirmation_required",
+            AppError::Validation(_) | AppError::InvalidInput(_) => "validation",
+            AppError::KeychainAccess(_) => "keychain_access",
+            AppError::Encryption(_) => "encryption",
+            AppError::Decryption(_) => "decryption",
+            AppError::Serialization(_) => "serialization",
+            AppError::Network(_) => "network",
+            AppError::Offline => "offline",
+            AppError::RateLimited(_) => "rate_limited",
+            AppError::Internal(_) => "internal",
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::AccountNotFound(username) => write!(f, "Account '{}' not found", username),
+            AppError::AccountExists(username) => write!(f, "Account '{}' already exists", username),
+            AppError::AccountArchived(username) => write!(f, "Account '{}' is archived", username),
+            AppError::AccountLimitReached(limit) => write!(f, "Account limit reached ({} accounts)", limit),
+            AppError::ConfirmationRequired => write!(f, "Account deletion requires confirmation"),
+            AppError::Offline => write!(f, "No internet connection"),
+            AppError::RateLimited(reset) => match chrono::DateTime::from_timestamp(*reset, 0) {
+                Some(reset) => write!(f, "GitHub API rate limit exceeded until {}", reset.to_rfc3339()),
+                None => write!(f, "GitHub API rate limit exceeded"),
+            },
+            AppError::Validation(error) => write!(f, "{}", error),
+            AppError::InvalidInput(message)
+            | AppError::KeychainAccess(message)
+            | AppError::Encryption(message)
+            | AppError::Decryption(message)
+            | AppError::Serialization(message)
+            | AppError::Network(message)
+            | AppError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<ValidationError> for AppError {
+    fn from(error: ValidationError) -> Self {
+        AppError::Validation(error)
+    }
+}
+
+/// Los módulos que aún devuelven `String` se consideran errores internos
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+/// Permite usar `?` con `AppError` en funciones que devuelven `String`
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_app_error_serializes_kind_and_message() {
+        assert_eq!(
+            serde_json::to_value(AppError::AccountNotFound("alice".to_string())).unwrap(),
+            json!({ "kind": "account_not_found", "message": "Account 'alice' not found" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::KeychainAccess("Keychain locked".to_string())).unwrap(),
+            json!({ "kind": "keychain_access", "message": "Keychain locked" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::Decryption("bad tag".to_string())).unwrap(),
+            json!({ "kind": "decryption", "message": "bad tag" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::Serialization("eof".to_string())).unwrap(),
+            json!({ "kind": "serialization", "message": "eof" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::AccountArchived("bob".to_string())).unwrap(),
+            json!({ "kind": "account_archived", "message": "Account 'bob' is archived" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::AccountLimitReached(10)).unwrap(),
+            json!({ "kind": "account_limit_reached", "message": "Account limit reached (10 accounts)" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::ConfirmationRequired).unwrap(),
+            json!({ "kind": "confirmation_required", "message": "Account deletion requires confirmation" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::RateLimited(1_700_000_000)).unwrap(),
+            json!({ "kind": "rate_limited", "message": "GitHub API rate limit exceeded until 2023-11-14T22:13:20+00:00" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppError::Offline).unwrap(),
+            json!({ "kind": "offline", "message": "No internet connection" })
+        );
+    }
+
+    #[test]
+    fn test_validation_error_keeps_field_in_message() {
+        let error = AppError::from(ValidationError::PayloadTooLarge { field: "token", size: 10, limit: 5 });
+
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["kind"], "validation");
+        assert!(value["message"].as_str().unwrap().contains("'token'"));
+    }
+
+    #[test]
+    fn test_string_conversions() {
+        assert_eq!(AppError::from("Main window not found"), AppError::Internal("Main window not found".to_string()));
+        assert_eq!(String::from(AppError::AccountExists("bob".to_string())), "Account 'bob' already exists");
+    }
+}
@@ -0,0 +1,321 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Traducciones de los textos del menú nativo
+//
+// Las tablas van embebidas en el binario. El idioma se elige al arrancar: el
+// guardado con `set_language` o, si no hay ninguno, el primero de los idiomas
+// preferidos de macOS que tenga tabla (inglés si ninguno la tiene). Una clave
+// que falta en el idioma actual se busca en inglés y, si tampoco está, se
+// muestra la propia clave.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::AccountsManager;
+use crate::error::AppError;
+use crate::settings::SettingsUpdate;
+
+/// Idiomas con traducciones
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Es,
+    En,
+}
+
+impl Language {
+    /// Código ISO 639-1 del idioma
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Es => "es",
+            Language::En => "en",
+        }
+    }
+
+    /// Interpreta un código de idioma (`es`, `en-US`, `es_ES.UTF-8`...)
+    ///
+    /// Solo cuenta el idioma, no la región; `None` si no hay tabla para él.
+    pub fn parse(code: &str) -> Option<Self> {
+        let language = code.split(['-', '_', '.']).next()?.trim().to_ascii_lowercase();
+
+        match language.as_str() {
+            "es" => Some(Language::Es),
+            "en" => Some(Language::En),
+            _ => None,
+        }
+    }
+
+    fn table(self) -> &'static HashMap<&'static str, &'static str> {
+        match self {
+            Language::Es => &ES,
+            Language::En => &EN,
+        }
+    }
+}
+
+/// Idioma actual de los menús
+static CURRENT: Lazy<RwLock<Language>> = Lazy::new(|| RwLock::new(system_language()));
+
+static ES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| ES_TABLE.iter().copied().collect());
+static EN: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| EN_TABLE.iter().copied().collect());
+
+const ES_TABLE: &[(&str, &str)] = &[
+    ("menu.app.settings", "Ajustes..."),
+    ("menu.app.appearance", "Apariencia"),
+    ("menu.app.appearance.system", "Usar la del sistema"),
+    ("menu.app.appearance.light", "Clara"),
+    ("menu.app.appearance.dark", "Oscura"),
+    ("menu.app.appearance.toggle", "Alternar Tema"),
+    ("menu.app.switch_account", "Cambiar de Cuenta"),
+    ("menu.file", "Archivo"),
+    ("menu.file.new_post", "Nueva Publicación"),
+    ("menu.file.save", "Guardar"),
+    ("menu.file.export_pdf", "Exportar como PDF…"),
+    ("menu.file.print", "Imprimir…"),
+    ("menu.file.close", "Cerrar"),
+    ("menu.file.close_all", "Cerrar Todo"),
+    ("menu.edit", "Edición"),
+    ("menu.edit.find", "Buscar en la página"),
+    ("menu.edit.find_next", "Buscar siguiente"),
+    ("menu.edit.find_previous", "Buscar anterior"),
+    ("menu.edit.search_x", "Buscar en X"),
+    ("menu.edit.copy_url", "Copiar enlace de la publicación actual"),
+    ("menu.accounts", "Cuentas"),
+    ("menu.accounts.add", "Agregar Cuenta..."),
+    ("menu.accounts.more", "Más cuentas…"),
+    ("menu.accounts.archived", "Cuentas archivadas"),
+    ("menu.accounts.delete_active", "Eliminar Cuenta Activa"),
+    ("menu.accounts.delete", "Eliminar cuenta…"),
+    ("menu.accounts.duplicate_window", "Duplicar cuenta activa a nueva ventana"),
+    ("menu.accounts.manage", "Gestionar cuentas…"),
+    ("menu.accounts.incognito_window", "Nueva ventana de incógnito"),
+    ("menu.accounts.export", "Exportar cuentas…"),
+    ("menu.accounts.import", "Importar cuentas…"),
+    ("menu.accounts.session_expired", "(sesión caducada)"),
+    ("menu.view", "Visualización"),
+    ("menu.view.reload", "Recargar"),
+    ("menu.view.reload_ignoring_cache", "Recargar ignorando caché"),
+    ("menu.view.fullscreen", "Pantalla Completa"),
+    ("menu.view.zoom_in", "Acercar"),
+    ("menu.view.zoom_out", "Alejar"),
+    ("menu.view.zoom_reset", "Tamaño Real"),
+    ("menu.navigation", "Navegación"),
+    ("menu.navigation.explore", "Explorar"),
+    ("menu.navigation.notifications", "Notificaciones"),
+    ("menu.navigation.messages", "Mensajes"),
+    ("menu.navigation.bookmarks", "Elementos Guardados"),
+    ("menu.navigation.lists", "Listas"),
+    ("menu.navigation.profile", "Perfil"),
+    ("menu.navigation.back", "Atrás"),
+    ("menu.navigation.forward", "Adelante"),
+    ("menu.navigation.scroll_top", "Ir Arriba"),
+    ("menu.navigation.scroll_bottom", "Ir Abajo"),
+    ("menu.navigation.scroll_timeline_top", "Ir al Principio de la Cronología"),
+    ("menu.window", "Ventana"),
+    ("menu.window.reset_state", "Restablecer Tamaño de Ventana"),
+    ("menu.help", "Ayuda"),
+    ("menu.help.check_updates", "Buscar Actualizaciones..."),
+    ("menu.help.auto_update_check", "Buscar Actualizaciones al Abrir"),
+    ("menu.help.update_to", "Actualizar a"),
+    ("menu.help.update_channel", "Canal de actualizaciones"),
+    ("menu.help.update_channel.stable", "Estable"),
+    ("menu.help.update_channel.beta", "Beta"),
+    ("menu.help.rotate_keys", "Rotar Claves de Cifrado..."),
+    ("menu.help.cleanup_keychain", "Limpiar Entradas Huérfanas del Llavero..."),
+    ("menu.help.verify_credentials", "Diagnóstico de credenciales"),
+    ("menu.help.diagnostics", "Copiar Informe de Diagnóstico"),
+];
+
+const EN_TABLE: &[(&str, &str)] = &[
+    ("menu.app.settings", "Settings..."),
+    ("menu.app.appearance", "Appearance"),
+    ("menu.app.appearance.system", "Use System Setting"),
+    ("menu.app.appearance.light", "Light"),
+    ("menu.app.appearance.dark", "Dark"),
+    ("menu.app.appearance.toggle", "Cycle Theme"),
+    ("menu.app.switch_account", "Switch Account"),
+    ("menu.file", "File"),
+    ("menu.file.new_post", "New Post"),
+    ("menu.file.save", "Save"),
+    ("menu.file.export_pdf", "Export as PDF…"),
+    ("menu.file.print", "Print…"),
+    ("menu.file.close", "Close"),
+    ("menu.file.close_all", "Close All"),
+    ("menu.edit", "Edit"),
+    ("menu.edit.find", "Find in Page"),
+    ("menu.edit.find_next", "Find Next"),
+    ("menu.edit.find_previous", "Find Previous"),
+    ("menu.edit.search_x", "Search X"),
+    ("menu.edit.copy_url", "Copy Link to Current Post"),
+    ("menu.accounts", "Accounts"),
+    ("menu.accounts.add", "Add Account..."),
+    ("menu.accounts.more", "More Accounts…"),
+    ("menu.accounts.archived", "Archived Accounts"),
+    ("menu.accounts.delete_active", "Remove Active Account"),
+    ("menu.accounts.delete", "Remove Account…"),
+    ("menu.accounts.duplicate_window", "Open Active Account in New Window"),
+    ("menu.accounts.manage", "Manage Accounts…"),
+    ("menu.accounts.incognito_window", "New Incognito Window"),
+    ("menu.accounts.export", "Export Accounts…"),
+    ("menu.accounts.import", "Import Accounts…"),
+    ("menu.accounts.session_expired", "(session expired)"),
+    ("menu.view", "View"),
+    ("menu.view.reload", "Reload"),
+    ("menu.view.reload_ignoring_cache", "Reload Ignoring Cache"),
+    ("menu.view.fullscreen", "Enter Full Screen"),
+    ("menu.view.zoom_in", "Zoom In"),
+    ("menu.view.zoom_out", "Zoom Out"),
+    ("menu.view.zoom_reset", "Actual Size"),
+    ("menu.navigation", "Navigate"),
+    ("menu.navigation.explore", "Explore"),
+    ("menu.navigation.notifications", "Notifications"),
+    ("menu.navigation.messages", "Messages"),
+    ("menu.navigation.bookmarks", "Bookmarks"),
+    ("menu.navigation.lists", "Lists"),
+    ("menu.navigation.profile", "Profile"),
+    ("menu.navigation.back", "Back"),
+    ("menu.navigation.forward", "Forward"),
+    ("menu.navigation.scroll_top", "Scroll to Top"),
+    ("menu.navigation.scroll_bottom", "Scroll to Bottom"),
+    ("menu.navigation.scroll_timeline_top", "Scroll to Top of Timeline"),
+    ("menu.window", "Window"),
+    ("menu.window.reset_state", "Reset Window Size"),
+    ("menu.help", "Help"),
+    ("menu.help.check_updates", "Check for Updates..."),
+    ("menu.help.auto_update_check", "Check for Updates at Launch"),
+    ("menu.help.update_to", "Update to"),
+    ("menu.help.update_channel", "Update Channel"),
+    ("menu.help.update_channel.stable", "Stable"),
+    ("menu.help.update_channel.beta", "Beta"),
+    ("menu.help.rotate_keys", "Rotate Encryption Keys..."),
+    ("menu.help.cleanup_keychain", "Clean Up Orphaned Keychain Entries..."),
+    ("menu.help.verify_credentials", "Credentials Diagnostics"),
+    ("menu.help.diagnostics", "Copy Diagnostics Report"),
+];
+
+/// Texto de `key` en el idioma actual
+///
+/// Si falta en ese idioma se usa el inglés, y si tampoco existe, la propia
+/// clave (así una traducción olvidada se ve en el menú sin romper nada).
+pub fn tr(key: &str) -> &str {
+    lookup(current(), key)
+}
+
+fn lookup(language: Language, key: &str) -> &str {
+    language
+        .table()
+        .get(key)
+        .or_else(|| EN.get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// Idioma actual de los menús
+pub fn current() -> Language {
+    *CURRENT.read().unwrap()
+}
+
+/// Cambia el idioma de los menús (hay que reconstruirlos para que se vea)
+pub fn set_current(language: Language) {
+    *CURRENT.write().unwrap() = language;
+}
+
+/// Primer idioma preferido del sistema con tabla, o inglés
+pub fn system_language() -> Language {
+    preferred_languages()
+        .iter()
+        .find_map(|code| Language::parse(code))
+        .unwrap_or(Language::En)
+}
+
+/// Idiomas preferidos del usuario en macOS (`NSLocale.preferredLanguages`),
+/// por orden de preferencia
+#[cfg(target_os = "macos")]
+fn preferred_languages() -> Vec<String> {
+    use objc2_foundation::NSLocale;
+
+    #[allow(unused_unsafe)]
+    unsafe {
+        let languages = NSLocale::preferredLanguages();
+        (0..languages.count())
+            .map(|index| languages.objectAtIndex(index).to_string())
+            .collect()
+    }
+}
+
+/// Fuera de macOS se usan las variables de entorno del locale
+#[cfg(not(target_os = "macos"))]
+fn preferred_languages() -> Vec<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Idioma guardado con `save_language`, si hay uno
+pub fn load_language(manager: &AccountsManager) -> Option<Language> {
+    manager.settings().language
+}
+
+/// Guarda el idioma para usarlo en los próximos arranques
+pub fn save_language(manager: &AccountsManager, language: Language) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        language: Some(language),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(Language::parse("es"), Some(Language::Es));
+        assert_eq!(Language::parse("es-ES"), Some(Language::Es));
+        assert_eq!(Language::parse("en_US.UTF-8"), Some(Language::En));
+        assert_eq!(Language::parse("EN"), Some(Language::En));
+        assert_eq!(Language::parse("fr-FR"), None);
+        assert_eq!(Language::parse(""), None);
+    }
+
+    #[test]
+    fn test_tables_have_the_same_keys() {
+        let mut es: Vec<&str> = ES_TABLE.iter().map(|(key, _)| *key).collect();
+        let mut en: Vec<&str> = EN_TABLE.iter().map(|(key, _)| *key).collect();
+        es.sort();
+        en.sort();
+
+        assert_eq!(es, en);
+        assert_eq!(ES.len(), ES_TABLE.len(), "duplicated key in ES_TABLE");
+        assert_eq!(EN.len(), EN_TABLE.len(), "duplicated key in EN_TABLE");
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup(Language::Es, "menu.accounts"), "Cuentas");
+        assert_eq!(lookup(Language::En, "menu.accounts"), "Accounts");
+        // Las claves desconocidas se muestran tal cual
+        assert_eq!(lookup(Language::Es, "menu.unknown"), "menu.unknown");
+        assert_eq!(lookup(Language::En, ""), "");
+    }
+
+    #[test]
+    fn test_language_is_persisted() {
+        let manager = AccountsManager::new(Box::leak(Box::new(InMemoryStore::new())));
+        assert_eq!(load_language(&manager), None);
+
+        save_language(&manager, Language::Es).unwrap();
+        assert_eq!(load_language(&manager), Some(Language::Es));
+    }
+}
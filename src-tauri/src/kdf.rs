@@ -0,0 +1,222 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Parámetros de Argon2id para derivar las claves de cifrado
+//
+// Por defecto se usan los de `Argon2::default()` (19 MiB, 2 iteraciones, 1
+// hilo). Se pueden ajustar con `XMAC_KDF_PARAMS=m_cost,t_cost,p_cost` para
+// derivar más rápido en equipos lentos o con más coste en equipos potentes.
+//
+// Los datos cifrados con parámetros distintos de los por defecto los llevan
+// delante (`argon2id$m=...,t=...,p=...$<base64>`), así que siempre se
+// descifran con los mismos con los que se cifraron aunque la configuración
+// cambie después. Los datos sin cabecera (todos los anteriores) usan los por
+// defecto.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use once_cell::sync::Lazy;
+use zeroize::Zeroizing;
+
+use crate::{decrypt_data, encrypt_data};
+
+/// Prefijo de los datos cifrados que indican sus parámetros
+const PARAMS_PREFIX: &str = "argon2id$";
+
+/// Parámetros configurados para los datos que se cifren a partir de ahora
+static CONFIGURED: Lazy<KdfParams> = Lazy::new(|| match std::env::var("XMAC_KDF_PARAMS") {
+    Ok(value) => KdfParams::parse(&value).unwrap_or_else(|| {
+        tracing::warn!("Ignoring invalid XMAC_KDF_PARAMS '{}', using defaults", value);
+        KdfParams::default()
+    }),
+    Err(_) => KdfParams::default(),
+});
+
+/// Coste de memoria (KiB), iteraciones y paralelismo de Argon2id
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// Los mismos que `Argon2::default()`, con los que se cifró todo hasta ahora
+    fn default() -> Self {
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Parámetros con los que se cifran los datos nuevos
+    pub fn configured() -> Self {
+        *CONFIGURED
+    }
+
+    /// Instancia de Argon2id con estos parámetros
+    pub fn argon2(&self) -> Result<Argon2<'static>, String> {
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, None)
+            .map_err(|e| format!("Invalid Argon2 parameters: {}", e))?;
+
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// Lee `m_cost,t_cost,p_cost`; `None` si el formato o los valores no son válidos
+    fn parse(value: &str) -> Option<Self> {
+        let values: Vec<u32> = value.split(',').map(|part| part.trim().parse().ok()).collect::<Option<_>>()?;
+        let [m_cost, t_cost, p_cost] = values[..] else {
+            return None;
+        };
+
+        let params = Self { m_cost, t_cost, p_cost };
+        params.argon2().ok().map(|_| params)
+    }
+
+    /// Cabecera `m=...,t=...,p=...`
+    fn encode(&self) -> String {
+        format!("m={},t={},p={}", self.m_cost, self.t_cost, self.p_cost)
+    }
+
+    /// Lee una cabecera escrita por `encode`
+    fn decode(header: &str) -> Option<Self> {
+        let mut params = Self { m_cost: 0, t_cost: 0, p_cost: 0 };
+
+        for field in header.split(',') {
+            let (name, value) = field.split_once('=')?;
+            let value = value.parse().ok()?;
+            match name {
+                "m" => params.m_cost = value,
+                "t" => params.t_cost = value,
+                "p" => params.p_cost = value,
+                _ => return None,
+            }
+        }
+
+        params.argon2().ok().map(|_| params)
+    }
+}
+
+/// Antepone los parámetros a un texto cifrado (los por defecto no se escriben,
+/// así los datos siguen siendo legibles por versiones anteriores)
+pub fn seal(params: &KdfParams, encrypted: String) -> String {
+    if *params == KdfParams::default() {
+        return encrypted;
+    }
+
+    format!("{}{}${}", PARAMS_PREFIX, params.encode(), encrypted)
+}
+
+/// Separa los parámetros de un texto guardado con `seal`
+///
+/// El base64 no contiene `$`, así que un texto sin cabecera nunca se confunde
+/// con uno que la tiene.
+pub fn open(stored: &str) -> Result<(KdfParams, &str), String> {
+    let Some(rest) = stored.strip_prefix(PARAMS_PREFIX) else {
+        return Ok((KdfParams::default(), stored));
+    };
+
+    let (header, encrypted) = rest.split_once('$').ok_or("Invalid KDF header: missing separator")?;
+    let params = KdfParams::decode(header).ok_or_else(|| format!("Invalid KDF header: '{}'", header))?;
+
+    Ok((params, encrypted))
+}
+
+/// Cifra `plaintext` con la clave que `derive` obtiene para `params` y
+/// guarda los parámetros junto al resultado
+pub fn encrypt_with(
+    params: &KdfParams,
+    plaintext: &str,
+    derive: impl FnOnce(&KdfParams) -> Result<[u8; 32], String>,
+) -> Result<String, String> {
+    let key = Zeroizing::new(derive(params)?);
+    Ok(seal(params, encrypt_data(plaintext, &key)?))
+}
+
+/// Descifra un texto guardado con `encrypt_with`, derivando la clave con los
+/// parámetros que lleva
+pub fn decrypt_with(
+    stored: &str,
+    derive: impl FnOnce(&KdfParams) -> Result<[u8; 32], String>,
+) -> Result<String, String> {
+    let (params, encrypted) = open(stored)?;
+    let key = Zeroizing::new(derive(&params)?);
+    decrypt_data(encrypted, &key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parámetros baratos para que los tests sean rápidos
+    fn fast() -> KdfParams {
+        KdfParams { m_cost: 1024, t_cost: 1, p_cost: 1 }
+    }
+
+    #[test]
+    fn test_default_params_match_argon2_default() {
+        let mut expected = [0u8; 32];
+        Argon2::default().hash_password_into(b"password", b"saltsaltsaltsalt", &mut expected).unwrap();
+
+        let mut key = [0u8; 32];
+        KdfParams::default()
+            .argon2()
+            .unwrap()
+            .hash_password_into(b"password", b"saltsaltsaltsalt", &mut key)
+            .unwrap();
+
+        assert_eq!(key, expected);
+    }
+
+    #[test]
+    fn test_parse_params() {
+        assert_eq!(KdfParams::parse("1024, 1, 1"), Some(fast()));
+        assert_eq!(KdfParams::parse("1024,1"), None);
+        assert_eq!(KdfParams::parse("1024,1,1,1"), None);
+        assert_eq!(KdfParams::parse("a,b,c"), None);
+        // Argon2 exige al menos 8 KiB por hilo
+        assert_eq!(KdfParams::parse("1,1,1"), None);
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        assert_eq!(seal(&KdfParams::default(), "AAAA".to_string()), "AAAA");
+        assert_eq!(open("AAAA").unwrap(), (KdfParams::default(), "AAAA"));
+
+        let sealed = seal(&fast(), "AAAA".to_string());
+        assert_eq!(sealed, "argon2id$m=1024,t=1,p=1$AAAA");
+        assert_eq!(open(&sealed).unwrap(), (fast(), "AAAA"));
+
+        assert!(open("argon2id$m=1024,t=1,p=1").is_err());
+        assert!(open("argon2id$m=1,t=1,p=1$AAAA").is_err());
+        assert!(open("argon2id$x=1$AAAA").is_err());
+    }
+
+    #[test]
+    fn test_custom_params_decrypt_only_with_same_params() {
+        let derive = |params: &KdfParams| -> Result<[u8; 32], String> {
+            let mut key = [0u8; 32];
+            params
+                .argon2()?
+                .hash_password_into(b"alice", b"xmacsaltalice000", &mut key)
+                .map_err(|e| e.to_string())?;
+            Ok(key)
+        };
+
+        let stored = encrypt_with(&fast(), "secreto", derive).unwrap();
+        assert_eq!(decrypt_with(&stored, derive).unwrap(), "secreto");
+
+        // Sin la cabecera (parámetros por defecto) la clave no coincide
+        let (_, encrypted) = open(&stored).unwrap();
+        assert!(decrypt_with(encrypted, derive).is_err());
+
+        // Ni con otros parámetros
+        let other = seal(&KdfParams { t_cost: 2, ..fast() }, encrypted.to_string());
+        assert!(decrypt_with(&other, derive).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Caché en memoria de claves derivadas y nombres de entradas del Keychain
+//
+// Cada derivación Argon2id usa los parámetros por defecto (19 MiB, 2
+// iteraciones). Sin caché, reconstruir el menú encadena varias derivaciones
+// (nombre de la lista, clave maestra, secreto de rotación...) y cambiar de
+// cuenta añade las de sus credenciales. Con la caché caliente solo quedan la
+// lectura del Keychain y el AES-GCM.
+//
+// `measure_cache_speedup` lee la lista y las credenciales de 5 cuentas de un
+// almacén en memoria. En un build release sobre un Xeon x86_64 de 1 núcleo
+// tarda unos 380 ms con la caché vacía y unos 50 µs con la caché caliente
+// (media de 5 rondas). Con el Keychain real hay que sumar su latencia en
+// ambos casos.
+//
+// Las claves se guardan en `Zeroizing` y se sobrescriben con ceros al
+// invalidarlas o al vaciar la caché al salir de la aplicación.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use zeroize::Zeroizing;
+
+use crate::ct_eq;
+use crate::kdf::KdfParams;
+
+/// Identificador de la clave maestra en la caché (no puede ser un handle de X)
+pub const MASTER_KEY_ID: &str = ":master";
+
+/// Clave derivada junto con el secreto y los parámetros con los que se obtuvo
+struct CachedKey {
+    secret: Option<Zeroizing<Vec<u8>>>,
+    params: KdfParams,
+    key: Zeroizing<[u8; 32]>,
+}
+
+/// Nombres hasheados de entradas del Keychain (`hash_key`), por entrada original
+static HASHED_NAMES: Lazy<RwLock<HashMap<String, String>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Claves AES derivadas, por username (o `MASTER_KEY_ID`)
+static DERIVED_KEYS: Lazy<RwLock<HashMap<String, CachedKey>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Retorna el nombre hasheado de `input`, calculándolo solo la primera vez
+pub fn hashed_name(input: &str, compute: impl FnOnce(&str) -> String) -> String {
+    if let Some(name) = HASHED_NAMES.read().unwrap().get(input) {
+        return name.clone();
+    }
+
+    let name = compute(input);
+
+    // No cachear fallos de hash (cadena vacía) para reintentar la próxima vez
+    if !name.is_empty() {
+        HASHED_NAMES.write().unwrap().insert(input.to_string(), name.clone());
+    }
+
+    name
+}
+
+/// Retorna la clave derivada para `id`, calculándola si no está en caché o si
+/// el secreto de derivación o los parámetros han cambiado desde que se guardó
+pub fn derived_key(
+    id: &str,
+    secret: Option<&[u8]>,
+    params: &KdfParams,
+    compute: impl FnOnce() -> Result<[u8; 32], String>,
+) -> Result<[u8; 32], String> {
+    if let Some(cached) = DERIVED_KEYS.read().unwrap().get(id) {
+        // El secreto de derivación se compara en tiempo constante
+        let same_secret = match (cached.secret.as_deref(), secret) {
+            (Some(cached), Some(secret)) => ct_eq(cached, secret),
+            (None, None) => true,
+            _ => false,
+        };
+
+        if same_secret && cached.params == *params {
+            return Ok(*cached.key);
+        }
+    }
+
+    let key = compute()?;
+
+    DERIVED_KEYS.write().unwrap().insert(
+        id.to_string(),
+        CachedKey {
+            secret: secret.map(|s| Zeroizing::new(s.to_vec())),
+            params: *params,
+            key: Zeroizing::new(key),
+        },
+    );
+
+    Ok(key)
+}
+
+/// Elimina de la caché la clave derivada y el nombre de credenciales de una cuenta
+pub fn invalidate(username: &str) {
+    DERIVED_KEYS.write().unwrap().remove(username);
+    HASHED_NAMES.write().unwrap().remove(&format!("credentials_{}", username));
+}
+
+/// Elimina todas las claves derivadas (p. ej. tras rotar el secreto)
+pub fn invalidate_keys() {
+    DERIVED_KEYS.write().unwrap().clear();
+}
+
+/// Vacía la caché por completo; las claves se sobrescriben con ceros al soltarse
+pub fn clear() {
+    invalidate_keys();
+    HASHED_NAMES.write().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_derived_key_is_computed_once() {
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Ok([7u8; 32])
+        };
+
+        let params = KdfParams::default();
+        assert_eq!(derived_key("cache_test_once", None, &params, compute).unwrap(), [7u8; 32]);
+        assert_eq!(derived_key("cache_test_once", None, &params, compute).unwrap(), [7u8; 32]);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_derived_key_recomputed_when_secret_changes() {
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Ok([calls.get() as u8; 32])
+        };
+
+        let params = KdfParams::default();
+        derived_key("cache_test_secret", None, &params, compute).unwrap();
+        let key = derived_key("cache_test_secret", Some(b"new"), &params, compute).unwrap();
+
+        assert_eq!(calls.get(), 2);
+        assert_eq!(key, [2u8; 32]);
+    }
+
+    #[test]
+    fn test_derived_key_recomputed_when_params_change() {
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Ok([calls.get() as u8; 32])
+        };
+
+        let custom = KdfParams { m_cost: 1024, t_cost: 1, p_cost: 1 };
+        derived_key("cache_test_params", None, &KdfParams::default(), compute).unwrap();
+        let key = derived_key("cache_test_params", None, &custom, compute).unwrap();
+
+        assert_eq!(calls.get(), 2);
+        assert_eq!(key, [2u8; 32]);
+    }
+
+    #[test]
+    fn test_invalidate_forces_recompute() {
+        let calls = Cell::new(0);
+        let compute = |input: &str| {
+            calls.set(calls.get() + 1);
+            format!("hashed-{}", input)
+        };
+
+        hashed_name("credentials_cache_test_user", compute);
+        invalidate("cache_test_user");
+        hashed_name("credentials_cache_test_user", compute);
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_failed_hash_is_not_cached() {
+        let calls = Cell::new(0);
+        let compute = |_: &str| {
+            calls.set(calls.get() + 1);
+            String::new()
+        };
+
+        hashed_name("cache_test_failure", compute);
+        hashed_name("cache_test_failure", compute);
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    /// Mide leer la lista y las credenciales de 5 cuentas con la caché vacía
+    /// y con la caché caliente (las cifras del principio del archivo)
+    ///
+    /// Vacía la caché global, así que se ejecuta aparte:
+    /// `cargo test --release --lib keycache -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn measure_cache_speedup() {
+        use crate::accounts::{load_accounts_list, load_credentials, AccountsManager};
+        use crate::secrets::InMemoryStore;
+        use std::time::{Duration, Instant};
+
+        let store: &'static InMemoryStore = Box::leak(Box::new(InMemoryStore::new()));
+        let manager = AccountsManager::new(store);
+        let usernames = ["alice", "bob", "carol", "dave", "erin"];
+        for username in usernames {
+            manager.add_account(username, Some(format!("token-{}", username)), None).unwrap();
+        }
+
+        let load_all = || {
+            let start = Instant::now();
+            load_accounts_list(store).unwrap();
+            for username in usernames {
+                assert!(load_credentials(store, username).unwrap().is_some());
+            }
+            start.elapsed()
+        };
+
+        const RUNS: u32 = 5;
+        let (mut cold, mut warm) = (Duration::ZERO, Duration::ZERO);
+        for _ in 0..RUNS {
+            clear();
+            cold += load_all();
+            warm += load_all();
+        }
+
+        println!("cold: {:?}, warm: {:?}", cold / RUNS, warm / RUNS);
+        assert!(warm < cold);
+    }
+}
@@ -16,11 +16,45 @@ use argon2::{password_hash::SaltString, Argon2, PasswordHasher};
 use base64::Engine;
 use rand::{rngs::OsRng, Rng};
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
 use tauri::{Emitter, Manager};
-use tracing_subscriber::EnvFilter;
+use tauri_plugin_deep_link::DeepLinkExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 mod menu;
+mod account_detect;
 mod accounts;
+mod app_info;
+mod appearance;
+mod backup;
+mod cleanup;
+mod deep_link;
+mod diagnostics;
+mod dialog;
+mod error;
+mod i18n;
+mod kdf;
+mod keycache;
+mod links;
+mod logging;
+mod profile;
+mod rotation;
+mod secrets;
+mod session;
+mod session_check;
+mod settings;
+mod shortcut;
+mod update_cache;
+mod update_download;
+mod updates;
+mod window;
+mod window_state;
+mod zoom;
+
+use accounts::AccountsManager;
+use error::AppError;
+use kdf::KdfParams;
 
 /// Tamaño de la clave AES-256 (32 bytes)
 const KEY_SIZE: usize = 32;
@@ -34,34 +68,6 @@ struct Credentials {
     session_data: Option<String>,
 }
 
-#[cfg(target_os = "macos")]
-fn get_keychain_item(key: &str) -> Option<String> {
-    use security_framework::passwords::get_generic_password;
-
-    match get_generic_password("com.twitter.xmac", key) {
-        Ok(data) => String::from_utf8(data).ok(),
-        Err(_) => None,
-    }
-}
-
-#[cfg(target_os = "macos")]
-fn set_keychain_item(key: &str, value: &str) -> Result<(), String> {
-    use security_framework::passwords::{delete_generic_password, set_generic_password};
-
-    let _ = delete_generic_password("com.twitter.xmac", key);
-    set_generic_password("com.twitter.xmac", key, value.as_bytes()).map_err(|e| e.to_string())
-}
-
-#[cfg(not(target_os = "macos"))]
-fn get_keychain_item(_key: &str) -> Option<String> {
-    None
-}
-
-#[cfg(not(target_os = "macos"))]
-fn set_keychain_item(_key: &str, _value: &str) -> Result<(), String> {
-    Err("Not supported on this platform".to_string())
-}
-
 /// Deriva una clave segura usando Argon2id para almacenamiento en Keychain.
 ///
 /// # Arguments
@@ -71,9 +77,23 @@ fn set_keychain_item(_key: &str, _value: &str) -> Result<(), String> {
 /// Hash Argon2id en formato PHC string
 ///
 /// # Security
-/// Usa Argon2id con parámetros por defecto (resistente a ataques de hardware)
+/// Usa Argon2id con parámetros por defecto (resistente a ataques de hardware).
+/// El salt es fijo para que el mismo `key` produzca siempre el mismo nombre de
+/// entrada y pueda volver a encontrarse en el Keychain. El resultado se cachea
+/// en memoria (ver `keycache`).
 pub fn hash_key(key: &str) -> String {
-    let salt = SaltString::generate(&mut OsRng);
+    keycache::hashed_name(key, compute_hash_key)
+}
+
+/// Calcula el hash Argon2id de `key` (sin caché)
+fn compute_hash_key(key: &str) -> String {
+    let salt = match SaltString::encode_b64(b"com.twitter.xmac") {
+        Ok(salt) => salt,
+        Err(e) => {
+            tracing::error!("Error hashing key: {}", e);
+            return String::new();
+        }
+    };
     let argon2 = Argon2::default();
 
     argon2
@@ -89,6 +109,7 @@ pub fn hash_key(key: &str) -> String {
 ///
 /// # Arguments
 /// * `username` - Username del usuario
+/// * `params` - Parámetros de Argon2id (los mismos con los que se cifró)
 ///
 /// # Returns
 /// Array de 32 bytes para AES-256-GCM
@@ -96,8 +117,8 @@ pub fn hash_key(key: &str) -> String {
 /// # Security
 /// Cada usuario tiene su propia clave de encriptación derivada de su username.
 /// Argon2id protege contra ataques de fuerza bruta.
-fn derive_encryption_key(username: &str) -> Result<[u8; KEY_SIZE], String> {
-    let argon2 = Argon2::default();
+fn derive_encryption_key(username: &str, params: &KdfParams) -> Result<[u8; KEY_SIZE], String> {
+    let argon2 = params.argon2()?;
     let mut key = [0u8; KEY_SIZE];
 
     // Salt fijo derivado del username para consistencia (exactamente 16 bytes)
@@ -184,7 +205,27 @@ pub fn decrypt_data(encrypted_b64: &str, key: &[u8; 32]) -> Result<String, Strin
         .decrypt(nonce, ciphertext)
         .map_err(|e| format!("Decryption error (data corrupted or wrong key): {}", e))?;
 
-    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8 in decrypted data: {}", e))
+    plaintext_to_string(plaintext)
+}
+
+/// Convierte el texto descifrado en `String`; si no es UTF-8 válido, los bytes
+/// se sobrescriben con ceros antes de descartarlos.
+fn plaintext_to_string(plaintext: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(plaintext).map_err(|e| {
+        let message = format!("Invalid UTF-8 in decrypted data: {}", e.utf8_error());
+        e.into_bytes().zeroize();
+        message
+    })
+}
+
+/// Compara dos valores sensibles (tokens, claves, secretos) en tiempo constante.
+///
+/// # Security
+/// `==` termina en el primer byte distinto y el tiempo que tarda revela cuántos
+/// bytes coinciden. La longitud sí puede filtrarse: solo el contenido se
+/// compara en tiempo constante.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
 }
 
 /// Encripta credenciales JSON usando AES-256-GCM.
@@ -192,6 +233,7 @@ pub fn decrypt_data(encrypted_b64: &str, key: &[u8; 32]) -> Result<String, Strin
 /// # Arguments
 /// * `plaintext` - JSON de credenciales en texto plano
 /// * `username` - Username para derivar clave única
+/// * `params` - Parámetros de Argon2id para derivar la clave
 ///
 /// # Returns
 /// Vec<u8> en formato: nonce || ciphertext || tag
@@ -201,9 +243,13 @@ pub fn decrypt_data(encrypted_b64: &str, key: &[u8; 32]) -> Result<String, Strin
 /// - AES-256-GCM provee autenticación y confidencialidad
 /// - Nonce aleatorio por cada encriptación (never reuse)
 /// - Tag de autenticación detecta manipulación
-fn encrypt_credentials(plaintext: &str, username: &str) -> Result<Vec<u8>, String> {
-    let key_bytes = derive_encryption_key(username)?;
-    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
+///
+/// Es el formato de la entrada única de v0.3.0 (ver `decrypt_credentials`);
+/// la app ya no lo escribe, solo los tests.
+#[cfg(test)]
+fn encrypt_credentials(plaintext: &str, username: &str, params: &KdfParams) -> Result<Vec<u8>, String> {
+    let key_bytes = Zeroizing::new(derive_encryption_key(username, params)?);
+    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(key_bytes.as_slice());
     let cipher = Aes256Gcm::new(key);
 
     // Generar nonce aleatorio (CRITICAL: nunca reusar)
@@ -228,6 +274,7 @@ fn encrypt_credentials(plaintext: &str, username: &str) -> Result<Vec<u8>, Strin
 /// # Arguments
 /// * `encrypted_data` - Bytes en formato: nonce || ciphertext || tag
 /// * `username` - Username para derivar la misma clave
+/// * `params` - Parámetros de Argon2id con los que se cifraron
 ///
 /// # Returns
 /// JSON de credenciales en texto plano
@@ -237,13 +284,13 @@ fn encrypt_credentials(plaintext: &str, username: &str) -> Result<Vec<u8>, Strin
 /// - Los datos están corruptos (formato inválido)
 /// - La clave es incorrecta
 /// - Los datos fueron manipulados (falla verificación de tag)
-fn decrypt_credentials(encrypted_data: &[u8], username: &str) -> Result<String, String> {
+fn decrypt_credentials(encrypted_data: &[u8], username: &str, params: &KdfParams) -> Result<String, String> {
     if encrypted_data.len() < NONCE_SIZE {
         return Err("Invalid encrypted data: too short".to_string());
     }
 
-    let key_bytes = derive_encryption_key(username)?;
-    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
+    let key_bytes = Zeroizing::new(derive_encryption_key(username, params)?);
+    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(key_bytes.as_slice());
     let cipher = Aes256Gcm::new(key);
 
     // Extraer nonce (primeros 12 bytes)
@@ -255,7 +302,7 @@ fn decrypt_credentials(encrypted_data: &[u8], username: &str) -> Result<String,
         .decrypt(nonce, ciphertext)
         .map_err(|e| format!("Decryption error (data corrupted or wrong key): {}", e))?;
 
-    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8 in decrypted data: {}", e))
+    plaintext_to_string(plaintext)
 }
 
 /// Guarda credenciales encriptadas en el Keychain de macOS.
@@ -269,37 +316,33 @@ fn decrypt_credentials(encrypted_data: &[u8], username: &str) -> Result<String,
 /// `Ok(())` si se guardó correctamente
 ///
 /// # Security
-/// Las credenciales se encriptan con AES-256-GCM antes de almacenarse.
-/// La clave se deriva del username usando Argon2id.
+/// Las credenciales se guardan igual que las de las cuentas
+/// (`accounts::store_credentials`): AES-256-GCM con la clave derivada del
+/// username y del secreto de rotación, repartidas en varias entradas si son
+/// grandes. Si ya había credenciales se conservan su UUID y su fecha de
+/// creación.
 #[tauri::command]
 async fn save_credentials(
     username: &str,
     token: Option<String>,
     session_data: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     tracing::info!("Saving credentials for user: {}", username);
 
-    let creds = Credentials {
+    let store = secrets::store();
+    // Unas credenciales que no se pueden descifrar se sustituyen sin más
+    let existing = accounts::load_credentials(store, username).ok().flatten();
+    let now = accounts::unix_now();
+
+    let creds = accounts::Credentials {
         username: username.to_string(),
+        uuid: existing.as_ref().map_or_else(|| uuid::Uuid::new_v4().to_string(), |creds| creds.uuid.clone()),
         token,
         session_data,
+        created_at: existing.as_ref().map_or(now, |creds| creds.created_at),
+        last_used: now,
     };
-
-    // Serializar a JSON
-    let creds_json =
-        serde_json::to_string(&creds).map_err(|e| format!("JSON serialization error: {}", e))?;
-
-    // Encriptar credenciales
-    let encrypted = encrypt_credentials(&creds_json, username)?;
-
-    // Convertir a base64 para almacenar como string en Keychain
-    let encrypted_b64 = base64::engine::general_purpose::STANDARD.encode(&encrypted);
-
-    // Hash del username para la key del Keychain
-    let hashed_key = hash_key(&format!("credentials_{}", username));
-
-    // Guardar en Keychain
-    set_keychain_item(&hashed_key, &encrypted_b64)?;
+    accounts::store_credentials(store, &creds)?;
 
     tracing::info!("Credentials saved successfully for user: {}", username);
     Ok(())
@@ -315,24 +358,20 @@ async fn save_credentials(
 ///
 /// # Security
 /// Verifica la integridad de los datos mediante el tag de autenticación AES-GCM.
+/// Lee las credenciales como el resto de la app (`accounts::load_credentials`),
+/// así que sirve también para las cuentas rotadas o repartidas en varias
+/// entradas.
 #[tauri::command]
-async fn get_credentials(username: &str) -> Result<Option<String>, String> {
+async fn get_credentials(username: &str) -> Result<Option<String>, AppError> {
     tracing::info!("Retrieving credentials for user: {}", username);
 
-    let hashed_key = hash_key(&format!("credentials_{}", username));
-
-    match get_keychain_item(&hashed_key) {
-        Some(encrypted_b64) => {
-            // Decodificar de base64
-            let encrypted = base64::engine::general_purpose::STANDARD
-                .decode(&encrypted_b64)
-                .map_err(|e| format!("Base64 decode error: {}", e))?;
-
-            // Desencriptar
-            let plaintext = decrypt_credentials(&encrypted, username)?;
+    match accounts::load_credentials(secrets::store(), username)? {
+        Some(creds) => {
+            let json = serde_json::to_string(&creds)
+                .map_err(|e| AppError::Serialization(format!("JSON serialization error: {}", e)))?;
 
             tracing::info!("Credentials retrieved successfully for user: {}", username);
-            Ok(Some(plaintext))
+            Ok(Some(json))
         }
         None => {
             tracing::info!("No credentials found for user: {}", username);
@@ -345,17 +384,21 @@ async fn get_credentials(username: &str) -> Result<Option<String>, String> {
 ///
 /// # Arguments
 /// * `username` - Username del usuario
+/// * `confirm` - Debe ser `true`; sin confirmación no se elimina nada
 ///
 /// # Security
 /// Esta operación es irreversible.
 #[tauri::command]
-async fn delete_credentials(username: &str) -> Result<(), String> {
-    tracing::info!("Deleting credentials for user: {}", username);
+async fn delete_credentials(username: &str, confirm: Option<bool>) -> Result<(), AppError> {
+    if confirm != Some(true) {
+        return Err(AppError::ConfirmationRequired);
+    }
 
-    use security_framework::passwords::delete_generic_password;
+    tracing::info!("Deleting credentials for user: {}", username);
 
-    let hashed_key = hash_key(&format!("credentials_{}", username));
-    let _ = delete_generic_password("com.twitter.xmac", &hashed_key);
+    // Unas credenciales grandes se guardan repartidas en varias entradas
+    accounts::delete_credentials(secrets::store(), username);
+    keycache::invalidate(username);
 
     tracing::info!("Credentials deleted for user: {}", username);
     Ok(())
@@ -365,50 +408,798 @@ async fn delete_credentials(username: &str) -> Result<(), String> {
 // Comandos de Multi-cuenta
 // =============================================================================
 
-/// Lista todas las cuentas disponibles
+/// Lista las cuentas disponibles (las archivadas solo con `include_archived`)
 #[tauri::command]
-async fn list_accounts() -> Result<Vec<accounts::AccountInfo>, String> {
-    accounts::list_accounts()
+async fn list_accounts(
+    manager: tauri::State<'_, AccountsManager>,
+    include_archived: Option<bool>,
+) -> Result<Vec<accounts::AccountInfo>, AppError> {
+    manager.list_accounts(include_archived.unwrap_or(false))
 }
 
 /// Obtiene la cuenta activa actual
 #[tauri::command]
-async fn get_active_account() -> Result<Option<String>, String> {
-    accounts::get_active_account()
+async fn get_active_account(manager: tauri::State<'_, AccountsManager>) -> Result<Option<String>, AppError> {
+    manager.get_active_account()
 }
 
 /// Establece la cuenta activa
 #[tauri::command]
-async fn set_active_account(username: String) -> Result<(), String> {
-    accounts::set_active_account(&username)
+async fn set_active_account(app: tauri::AppHandle, username: String) -> Result<(), AppError> {
+    activate_account(&app, &username)
 }
 
 /// Guarda credenciales de una cuenta (usado por login-detector.js)
+///
+/// La sesión se toma de las cookies del WebView (incluye `auth_token`, que es
+/// HttpOnly y no es accesible desde JS); `session_data` solo se usa si no se
+/// pueden leer.
 #[tauri::command]
 async fn save_account_credentials(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
     username: String,
     token: Option<String>,
     session_data: Option<String>,
-) -> Result<String, String> {
-    accounts::add_account(&username, token, session_data)
+) -> Result<String, AppError> {
+    let captured = app
+        .get_webview_window(window::MAIN_WINDOW)
+        .and_then(|window| session::capture_session(&window).ok())
+        .filter(|session| !session.cookies.is_empty())
+        .and_then(|session| serde_json::to_string(&session).ok());
+
+    let uuid = manager.add_account(&username, token, captured.or(session_data))?;
+
+    // Nombre visible y avatar desde X; un fallo de red no invalida el alta
+    profile::spawn_refresh(&app, manager.resolve_username(&username)?);
+
+    Ok(uuid)
+}
+
+/// Da de alta la cuenta con la que se acaba de iniciar sesión en el WebView
+/// y la deja activa
+///
+/// Como `save_account_credentials`, prefiere las cookies del WebView a
+/// `session_data`. Si la cuenta ya existe solo renueva sus credenciales. El
+/// alta y el cambio de cuenta activa se guardan juntos; después se
+/// reconstruye el menú y se emite `account-changed`. No se intercambian las
+/// cookies del WebView: ya son las de esta cuenta.
+#[tauri::command]
+async fn on_login_detected(
+    app: tauri::AppHandle,
+    username: String,
+    session_data: String,
+) -> Result<accounts::LoginDetected, AppError> {
+    let manager = app.state::<AccountsManager>();
+    let captured = app
+        .get_webview_window(window::MAIN_WINDOW)
+        .and_then(|window| session::capture_session(&window).ok())
+        .filter(|session| !session.cookies.is_empty())
+        .and_then(|session| serde_json::to_string(&session).ok());
+
+    let result = manager.login_detected(&username, captured.unwrap_or(session_data))?;
+
+    if let Err(e) = menu::builder::rebuild_menu(&app) {
+        tracing::error!("Failed to rebuild menu: {}", e);
+    }
+    if let Err(e) = app.emit(accounts::ACCOUNT_CHANGED_EVENT, &result.account) {
+        tracing::error!("Failed to emit {}: {}", accounts::ACCOUNT_CHANGED_EVENT, e);
+    }
+
+    // Nombre visible y avatar desde X; un fallo de red no invalida el alta
+    profile::spawn_refresh(&app, result.account.username.clone());
+
+    Ok(result)
+}
+
+/// Guarda las cookies de x.com del WebView en las credenciales de la cuenta
+///
+/// Usa la cuenta activa o, si no hay ninguna, `username` (la cuenta se crea si
+/// no existe). Actualiza `last_used` y emite `accounts-changed`.
+///
+/// # Returns
+/// Username de la cuenta actualizada
+#[tauri::command]
+async fn capture_session(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    username: Option<String>,
+) -> Result<String, AppError> {
+    let username = match manager.get_active_account()? {
+        Some(active) => active,
+        None => manager.resolve_username(
+            &username.ok_or("Cannot resolve the account for the current session")?,
+        )?,
+    };
+
+    let window = window::focused_account_window(&app).ok_or("Main window not found")?;
+    let session_data = session::capture_session_data(&window)?;
+
+    manager.save_session(&username, session_data)?;
+    profile::spawn_refresh(&app, username.clone());
+    tracing::info!("Stored webview session for {}", username);
+
+    Ok(username)
+}
+
+/// Restaura en el WebView las cookies guardadas de una cuenta y la activa
+///
+/// Como `switch_account`, pero vuelve a cargar las cookies aunque la cuenta
+/// ya fuera la activa.
+#[tauri::command]
+async fn restore_session(app: tauri::AppHandle, username: String) -> Result<(), AppError> {
+    switch_account_session(&app, &username, true)
 }
 
 /// Elimina una cuenta
+///
+/// Solo se ejecuta con `confirm: true` (el frontend ya ha pedido confirmación).
+/// El menú no pasa por aquí: confirma con un aviso nativo y llama a
+/// `remove_account_and_web_data`.
+#[tauri::command]
+async fn delete_account(
+    app: tauri::AppHandle,
+    username: String,
+    confirm: Option<bool>,
+    keep_web_data: Option<bool>,
+) -> Result<accounts::AccountRemoval, AppError> {
+    if !confirm.unwrap_or(false) {
+        return Err(AppError::ConfirmationRequired);
+    }
+
+    remove_account_and_web_data(&app, &username, keep_web_data.unwrap_or(false))
+}
+
+/// Elimina una cuenta ya confirmada por el usuario
+///
+/// Si la cuenta eliminada es la cargada, borra también las cookies y los datos
+/// web de x.com (salvo `keep_web_data`) y restaura la sesión de la nueva
+/// cuenta activa, si la hay. Los datos web se borran antes que las credenciales.
+pub(crate) fn remove_account_and_web_data<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    username: &str,
+    keep_web_data: bool,
+) -> Result<accounts::AccountRemoval, AppError> {
+    let manager = app.state::<AccountsManager>();
+    let username = &manager.resolve_username(username)?;
+    let loaded = manager.get_active_account()?;
+    let uuid = manager
+        .list_accounts(true)?
+        .into_iter()
+        .find(|a| &a.username == username)
+        .map(|a| a.uuid);
+
+    let was_active = loaded.as_deref() == Some(username.as_str());
+    let clear = session::should_clear_web_data(username, loaded.as_deref(), keep_web_data);
+    let window = app.get_webview_window(window::MAIN_WINDOW).filter(|_| clear);
+
+    let web_data_cleared = manager.remove_account_with_web_data(username, true, || {
+        window.as_ref().is_some_and(|window| clear_webview_session(username, || session::clear_web_data(window)))
+    })?;
+
+    if let Some(uuid) = uuid {
+        profile::remove_avatar(app, &uuid);
+    }
+
+    let Some(window) = window else {
+        return Ok(accounts::AccountRemoval { web_data_cleared, was_active });
+    };
+
+    match manager.get_active_account()? {
+        Some(next) => {
+            if let Err(e) = app.emit(accounts::ACCOUNT_CHANGED_EVENT, manager.set_active_account(&next)?) {
+                tracing::error!("Failed to emit {}: {}", accounts::ACCOUNT_CHANGED_EVENT, e);
+            }
+            session::restore_session(&window, manager.store(), &next)?;
+        }
+        None => session::open_login(&window)?,
+    }
+
+    Ok(accounts::AccountRemoval { web_data_cleared, was_active })
+}
+
+/// Cierra la sesión de una cuenta sin eliminarla
+///
+/// Si es la cuenta cargada, borra las cookies y el localStorage de x.com y
+/// abre el login; después elimina sus credenciales del Keychain. Retorna si
+/// se han borrado datos del WebView.
+#[tauri::command]
+async fn clear_session_data(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    username: String,
+) -> Result<bool, AppError> {
+    let username = manager.resolve_username(&username)?;
+    let loaded = manager.get_active_account()?;
+    let window = app
+        .get_webview_window(window::MAIN_WINDOW)
+        .filter(|_| loaded.as_deref() == Some(username.as_str()));
+
+    let cleared = manager.clear_session_data(&username, || {
+        window.as_ref().is_some_and(|window| clear_webview_session(&username, || session::clear_session_data(window)))
+    })?;
+
+    if let Some(window) = &window {
+        session::open_login(window)?;
+    }
+
+    Ok(cleared)
+}
+
+/// Cierra la sesión de todas las cuentas y las elimina
+///
+/// Solo se ejecuta con `confirm: true`. Borra las cookies y los datos web de
+/// x.com (compartidos por todas las ventanas de cuenta), elimina las cuentas
+/// con sus credenciales y avatares, cierra las ventanas de cuenta y deja la
+/// ventana principal en el login. Las preferencias se conservan.
+#[tauri::command]
+async fn logout_all_accounts(app: tauri::AppHandle, confirm: Option<bool>) -> Result<accounts::LogoutAll, AppError> {
+    let manager = app.state::<AccountsManager>();
+    let main_window = app.get_webview_window(window::MAIN_WINDOW);
+
+    let (removed, web_data_cleared) = manager.logout_all_accounts(confirm.unwrap_or(false), || {
+        main_window
+            .as_ref()
+            .is_some_and(|window| clear_webview_session("all accounts", || session::clear_web_data(window)))
+    })?;
+
+    for account in &removed {
+        profile::remove_avatar(&app, &account.uuid);
+    }
+
+    for (label, account_window) in app.webview_windows() {
+        if label.starts_with(window::ACCOUNT_WINDOW_PREFIX) {
+            if let Err(e) = account_window.close() {
+                tracing::warn!("Failed to close {}: {}", label, e);
+            }
+        }
+    }
+
+    if let Err(e) = menu::builder::rebuild_menu(&app) {
+        tracing::error!("Failed to rebuild menu: {}", e);
+    }
+    if let Some(window) = &main_window {
+        session::open_login(window)?;
+    }
+
+    Ok(accounts::LogoutAll {
+        removed: removed.len(),
+        web_data_cleared,
+    })
+}
+
+/// Handle del perfil abierto en la ventana principal, si lo hay
+///
+/// Lo llama `login-detector.js` al cambiar de ruta (X no recarga la página);
+/// las cargas completas se comprueban en `on_page_load`. Si el handle no está
+/// en la lista de cuentas emite `new-account-detected`.
 #[tauri::command]
-async fn delete_account(username: String) -> Result<(), String> {
-    accounts::remove_account(&username)
+async fn detect_logged_in_user(app: tauri::AppHandle) -> Result<Option<String>, AppError> {
+    Ok(account_detect::detect_logged_in_user(&app)?)
+}
+
+/// Ejecuta `clear` registrando el resultado; un fallo no interrumpe el
+/// borrado de las credenciales
+fn clear_webview_session(username: &str, clear: impl FnOnce() -> Result<(), String>) -> bool {
+    match clear() {
+        Ok(()) => {
+            tracing::info!("Cleared web data of {}", username);
+            true
+        }
+        Err(e) => {
+            tracing::warn!("Failed to clear web data of {}: {}", username, e);
+            false
+        }
+    }
 }
 
 /// Cambia a otra cuenta
 #[tauri::command]
-async fn switch_account(username: String) -> Result<(), String> {
-    accounts::set_active_account(&username)
+async fn switch_account(app: tauri::AppHandle, username: String) -> Result<(), AppError> {
+    activate_account(&app, &username)
+}
+
+/// Cambia la cuenta activa y emite `account-changed` con la cuenta nueva
+///
+/// Además intercambia las cookies de x.com del WebView: guarda las de la cuenta
+/// saliente y restaura las de la entrante (o abre el login si han expirado).
+pub(crate) fn activate_account<R: tauri::Runtime>(app: &tauri::AppHandle<R>, username: &str) -> Result<(), AppError> {
+    switch_account_session(app, username, false)
+}
+
+/// Activa `username` e intercambia las cookies de la ventana principal
+///
+/// Con `reload_active` restaura las cookies guardadas aunque `username` ya
+/// fuera la cuenta activa; si no, en ese caso solo emite el evento.
+fn switch_account_session<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    username: &str,
+    reload_active: bool,
+) -> Result<(), AppError> {
+    let manager = app.state::<AccountsManager>();
+    let username = &manager.resolve_username(username)?;
+    let previous = manager.get_active_account()?;
+    let payload = manager.set_active_account(username)?;
+
+    if let Err(e) = app.emit(accounts::ACCOUNT_CHANGED_EVENT, payload) {
+        tracing::error!("Failed to emit {}: {}", accounts::ACCOUNT_CHANGED_EVENT, e);
+    }
+
+    if !reload_active && previous.as_deref() == Some(username.as_str()) {
+        return Ok(());
+    }
+
+    if let Some(window) = app.get_webview_window(window::MAIN_WINDOW) {
+        session::switch_session(&window, manager.store(), previous.as_deref(), username)?;
+    }
+
+    Ok(())
+}
+
+/// Abre la cuenta en una ventana adicional (o enfoca la existente)
+///
+/// Las ventanas comparten cookies, así que solo se admite la cuenta activa.
+///
+/// # Returns
+/// Label de la ventana
+#[tauri::command]
+async fn open_account_window(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    username: String,
+) -> Result<String, AppError> {
+    let username = manager.resolve_username(&username)?;
+    if manager.get_active_account()?.as_deref() != Some(username.as_str()) {
+        return Err(AppError::Internal(format!("Account '{}' is not the active account", username)));
+    }
+
+    Ok(window::open_account_window(&app, &username)?)
+}
+
+/// Abre el gestor de cuentas (o enfoca el existente)
+///
+/// # Returns
+/// Label de la ventana
+#[tauri::command]
+async fn open_accounts_manager(app: tauri::AppHandle) -> Result<String, AppError> {
+    Ok(window::open_accounts_manager(&app)?)
+}
+
+/// Abre la ventana de incógnito (o enfoca la existente)
+///
+/// # Returns
+/// Label de la ventana
+#[tauri::command]
+async fn open_incognito_window(app: tauri::AppHandle) -> Result<String, AppError> {
+    Ok(window::open_incognito_window(&app)?)
+}
+
+/// Abre el perfil de X de `handle` en la ventana con foco
+///
+/// Acepta el handle con o sin "@"; se rechaza si no tiene el formato de X
+/// (1-15 letras, dígitos o guiones bajos).
+#[tauri::command]
+async fn open_profile(app: tauri::AppHandle, handle: String) -> Result<(), AppError> {
+    let handle = accounts::validate_username(&handle)?;
+    let url = menu::nav_config::resolve_profile(&app, &handle);
+
+    menu::navigation::open_in_app(&app, &url)?;
+
+    tracing::info!("Opened profile @{}", handle);
+    Ok(())
+}
+
+/// Abre el editor de X en la ventana con foco con `text` ya escrito
+///
+/// Para integraciones de "compartir en X"; el texto se rechaza si supera
+/// `MAX_COMPOSE_CHARS` caracteres.
+#[tauri::command]
+async fn compose_tweet(app: tauri::AppHandle, text: String) -> Result<(), AppError> {
+    let url = menu::navigation::compose_url(&text)?;
+    menu::navigation::open_in_app(&app, url.as_str())
+}
+
+/// Busca en la página de X de la ventana con foco
+///
+/// Lo llama la barra de búsqueda (Cmd+F) con el texto escrito; sin `query`
+/// repite la última búsqueda. El contador de coincidencias se actualiza en la
+/// propia barra.
+#[tauri::command]
+async fn find_in_page(
+    app: tauri::AppHandle,
+    query: Option<String>,
+    direction: menu::find::FindDirection,
+) -> Result<(), AppError> {
+    menu::find::find_in_page(&app, query.as_deref(), direction)
+}
+
+/// Guarda en `path` la página de la ventana que lo llama como PDF
+///
+/// `path` tiene que ser absoluta y acabar en `.pdf`. Es lo mismo que
+/// Archivo > Exportar como PDF… sin el diálogo de guardado.
+///
+/// # Returns
+/// Ruta del fichero escrito
+#[tauri::command]
+async fn export_page_pdf(window: tauri::WebviewWindow, path: String) -> Result<String, AppError> {
+    let path = menu::print::export_page_pdf(&window, std::path::Path::new(&path)).await?;
+    Ok(path.display().to_string())
+}
+
+/// Muestra en el Dock las notificaciones pendientes de X
+///
+/// Lo llama login-detector.js cuando cambia el contador de la pestaña
+/// Notificaciones; con 0 se quita el número. Solo cuenta la ventana
+/// principal: el Dock es de la cuenta activa, no de las ventanas adicionales.
+#[tauri::command]
+async fn set_badge_count(app: tauri::AppHandle, window: tauri::WebviewWindow, count: u32) -> Result<(), AppError> {
+    if window.label() == window::MAIN_WINDOW {
+        window::set_badge_count(&app, count);
+    }
+    Ok(())
+}
+
+/// Abre una URL de X en la ventana con foco
+///
+/// Solo se aceptan URLs `https` de x.com, twitter.com o pro.x.com (o de la
+/// instancia configurada en `navigation.json`).
+#[tauri::command]
+async fn open_in_app(app: tauri::AppHandle, url: String) -> Result<(), AppError> {
+    menu::navigation::open_in_app(&app, &url)
+}
+
+/// Guarda el nombre visible y el avatar de una cuenta
+///
+/// login-detector.js envía los valores que extrae de la página; si no se pasa
+/// ninguno, se obtienen del perfil de X con la sesión guardada.
+#[tauri::command]
+async fn update_account_profile(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    username: String,
+    display_name: Option<String>,
+    avatar_url: Option<String>,
+) -> Result<(), AppError> {
+    if display_name.is_none() && avatar_url.is_none() {
+        profile::refresh_profile(&app, &username).await?;
+        return Ok(());
+    }
+
+    manager.update_account_profile(&username, display_name, avatar_url)?;
+    window::update_window_identity(&app);
+    Ok(())
+}
+
+/// Comprueba ahora (sin esperar a la comprobación periódica) si la sesión
+/// guardada de una cuenta sigue siendo válida
+///
+/// Retorna `None` si X no ha dado una respuesta concluyente.
+#[tauri::command]
+async fn validate_session(app: tauri::AppHandle, username: String) -> Result<Option<bool>, AppError> {
+    Ok(session_check::validate_session(&app, &username).await?)
+}
+
+/// Días transcurridos desde que se guardaron las credenciales de la cuenta
+#[tauri::command]
+async fn get_account_session_age(manager: tauri::State<'_, AccountsManager>, username: String) -> Result<i64, AppError> {
+    manager.session_age_days(&username)
+}
+
+/// Datos de una cuenta con un resumen de sus credenciales (sin secretos)
+#[tauri::command]
+async fn get_account_details(
+    manager: tauri::State<'_, AccountsManager>,
+    username: String,
+) -> Result<accounts::AccountDetails, AppError> {
+    manager.account_details(&username)
+}
+
+/// Cuentas sin usar en más de `threshold_days` días (30 por defecto), cuya
+/// sesión probablemente haya caducado
+#[tauri::command]
+async fn get_stale_accounts(
+    manager: tauri::State<'_, AccountsManager>,
+    threshold_days: Option<i64>,
+) -> Result<Vec<String>, AppError> {
+    manager.stale_accounts(threshold_days.unwrap_or(accounts::STALE_SESSION_DAYS))
+}
+
+/// Renombra una cuenta (p. ej. tras cambiar el handle en X)
+#[tauri::command]
+async fn rename_account(
+    manager: tauri::State<'_, AccountsManager>,
+    old_username: String,
+    new_username: String,
+) -> Result<(), AppError> {
+    manager.rename_account(&old_username, &new_username)
+}
+
+/// Reordena las cuentas del menú Cuentas según la lista de usernames
+#[tauri::command]
+async fn reorder_accounts(manager: tauri::State<'_, AccountsManager>, ordered_usernames: Vec<String>) -> Result<(), AppError> {
+    manager.reorder_accounts(&ordered_usernames)
+}
+
+/// Fija (o desfija) una cuenta en la parte superior del menú Cuentas
+#[tauri::command]
+async fn pin_account(manager: tauri::State<'_, AccountsManager>, username: String, pinned: bool) -> Result<(), AppError> {
+    manager.pin_account(&username, pinned)
+}
+
+/// Número máximo de cuentas configurado
+#[tauri::command]
+async fn get_max_accounts(manager: tauri::State<'_, AccountsManager>) -> Result<usize, AppError> {
+    manager.max_accounts()
+}
+
+/// Cambia el número máximo de cuentas
+#[tauri::command]
+async fn set_max_accounts(manager: tauri::State<'_, AccountsManager>, limit: usize) -> Result<(), AppError> {
+    manager.set_max_accounts(limit)
+}
+
+/// Preferencias de la aplicación (`settings.json` en el directorio de
+/// configuración)
+#[tauri::command]
+async fn get_settings(manager: tauri::State<'_, AccountsManager>) -> Result<settings::AppSettings, AppError> {
+    Ok(manager.settings())
+}
+
+/// Cambia las preferencias presentes en `update` y devuelve las resultantes
+///
+/// Los campos que no aparecen se conservan; un campo desconocido es un error.
+#[tauri::command]
+async fn update_settings(
+    manager: tauri::State<'_, AccountsManager>,
+    update: settings::SettingsUpdate,
+) -> Result<settings::AppSettings, AppError> {
+    manager.update_settings(&update)
+}
+
+/// Cambia el user-agent de las ventanas de X (`None` vuelve al del WebView)
+///
+/// Tiene que ser ASCII imprimible y no estar vacío. Solo se aplica a las
+/// ventanas que se crean después: las abiertas conservan el suyo hasta que
+/// se cierran y se vuelven a abrir (o se reinicia la app).
+#[tauri::command]
+async fn set_user_agent(
+    manager: tauri::State<'_, AccountsManager>,
+    user_agent: Option<String>,
+) -> Result<settings::AppSettings, AppError> {
+    manager.update_settings(&settings::SettingsUpdate {
+        user_agent: Some(user_agent),
+        ..Default::default()
+    })
+}
+
+/// Factor de zoom actual de la ventana que lo pide
+#[tauri::command]
+async fn get_zoom_level(app: tauri::AppHandle, window: tauri::WebviewWindow) -> Result<f64, AppError> {
+    Ok(zoom::window_zoom(&app, &window))
+}
+
+/// Aplica un factor de zoom a la ventana que lo pide y lo guarda para el
+/// próximo arranque
+///
+/// El factor se limita a 0.5–3.0 en pasos de 0.1.
+///
+/// # Returns
+/// Factor aplicado
+#[tauri::command]
+async fn set_zoom_level(
+    app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    manager: tauri::State<'_, AccountsManager>,
+    zoom: f64,
+) -> Result<f64, AppError> {
+    Ok(zoom::apply_zoom(&app, &window, &manager, zoom)?)
+}
+
+/// Cambia el idioma de los menús y lo guarda para los próximos arranques
+///
+/// `language` es un código de idioma (`es`, `en`, `en-US`...). El menú se
+/// reconstruye en el momento.
+#[tauri::command]
+async fn set_language(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    language: String,
+) -> Result<(), AppError> {
+    let language = i18n::Language::parse(&language)
+        .ok_or_else(|| AppError::Internal(format!("Unsupported language '{}'", language)))?;
+
+    i18n::save_language(&manager, language)?;
+    i18n::set_current(language);
+    menu::builder::rebuild_menu(&app).map_err(|e| AppError::Internal(format!("Failed to rebuild menu: {}", e)))?;
+
+    tracing::info!("Menu language set to {}", language.code());
+    Ok(())
+}
+
+/// Cambia la apariencia de las ventanas y la guarda para los próximos arranques
+///
+/// `mode` es `system`, `light` o `dark`.
+#[tauri::command]
+async fn set_appearance(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    mode: String,
+) -> Result<(), AppError> {
+    let appearance = appearance::Appearance::parse(&mode)
+        .ok_or_else(|| AppError::Internal(format!("Unsupported appearance '{}'", mode)))?;
+
+    appearance::set_appearance(&app, &manager, appearance)
+}
+
+/// Cambia el tema de las ventanas (barra de título y fondo) y lo guarda
+///
+/// `theme` es `light`, `dark` o `system`; es lo mismo que `set_appearance`.
+/// X no deja cambiar su propio modo oscuro desde fuera (CORS), así que el
+/// tema se controla desde la app.
+#[tauri::command]
+async fn set_theme(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    theme: appearance::Appearance,
+) -> Result<(), AppError> {
+    appearance::set_appearance(&app, &manager, theme)
+}
+
+/// Cambia el atajo global que muestra u oculta la ventana principal
+///
+/// `accel` tiene el formato `Cmd+Shift+X` (modificadores y una tecla).
+#[tauri::command]
+async fn set_toggle_shortcut(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    accel: String,
+) -> Result<(), AppError> {
+    shortcut::set_toggle_shortcut(&app, &manager, &accel)
+}
+
+/// Comprueba que las credenciales de todas las cuentas se pueden descifrar
+#[tauri::command]
+async fn verify_credentials(manager: tauri::State<'_, AccountsManager>) -> Result<Vec<(String, bool)>, AppError> {
+    manager.verify_credentials()
+}
+
+/// Archiva una cuenta: deja de aparecer en el selector pero conserva sus credenciales
+#[tauri::command]
+async fn archive_account(manager: tauri::State<'_, AccountsManager>, username: String) -> Result<(), AppError> {
+    manager.archive_account(&username)
+}
+
+/// Devuelve una cuenta archivada al selector
+#[tauri::command]
+async fn unarchive_account(manager: tauri::State<'_, AccountsManager>, username: String) -> Result<(), AppError> {
+    manager.unarchive_account(&username)
+}
+
+/// Cambia la etiqueta (texto o emoji) y el color de una cuenta
+#[tauri::command]
+async fn set_account_label(
+    manager: tauri::State<'_, AccountsManager>,
+    username: String,
+    label: Option<String>,
+    color: Option<String>,
+) -> Result<(), AppError> {
+    manager.set_account_label(&username, label, color)
+}
+
+/// Cambia el proxy (`http://` o `socks5://`) de las ventanas adicionales de
+/// una cuenta
+///
+/// Se aplica a las ventanas que se abran a partir de ahora. La ventana
+/// principal siempre se conecta directamente: el proxy de un WebView se fija
+/// al crearlo y esa ventana no se recrea al cambiar de cuenta.
+#[tauri::command]
+async fn set_account_proxy(
+    manager: tauri::State<'_, AccountsManager>,
+    username: String,
+    proxy_url: Option<String>,
+) -> Result<(), AppError> {
+    manager.set_account_proxy(&username, proxy_url)
 }
 
-/// Reconstruye el menú de cuentas (llamar después de agregar/eliminar)
+/// Cambia el orden del menú Cuentas entre manual y por uso reciente
 #[tauri::command]
-async fn rebuild_accounts_menu(app: tauri::AppHandle) -> Result<(), String> {
-    menu::builder::rebuild_menu(&app).map_err(|e| e.to_string())
+async fn set_account_sort_mode(manager: tauri::State<'_, AccountsManager>, recent: bool) -> Result<(), AppError> {
+    manager.set_account_sort_mode(recent)
+}
+
+/// Exporta todas las cuentas a un archivo encriptado con una passphrase
+///
+/// # Returns
+/// Número de cuentas exportadas
+#[tauri::command]
+async fn export_accounts_backup(
+    manager: tauri::State<'_, AccountsManager>,
+    path: String,
+    passphrase: String,
+) -> Result<usize, AppError> {
+    Ok(backup::export_accounts(&manager, &path, &passphrase)?)
+}
+
+/// Importa cuentas desde un archivo de copia de seguridad
+///
+/// # Arguments
+/// * `overwrite` - Si es `true`, las cuentas existentes se sobrescriben con las de la copia
+#[tauri::command]
+async fn import_accounts_backup(
+    manager: tauri::State<'_, AccountsManager>,
+    path: String,
+    passphrase: String,
+    overwrite: Option<bool>,
+) -> Result<backup::ImportSummary, AppError> {
+    Ok(backup::import_accounts(&manager, &path, &passphrase, overwrite.unwrap_or(false))?)
+}
+
+/// Migra las credenciales de v0.3.0 y fusiona cuentas duplicadas
+///
+/// Emite `migration-progress` al empezar cada fase. Con `dry_run: true` no
+/// escribe nada y el informe describe lo que se haría.
+#[tauri::command]
+async fn run_migration(app: tauri::AppHandle, dry_run: bool) -> Result<accounts::MigrationReport, AppError> {
+    migrate(&app, dry_run)
+}
+
+/// Ruta común de `run_migration` y de la migración del arranque
+fn migrate<R: tauri::Runtime>(app: &tauri::AppHandle<R>, dry_run: bool) -> Result<accounts::MigrationReport, AppError> {
+    app.state::<AccountsManager>().run_migration(dry_run, |step| {
+        if let Err(e) = app.emit(accounts::MIGRATION_PROGRESS_EVENT, step) {
+            tracing::error!("Failed to emit {}: {}", accounts::MIGRATION_PROGRESS_EVENT, e);
+        }
+    })
+}
+
+/// Reconstruye el menú de cuentas
+///
+/// Se mantiene por compatibilidad con frontends antiguos: el menú ya se
+/// reconstruye solo tras cada cambio en las cuentas, así que aquí únicamente
+/// se programa una reconstrucción (agrupada con las pendientes).
+#[tauri::command]
+async fn rebuild_accounts_menu(app: tauri::AppHandle) -> Result<(), AppError> {
+    menu::builder::schedule_rebuild(&app);
+    Ok(())
+}
+
+/// Elimina las entradas del Keychain que no corresponden a ninguna cuenta
+///
+/// Con `preview: true` solo las lista. La lista de cuentas y su copia de
+/// seguridad no se borran nunca.
+#[tauri::command]
+async fn cleanup_keychain(
+    manager: tauri::State<'_, AccountsManager>,
+    preview: bool,
+) -> Result<cleanup::CleanupReport, AppError> {
+    cleanup::cleanup_keychain(&manager, preview)
+}
+
+/// Lista las entradas del Keychain que la aplicación ya no usa, sin borrarlas
+///
+/// Incluye las que el Keychain no enumera pero se pueden deducir de los
+/// nombres de cuenta conocidos.
+#[tauri::command]
+async fn list_orphaned_keychain_entries(manager: tauri::State<'_, AccountsManager>) -> Result<Vec<String>, AppError> {
+    cleanup::list_orphaned_keychain_entries(&manager)
+}
+
+/// Elimina las entradas del Keychain que la aplicación ya no usa
+///
+/// # Returns
+/// Número de entradas eliminadas
+#[tauri::command]
+async fn purge_orphaned_entries(manager: tauri::State<'_, AccountsManager>) -> Result<usize, AppError> {
+    cleanup::purge_orphaned_entries(&manager)
+}
+
+/// Rota las claves de encriptación y re-encripta todos los datos guardados
+///
+/// # Returns
+/// Informe con el número de entradas rotadas y los fallos por entrada
+#[tauri::command]
+async fn rotate_keys(manager: tauri::State<'_, AccountsManager>) -> Result<rotation::RotationReport, AppError> {
+    rotation::rotate_manager_keys(&manager)
 }
 
 // =============================================================================
@@ -417,98 +1208,364 @@ async fn rebuild_accounts_menu(app: tauri::AppHandle) -> Result<(), String> {
 
 /// Verifica actualizaciones desde GitHub Releases.
 ///
-/// # Arguments
-/// * `_window` - Ventana Webview (para futuras notificaciones)
+/// El repositorio es el de `updates::update_repo()`.
 ///
 /// # Returns
-/// Versión más reciente encontrada
+/// Versiones actual y más reciente, con la URL y las notas de la release
 ///
 /// # Behavior
-/// Si hay una nueva versión, abre automáticamente la página de releases.
-#[tauri::command]
-async fn check_updates(_window: tauri::WebviewWindow) -> Result<String, String> {
-    tracing::info!("Checking for updates...");
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get("https://api.github.com/repos/686f6c61/Xcom-mac-silicon/releases/latest")
-        .header("User-Agent", "X-Mac-Client")
-        .send()
-        .await
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
-
-    if response.status().is_success() {
-        let release: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-        let latest_version = release["tag_name"]
-            .as_str()
-            .ok_or("Missing tag_name in release response")?
-            .replace('v', "");
-
-        let current_version = env!("CARGO_PKG_VERSION").to_string();
-
-        tracing::info!("Current: {}, Latest: {}", current_version, latest_version);
-
-        if latest_version != current_version {
-            tracing::info!("New version available: {}", latest_version);
-            tauri_plugin_opener::open_url(
-                "https://github.com/686f6c61/Xcom-mac-silicon/releases",
-                None::<String>,
-            )
-            .map_err(|e| format!("Failed to open URL: {}", e))?;
-            Ok(latest_version)
-        } else {
-            tracing::info!("Already on latest version");
-            Ok(current_version)
+/// No abre nada: si hay una nueva versión, emite `update-available` a la
+/// ventana principal y el frontend decide cómo avisar (ver
+/// `get_update_policy`). Abrir la release es `open_release_page`.
+///
+/// Es la comprobación automática: no avisa de la versión omitida con
+/// `skip_update_version` ni mientras dure `snooze_updates`.
+///
+/// Sin conexión falla con el tipo `offline` sin hacer ninguna petición. Si
+/// se ha agotado el límite de la API de GitHub, usa la última respuesta
+/// guardada o falla con `rate_limited` y el momento en que se renueva.
+#[tauri::command]
+async fn check_updates(app: tauri::AppHandle) -> Result<updates::UpdateInfo, AppError> {
+    let channel = updates::load_update_channel(&app.state::<AccountsManager>());
+    let cache = update_cache::cache_path(&app);
+    let info = updates::check_for_updates(channel, cache.as_deref()).await?;
+
+    let manager = app.state::<AccountsManager>();
+    if let Err(e) = updates::record_check(&manager, chrono::Utc::now().timestamp()) {
+        tracing::warn!("Failed to save update check time: {}", e);
+    }
+    updates::set_available_update(&app, &manager, &info);
+
+    if updates::should_notify(&manager, &info, chrono::Utc::now().timestamp()) {
+        if let Err(e) = app.emit_to(window::MAIN_WINDOW, updates::UPDATE_AVAILABLE_EVENT, &info) {
+            tracing::error!("Failed to emit {}: {}", updates::UPDATE_AVAILABLE_EVENT, e);
         }
-    } else {
-        let status = response.status();
-        tracing::error!("GitHub API returned status: {}", status);
-        Err(format!("GitHub API error: {}", status))
     }
+
+    Ok(info)
+}
+
+/// Política de actualizaciones guardada (`off`, `notify_only` u `open_page`)
+#[tauri::command]
+async fn get_update_policy(manager: tauri::State<'_, AccountsManager>) -> Result<updates::AutoUpdatePolicy, AppError> {
+    Ok(updates::load_update_policy(&manager))
+}
+
+/// Cambia qué se hace con las actualizaciones al arrancar
+///
+/// `policy` es `off`, `notify_only` u `open_page`. Se aplica a partir del
+/// siguiente arranque; la marca de Ayuda > Buscar Actualizaciones al Abrir se
+/// actualiza en el momento.
+#[tauri::command]
+async fn set_update_policy(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    policy: updates::AutoUpdatePolicy,
+) -> Result<(), AppError> {
+    updates::save_update_policy(&manager, policy)?;
+    if let Err(e) = menu::builder::rebuild_menu(&app) {
+        tracing::error!("Failed to rebuild menu: {}", e);
+    }
+
+    tracing::info!("Update policy set to {:?}", policy);
+
+    Ok(())
+}
+
+/// Activa o desactiva la comprobación de actualizaciones al arrancar
+///
+/// Desactivarla equivale a la política `off`; al activarla se conserva la
+/// política que hubiera o, si era `off`, se vuelve a `open_page`.
+///
+/// `interval_hours` es el tiempo mínimo entre dos comprobaciones automáticas
+/// (de 1 hora a 30 días). Ayuda > Buscar Actualizaciones funciona siempre.
+#[tauri::command]
+async fn set_auto_update_check(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    enabled: bool,
+    interval_hours: u32,
+) -> Result<settings::AppSettings, AppError> {
+    updates::set_auto_update_check(&app, &manager, enabled, interval_hours)
 }
 
+/// Cambia el canal de actualizaciones (`stable` o `beta`)
+///
+/// Con `beta`, las comprobaciones también tienen en cuenta las pre-releases.
 #[tauri::command]
-async fn open_help() -> Result<(), String> {
+async fn set_update_channel(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    channel: updates::UpdateChannel,
+) -> Result<(), AppError> {
+    updates::set_update_channel(&app, &manager, channel)
+}
+
+/// Deja de avisar de `version` al arrancar (botón "Omitir esta versión")
+///
+/// Si sale una versión más nueva, se vuelve a avisar. La versión omitida
+/// también desaparece del menú Ayuda.
+#[tauri::command]
+async fn skip_update_version(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, AccountsManager>,
+    version: String,
+) -> Result<(), AppError> {
+    let version = updates::skip_version(&manager, &version)?;
+    updates::forget_available_update(&app, &version);
+
+    tracing::info!("Skipping update {}", version);
+
+    Ok(())
+}
+
+/// Aplaza `hours` horas los avisos de actualización (botón "Recordar más tarde")
+#[tauri::command]
+async fn snooze_updates(manager: tauri::State<'_, AccountsManager>, hours: u32) -> Result<(), AppError> {
+    let until = updates::snooze(&manager, hours, chrono::Utc::now().timestamp())?;
+
+    tracing::info!("Update notifications snoozed until {}", until);
+
+    Ok(())
+}
+
+/// Descarga el instalador de la última release a `~/Downloads` y lo abre
+///
+/// `url` es la de `UpdateInfo::asset`; el progreso se emite con
+/// `update-download-progress`. Se comprueban el tamaño y, si se publica, la
+/// suma SHA-256.
+///
+/// # Returns
+/// Ruta del instalador descargado
+#[tauri::command]
+async fn download_update(app: tauri::AppHandle, url: String) -> Result<String, AppError> {
+    let path = update_download::download_update(&app, &url).await?;
+    Ok(path.display().to_string())
+}
+
+/// Cancela la descarga de `download_update` en curso
+///
+/// # Returns
+/// `true` si había una descarga que cancelar
+#[tauri::command]
+async fn cancel_update_download(app: tauri::AppHandle) -> Result<bool, AppError> {
+    update_download::cancel_update_download(&app)
+}
+
+/// Abre la página de releases del repositorio de actualizaciones
+///
+/// Es lo que llama el frontend cuando el usuario acepta el aviso de
+/// `update-available`.
+#[tauri::command]
+async fn open_release_page() -> Result<(), AppError> {
+    tauri_plugin_opener::open_url(updates::releases_page_url(updates::update_repo())?, None::<String>)
+        .map_err(|e| AppError::Internal(format!("Failed to open URL: {}", e)))
+}
+
+/// Directorio con los ficheros de log, para adjuntarlos a un informe de error
+#[tauri::command]
+async fn get_log_directory(app: tauri::AppHandle) -> Result<String, AppError> {
+    logging::log_directory(&app.config().identifier)
+        .map(|directory| directory.display().to_string())
+        .ok_or_else(|| AppError::Internal("Cannot resolve the log directory".to_string()))
+}
+
+/// Informe de diagnóstico en JSON, sin tokens ni sesiones y con los nombres de
+/// usuario enmascarados
+#[tauri::command]
+async fn generate_diagnostics(app: tauri::AppHandle, manager: tauri::State<'_, AccountsManager>) -> Result<String, AppError> {
+    let log_dir = logging::log_directory(&app.config().identifier);
+    Ok(diagnostics::generate_diagnostics(&manager, log_dir.as_deref()))
+}
+
+/// Versión, commit y fecha de la compilación, para la ventana "Acerca de"
+#[tauri::command]
+async fn get_app_info() -> Result<app_info::AppInfo, AppError> {
+    Ok(app_info::AppInfo::current())
+}
+
+#[tauri::command]
+async fn open_help() -> Result<(), AppError> {
     tauri_plugin_opener::open_url(
         "https://github.com/686f6c61/Xcom-mac-silicon",
         None::<String>,
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| AppError::Internal(e.to_string()))?;
     Ok(())
 }
 
+/// Comprobación de actualizaciones al arrancar
+///
+/// Antes de esperar nada se decide si toca: no se hace con la política `off`
+/// ni si la última comprobación correcta es de hace menos de
+/// `update_check_interval_hours`.
+fn spawn_startup_update_check(app: &tauri::AppHandle) {
+    let manager = app.state::<AccountsManager>();
+    let settings = manager.settings();
+
+    if !settings.update_policy.checks_on_launch() {
+        tracing::info!("Automatic update check is off");
+        return;
+    }
+    if !updates::check_due(
+        updates::load_last_check(&manager),
+        settings.update_check_interval_hours,
+        chrono::Utc::now().timestamp(),
+    ) {
+        tracing::info!(
+            "Last update check was less than {}h ago, skipping",
+            settings.update_check_interval_hours
+        );
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Reducir delay de 5s a 2s
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+        tracing::info!("Starting automatic update check");
+        loop {
+            match check_updates(app.clone()).await {
+                Ok(_) => break,
+                Err(AppError::Offline) => {
+                    tracing::info!("Offline, skipping the automatic update check");
+                    break;
+                }
+                // Volver a probar cuando GitHub renueve el límite
+                Err(AppError::RateLimited(reset)) => {
+                    let wait = (reset - chrono::Utc::now().timestamp()).max(0) as u64 + 1;
+                    tracing::info!("GitHub API rate limit exceeded, next update check in {}s", wait);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(wait)).await;
+                }
+                Err(e) => {
+                    tracing::error!("Update check failed: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+}
+
 /// Punto de entrada de la aplicación Tauri.
 ///
 /// Inicializa logging, plugins y handlers de comandos.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Inicializar logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
+    let context = tauri::generate_context!();
+    let identifier = context.config().identifier.clone();
+
+    // Inicializar logging (texto, o JSON con XMAC_LOG_FORMAT=json), en stdout
+    // y en el directorio de logs
+    logging::init_logging(&identifier).init();
 
     tracing::info!("Starting X macOS Client v{}", env!("CARGO_PKG_VERSION"));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(links::init())
         .setup(|app| {
+            // Enlaces de X abiertos desde otras apps; hasta que la ventana
+            // principal esté lista se guardan en `DeepLinks`
+            app.manage(deep_link::DeepLinks::default());
+            app.manage(menu::navigation::PendingClicks::default());
+            app.manage(window::BadgeCount::default());
+            let deep_link_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                deep_link::handle_urls(&deep_link_handle, event.urls());
+            });
+            match app.deep_link().get_current() {
+                Ok(Some(urls)) => deep_link::handle_urls(app.handle(), urls),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to read the launch deep link: {}", e),
+            }
+
+            // Estado de cuentas: cada cambio se notifica al frontend y al menú
+            let handle = app.handle().clone();
+            // Límite de tamaño de token/session_data (bytes), configurable con
+            // XMAC_MAX_PAYLOAD_SIZE
+            let max_payload_size = std::env::var("XMAC_MAX_PAYLOAD_SIZE")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(accounts::DEFAULT_MAX_PAYLOAD_SIZE);
+
+            let recovery_handle = app.handle().clone();
+            let active_handle = app.handle().clone();
+            // Preferencias que no son secretas, fuera del Keychain
+            let app_settings = match app.path().app_config_dir() {
+                Ok(dir) => settings::Settings::open(settings::settings_path(&dir)),
+                Err(e) => {
+                    tracing::warn!("Cannot resolve the config directory, settings will not be saved: {}", e);
+                    settings::Settings::in_memory()
+                }
+            };
+            settings::migrate_legacy(secrets::store(), &app_settings);
+
+            let manager = AccountsManager::new(secrets::store())
+                .max_payload_size(max_payload_size)
+                .with_settings(std::sync::Arc::new(app_settings))
+                .on_recovery(move || {
+                    if let Err(e) = recovery_handle.emit(accounts::ACCOUNTS_RECOVERED_EVENT, ()) {
+                        tracing::error!("Failed to emit {}: {}", accounts::ACCOUNTS_RECOVERED_EVENT, e);
+                    }
+                })
+                .on_change(move |payload| {
+                    if let Err(e) = handle.emit(accounts::ACCOUNTS_CHANGED_EVENT, payload) {
+                        tracing::error!("Failed to emit {}: {}", accounts::ACCOUNTS_CHANGED_EVENT, e);
+                    }
+
+                    menu::builder::schedule_rebuild(&handle);
+                })
+                .on_active_change(move |payload| {
+                    if let Err(e) = active_handle.emit(accounts::ACTIVE_ACCOUNT_CHANGED_EVENT, payload) {
+                        tracing::error!("Failed to emit {}: {}", accounts::ACTIVE_ACCOUNT_CHANGED_EVENT, e);
+                    }
+
+                    window::update_window_identity(&active_handle);
+                });
+            app.manage(manager);
+
+            // Tamaño, posición y estado de las ventanas de la última sesión
+            let window_states = window_state::WindowStates::open(window_state::state_path(app.handle()));
+            window_state::migrate_legacy(secrets::store(), &window_states);
+            app.manage(window_states);
+
+            // La ventana principal se crea aquí (no desde tauri.conf.json)
+            // para usar el user-agent de las preferencias y restaurar su
+            // estado antes de mostrarla
+            let window = window::build_main_window(app.handle())?;
+
             // Migrar credenciales de v0.3.0 a v0.4.0 si es necesario
-            tauri::async_runtime::spawn(async {
-                if let Err(e) = accounts::migrate_legacy_credentials() {
-                    tracing::error!("Migration failed: {}", e);
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match migrate(&handle, false) {
+                    Ok(report) => {
+                        if let Err(e) = handle.emit(accounts::MIGRATION_COMPLETE_EVENT, &report) {
+                            tracing::error!("Failed to emit {}: {}", accounts::MIGRATION_COMPLETE_EVENT, e);
+                        }
+                    }
+                    Err(e) => tracing::error!("Migration failed: {}", e),
                 }
+
+                // Mostrar la cuenta activa en el título desde el arranque
+                window::update_window_identity(&handle);
+
+                // Comprobar las sesiones guardadas una vez migradas las cuentas
+                session_check::spawn_validation(&handle);
             });
 
+            // Idioma de los menús: el elegido por el usuario o el del sistema
+            let manager = app.state::<AccountsManager>();
+            if let Some(language) = i18n::load_language(&manager) {
+                i18n::set_current(language);
+            }
+
+            // Apariencia guardada (por defecto, la del sistema)
+            appearance::apply(app.handle(), appearance::load_appearance(&manager));
+
             // Construir y establecer menú nativo
             let menu = menu::builder::build_menu(app.handle())
                 .expect("Failed to build menu");
@@ -519,22 +1576,54 @@ pub fn run() {
             // Configurar handlers de menú
             menu::handlers::setup_menu_handlers(app.handle());
 
-            let window = app
-                .get_webview_window("main")
-                .ok_or("Failed to get main window")?;
-
-            let window_clone = window.clone();
-            tauri::async_runtime::spawn(async move {
-                // Reducir delay de 5s a 2s
-                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                tracing::info!("Starting automatic update check");
-                if let Err(e) = check_updates(window_clone).await {
-                    tracing::error!("Update check failed: {}", e);
-                }
-            });
+            // Destinos de la navegación del menú (x.com salvo que se configure otra base)
+            let nav_config = app
+                .path()
+                .app_config_dir()
+                .map(|dir| menu::nav_config::NavConfig::load(&dir))
+                .unwrap_or_default();
+            // Los enlaces a la instancia configurada también se quedan en la app
+            let mut link_policy = links::LinkPolicy::from_env();
+            if let Some(host) = tauri::Url::parse(&nav_config.base_url).ok().as_ref().and_then(tauri::Url::host_str) {
+                link_policy = link_policy.with_host(host);
+            }
+            app.manage(link_policy);
+            app.manage(nav_config);
+
+            // Restaurar el último zoom
+            app.manage(zoom::ZoomLevels::default());
+            let saved_zoom = zoom::load_zoom(&manager);
+            if let Err(e) = zoom::set_window_zoom(app.handle(), &window, saved_zoom) {
+                tracing::warn!("Failed to restore zoom: {}", e);
+            }
+
+            // Atajo global para mostrar u ocultar la ventana principal
+            shortcut::init(app.handle(), &manager);
+
+            // Abrir el enlace con el que se lanzó la app, si lo hay
+            deep_link::mark_ready(app.handle());
+
+            app.manage(update_download::UpdateDownload::default());
+            app.manage(updates::AvailableUpdate::default());
+
+            spawn_startup_update_check(app.handle());
 
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            // Perfil abierto en la ventana principal: posible cuenta nueva
+            if webview.label() == window::MAIN_WINDOW && payload.event() == tauri::webview::PageLoadEvent::Finished {
+                if let Err(e) = account_detect::detect_logged_in_user(webview.app_handle()) {
+                    tracing::debug!("Account detection failed: {}", e);
+                }
+            }
+        })
+        .on_window_event(|window, event| {
+            // Con la apariencia del sistema, seguir sus cambios
+            if let tauri::WindowEvent::ThemeChanged(theme) = event {
+                appearance::on_theme_changed(window.app_handle(), *theme);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             save_credentials,
             get_credentials,
@@ -543,14 +1632,79 @@ pub fn run() {
             get_active_account,
             set_active_account,
             save_account_credentials,
+            on_login_detected,
             delete_account,
+            clear_session_data,
+            logout_all_accounts,
+            detect_logged_in_user,
             switch_account,
+            capture_session,
+            restore_session,
+            open_account_window,
+            open_incognito_window,
+            open_profile,
+            open_in_app,
+            compose_tweet,
+            set_badge_count,
+            find_in_page,
+            export_page_pdf,
+            open_accounts_manager,
+            rename_account,
+            update_account_profile,
+            get_account_session_age,
+            validate_session,
+            get_account_details,
+            get_stale_accounts,
+            reorder_accounts,
+            pin_account,
+            set_account_sort_mode,
+            set_account_label,
+            set_account_proxy,
+            archive_account,
+            unarchive_account,
+            verify_credentials,
+            get_max_accounts,
+            set_max_accounts,
+            get_settings,
+            update_settings,
+            set_user_agent,
+            set_auto_update_check,
+            set_language,
+            set_appearance,
+            set_theme,
+            set_toggle_shortcut,
+            get_zoom_level,
+            set_zoom_level,
+            export_accounts_backup,
+            import_accounts_backup,
+            rotate_keys,
+            cleanup_keychain,
+            list_orphaned_keychain_entries,
+            purge_orphaned_entries,
             rebuild_accounts_menu,
+            run_migration,
             check_updates,
-            open_help
+            get_update_policy,
+            set_update_policy,
+            set_update_channel,
+            skip_update_version,
+            snooze_updates,
+            download_update,
+            cancel_update_download,
+            open_release_page,
+            open_help,
+            get_app_info,
+            generate_diagnostics,
+            get_log_directory
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Sobrescribir con ceros las claves cacheadas antes de salir
+                keycache::clear();
+            }
+        });
 }
 
 #[cfg(test)]
@@ -562,18 +1716,30 @@ mod tests {
         let username = "test_user";
         let original = r#"{"username":"test","token":"secret123"}"#;
 
-        let encrypted = encrypt_credentials(original, username).unwrap();
-        let decrypted = decrypt_credentials(&encrypted, username).unwrap();
+        let encrypted = encrypt_credentials(original, username, &KdfParams::default()).unwrap();
+        let decrypted = decrypt_credentials(&encrypted, username, &KdfParams::default()).unwrap();
 
         assert_eq!(original, decrypted);
     }
 
+    #[test]
+    fn test_encryption_custom_kdf_params() {
+        let username = "test_user";
+        let original = r#"{"username":"test","token":"secret123"}"#;
+        let params = KdfParams { m_cost: 1024, t_cost: 1, p_cost: 1 };
+
+        let encrypted = encrypt_credentials(original, username, &params).unwrap();
+
+        assert_eq!(decrypt_credentials(&encrypted, username, &params).unwrap(), original);
+        assert!(decrypt_credentials(&encrypted, username, &KdfParams::default()).is_err());
+    }
+
     #[test]
     fn test_encryption_wrong_user() {
         let original = r#"{"username":"test","token":"secret"}"#;
 
-        let encrypted = encrypt_credentials(original, "user1").unwrap();
-        let result = decrypt_credentials(&encrypted, "user2");
+        let encrypted = encrypt_credentials(original, "user1", &KdfParams::default()).unwrap();
+        let result = decrypt_credentials(&encrypted, "user2", &KdfParams::default());
 
         assert!(result.is_err());
     }
@@ -583,15 +1749,15 @@ mod tests {
         let username = "test_user";
         let original = r#"{"username":"test","token":"secret123"}"#;
 
-        let encrypted1 = encrypt_credentials(original, username).unwrap();
-        let encrypted2 = encrypt_credentials(original, username).unwrap();
+        let encrypted1 = encrypt_credentials(original, username, &KdfParams::default()).unwrap();
+        let encrypted2 = encrypt_credentials(original, username, &KdfParams::default()).unwrap();
 
         // Los nonces aleatorios hacen que cada encriptación sea diferente
         assert_ne!(encrypted1, encrypted2);
 
         // Pero ambos deben desencriptar correctamente
-        let decrypted1 = decrypt_credentials(&encrypted1, username).unwrap();
-        let decrypted2 = decrypt_credentials(&encrypted2, username).unwrap();
+        let decrypted1 = decrypt_credentials(&encrypted1, username, &KdfParams::default()).unwrap();
+        let decrypted2 = decrypt_credentials(&encrypted2, username, &KdfParams::default()).unwrap();
 
         assert_eq!(decrypted1, original);
         assert_eq!(decrypted2, original);
@@ -602,19 +1768,77 @@ mod tests {
         let username = "test_user";
         let corrupted_data = vec![1, 2, 3, 4, 5]; // Datos inválidos
 
-        let result = decrypt_credentials(&corrupted_data, username);
+        let result = decrypt_credentials(&corrupted_data, username, &KdfParams::default());
 
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_zeroizing_key_is_zeroed_on_drop() {
+        let mut slot = std::mem::MaybeUninit::new(Zeroizing::new([0xAAu8; KEY_SIZE]));
+        let value = slot.as_mut_ptr();
+
+        // Puntero al array dentro del hueco, sacado del puntero del propio
+        // hueco: sigue siendo memoria válida después de soltar el valor y
+        // todas las lecturas pasan por él
+        let bytes: *const [u8; KEY_SIZE] = unsafe { &**value };
+        assert_eq!(unsafe { bytes.read() }, [0xAAu8; KEY_SIZE]);
+
+        unsafe { std::ptr::drop_in_place(value) };
+
+        assert_eq!(unsafe { bytes.read_volatile() }, [0u8; KEY_SIZE]);
+    }
+
+    #[test]
+    fn test_invalid_utf8_plaintext_is_rejected() {
+        assert!(plaintext_to_string(vec![0xff, 0xfe]).is_err());
+        assert_eq!(plaintext_to_string(b"ok".to_vec()).unwrap(), "ok");
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"token-123", b"token-123"));
+        assert!(ct_eq(b"", b""));
+        assert!(!ct_eq(b"token-123", b"token-124"));
+        assert!(!ct_eq(b"token-123", b"token-12"));
+        assert!(!ct_eq(b"token", b""));
+
+        let key = derive_encryption_key("user1", &KdfParams::default()).unwrap();
+        assert!(ct_eq(&key, &derive_encryption_key("user1", &KdfParams::default()).unwrap()));
+        assert!(!ct_eq(&key, &derive_encryption_key("user2", &KdfParams::default()).unwrap()));
+    }
+
+    #[test]
+    fn test_hash_key_is_deterministic() {
+        // El nombre de la entrada en Keychain debe poder recalcularse
+        assert_eq!(hash_key("accounts_list"), hash_key("accounts_list"));
+        assert_ne!(hash_key("credentials_a"), hash_key("credentials_b"));
+    }
+
     #[test]
     fn test_derive_encryption_key_consistency() {
         let username = "test_user";
 
-        let key1 = derive_encryption_key(username).unwrap();
-        let key2 = derive_encryption_key(username).unwrap();
+        let key1 = derive_encryption_key(username, &KdfParams::default()).unwrap();
+        let key2 = derive_encryption_key(username, &KdfParams::default()).unwrap();
 
         // La misma clave debe generarse para el mismo usuario
         assert_eq!(key1, key2);
     }
+
+    #[test]
+    fn test_open_profile_handle() {
+        let url = |handle: &str| {
+            accounts::validate_username(handle).map(|handle| menu::nav_config::NavConfig::default().profile_url(&handle))
+        };
+
+        assert_eq!(url("@foo").unwrap(), "https://x.com/foo");
+        assert_eq!(url("foo").unwrap(), "https://x.com/foo");
+        assert_eq!(url(&"a".repeat(15)).unwrap(), format!("https://x.com/{}", "a".repeat(15)));
+        assert!(url("bad/name").is_err());
+        assert!(url("../settings").is_err());
+        assert!(url("").is_err());
+        assert!(url("@").is_err());
+        assert!(url(&"a".repeat(16)).is_err());
+    }
 }
@@ -0,0 +1,150 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Enlaces externos desde las ventanas de X
+//
+// Las ventanas solo navegan dentro de X: un enlace a cualquier otro sitio se
+// abre en el navegador por defecto en lugar de sustituir a x.com (y a la
+// sesión) en la ventana. Se intercepta con el `on_navigation` de un plugin,
+// así se aplica a todas las ventanas, también a la principal que crea
+// `tauri.conf.json`.
+//
+// Los hosts que se quedan en la app son x.com, twitter.com y t.co (y sus
+// subdominios), el de la base de `navigation.json` si hay una, y los que se
+// añadan con `XMAC_IN_APP_HOSTS=host1,host2`.
+
+use tauri::plugin::{Builder, TauriPlugin};
+use tauri::{Manager, Runtime, Url};
+
+/// Hosts que se abren siempre dentro de la app
+pub const DEFAULT_IN_APP_HOSTS: &[&str] = &["x.com", "twitter.com", "t.co"];
+
+/// Host de las páginas propias de la app fuera de macOS (`http://tauri.localhost`)
+const APP_HOST: &str = "tauri.localhost";
+
+/// Hosts cuyos enlaces se abren dentro de la app
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinkPolicy {
+    hosts: Vec<String>,
+}
+
+impl Default for LinkPolicy {
+    fn default() -> Self {
+        Self {
+            hosts: DEFAULT_IN_APP_HOSTS.iter().map(|host| host.to_string()).collect(),
+        }
+    }
+}
+
+impl LinkPolicy {
+    /// Hosts por defecto más los de `XMAC_IN_APP_HOSTS`
+    pub fn from_env() -> Self {
+        let extra = std::env::var("XMAC_IN_APP_HOSTS").unwrap_or_default();
+        extra.split(',').fold(Self::default(), |policy, host| policy.with_host(host))
+    }
+
+    /// Añade un host (y sus subdominios); los vacíos se ignoran
+    pub fn with_host(mut self, host: &str) -> Self {
+        let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+        if !host.is_empty() && !self.hosts.contains(&host) {
+            self.hosts.push(host);
+        }
+        self
+    }
+
+    /// Si `url` se abre en la ventana o en el navegador por defecto
+    ///
+    /// Solo se sacan de la app los enlaces `http`/`https`: las páginas propias
+    /// (`tauri://`), `about:blank`, `blob:`, etc. siguen en la ventana.
+    pub fn opens_in_app(&self, url: &Url) -> bool {
+        if !matches!(url.scheme(), "http" | "https") {
+            return true;
+        }
+
+        let Some(host) = url.host_str() else {
+            return true;
+        };
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+        host == APP_HOST
+            || self.hosts.iter().any(|allowed| {
+                host == *allowed
+                    || host
+                        .strip_suffix(allowed.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            })
+    }
+}
+
+/// Plugin que desvía al navegador los enlaces fuera de los hosts de
+/// `LinkPolicy` (la gestionada en el estado, o la por defecto)
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("external-links")
+        .on_navigation(|webview, url| {
+            // Respuesta de `click_element_by_testid`, no una navegación real
+            if crate::menu::navigation::handle_click_result(webview.app_handle(), url) {
+                return false;
+            }
+
+            let in_app = match webview.try_state::<LinkPolicy>() {
+                Some(policy) => policy.opens_in_app(url),
+                None => LinkPolicy::default().opens_in_app(url),
+            };
+
+            if !in_app {
+                tracing::info!("Opening external link in the default browser: {}", url);
+                if let Err(e) = tauri_plugin_opener::open_url(url.as_str(), None::<String>) {
+                    tracing::error!("Failed to open {} in the browser: {}", url, e);
+                }
+            }
+
+            in_app
+        })
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_app(policy: &LinkPolicy, url: &str) -> bool {
+        policy.opens_in_app(&url.parse().unwrap())
+    }
+
+    #[test]
+    fn test_x_links_stay_in_app() {
+        let policy = LinkPolicy::default();
+
+        assert!(in_app(&policy, "https://x.com/home"));
+        assert!(in_app(&policy, "https://mobile.twitter.com/alice/status/1"));
+        assert!(in_app(&policy, "https://t.co/abc123"));
+        assert!(in_app(&policy, "https://X.COM./explore"));
+        assert!(in_app(&policy, "tauri://localhost/accounts.html"));
+        assert!(in_app(&policy, "http://tauri.localhost/accounts.html"));
+        assert!(in_app(&policy, "about:blank"));
+    }
+
+    #[test]
+    fn test_other_links_open_in_browser() {
+        let policy = LinkPolicy::default();
+
+        assert!(!in_app(&policy, "https://example.com/"));
+        assert!(!in_app(&policy, "https://github.com/686f6c61/Xcom-mac-silicon"));
+        // Un host que solo termina igual no es un subdominio
+        assert!(!in_app(&policy, "https://notx.com/"));
+        assert!(!in_app(&policy, "https://x.com.evil.example/"));
+    }
+
+    #[test]
+    fn test_extra_hosts() {
+        let policy = LinkPolicy::default().with_host(" Nitter.Example ").with_host("");
+
+        assert!(in_app(&policy, "https://nitter.example/alice"));
+        assert!(in_app(&policy, "https://www.nitter.example/alice"));
+        assert!(!in_app(&policy, "https://example.com/"));
+        assert_eq!(policy.hosts.len(), DEFAULT_IN_APP_HOSTS.len() + 1);
+    }
+}
@@ -0,0 +1,167 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Configuración de los logs
+//
+// Por defecto se escriben en texto legible. Con `XMAC_LOG_FORMAT=json` cada
+// línea es un objeto JSON, más fácil de analizar cuando un usuario adjunta
+// sus logs a un informe de error. El nivel se controla con `RUST_LOG`.
+//
+// Además de stdout, los logs se guardan en `logs/` dentro del directorio de
+// datos de la app (el mismo que `app_data_dir` de Tauri), en un fichero por
+// día; solo se conservan los últimos `MAX_LOG_FILES`.
+
+use std::path::{Path, PathBuf};
+
+use tracing::Subscriber;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// Variable de entorno que elige el formato
+pub const LOG_FORMAT_ENV: &str = "XMAC_LOG_FORMAT";
+/// Subdirectorio de los logs dentro del directorio de datos
+const LOG_DIR_NAME: &str = "logs";
+/// Prefijo de los ficheros (`xmac.2025-03-01.log`)
+const LOG_FILE_PREFIX: &str = "xmac";
+/// Extensión de los ficheros
+const LOG_FILE_SUFFIX: &str = "log";
+/// Días de logs que se conservan
+const MAX_LOG_FILES: usize = 7;
+
+/// Formato de los logs
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LogFormat {
+    /// Texto legible (por defecto)
+    #[default]
+    Text,
+    /// Una línea JSON por evento
+    Json,
+}
+
+impl LogFormat {
+    /// Interpreta el valor de `XMAC_LOG_FORMAT`; cualquier valor distinto de
+    /// `json` deja el formato por defecto
+    pub fn parse(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(value) if value.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+
+    /// Formato elegido en el entorno
+    pub fn from_env() -> Self {
+        Self::parse(std::env::var(LOG_FORMAT_ENV).ok().as_deref())
+    }
+}
+
+/// Directorio de los logs para el directorio de datos del sistema
+/// (`~/Library/Application Support` en macOS) y el identificador de la app
+pub fn resolve_log_directory(data_dir: Option<PathBuf>, identifier: &str) -> Option<PathBuf> {
+    let identifier = identifier.trim();
+    if identifier.is_empty() {
+        return None;
+    }
+
+    Some(data_dir?.join(identifier).join(LOG_DIR_NAME))
+}
+
+/// Directorio de los logs de la app con identificador `identifier`
+pub fn log_directory(identifier: &str) -> Option<PathBuf> {
+    resolve_log_directory(dirs::data_dir(), identifier)
+}
+
+/// Fichero de log que rota cada día en `directory`
+fn file_appender(directory: &Path) -> Result<RollingFileAppender, String> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(directory)
+        .map_err(|e| format!("Failed to open log directory {}: {}", directory.display(), e))
+}
+
+/// Subscriber de `tracing` con el formato y el filtro del entorno, que
+/// escribe en stdout y en el directorio de logs de `identifier`
+///
+/// Se instala con `.init()` (de `SubscriberInitExt`) al arrancar. Si no se
+/// puede abrir el directorio de logs, solo se escribe en stdout.
+pub fn init_logging(identifier: &str) -> Box<dyn Subscriber + Send + Sync> {
+    let file = log_directory(identifier).and_then(|directory| match file_appender(&directory) {
+        Ok(appender) => Some(appender),
+        Err(e) => {
+            // Todavía no hay subscriber
+            eprintln!("{}", e);
+            None
+        }
+    });
+
+    subscriber(LogFormat::from_env(), file)
+}
+
+/// Capa de `fmt` en `format`
+fn fmt_layer<W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'writer> tracing_subscriber::fmt::MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi);
+
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// Subscriber para `format`, filtrando con `RUST_LOG` (o `info`)
+fn subscriber(format: LogFormat, file: Option<RollingFileAppender>) -> Box<dyn Subscriber + Send + Sync> {
+    let mut layers = vec![fmt_layer(format, std::io::stdout, true)];
+    if let Some(file) = file {
+        layers.push(fmt_layer(format, file, false));
+    }
+
+    Box::new(
+        tracing_subscriber::registry()
+            .with(layers)
+            .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_from_env_value() {
+        assert_eq!(LogFormat::parse(None), LogFormat::Text);
+        assert_eq!(LogFormat::parse(Some("json")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some(" JSON ")), LogFormat::Json);
+        assert_eq!(LogFormat::parse(Some("text")), LogFormat::Text);
+        assert_eq!(LogFormat::parse(Some("")), LogFormat::Text);
+        assert_eq!(LogFormat::parse(Some("yaml")), LogFormat::Text);
+    }
+
+    #[test]
+    fn test_both_formats_build_a_subscriber() {
+        for format in [LogFormat::Text, LogFormat::Json] {
+            tracing::subscriber::with_default(subscriber(format, None), || {
+                tracing::info!(format = ?format, "logging configured");
+            });
+        }
+    }
+
+    #[test]
+    fn test_log_directory_resolution() {
+        let data_dir = PathBuf::from("/Users/alice/Library/Application Support");
+
+        assert_eq!(
+            resolve_log_directory(Some(data_dir.clone()), "com.twitter.xmac"),
+            Some(data_dir.join("com.twitter.xmac").join("logs"))
+        );
+        assert_eq!(resolve_log_directory(None, "com.twitter.xmac"), None);
+        assert_eq!(resolve_log_directory(Some(data_dir), " "), None);
+    }
+}
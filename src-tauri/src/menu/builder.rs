@@ -7,10 +7,19 @@
 // Construcción de menús nativos de macOS
 
 use tauri::{
-    menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder, AboutMetadataBuilder},
-    AppHandle, Runtime,
+    menu::{
+        CheckMenuItemBuilder, IconMenuItemBuilder, MenuBuilder, MenuItemBuilder, MenuItemKind, NativeIcon,
+        SubmenuBuilder, AboutMetadataBuilder,
+    },
+    AppHandle, Manager, Runtime,
 };
+use once_cell::sync::Lazy;
+use super::debounce::{Debouncer, REBUILD_DEBOUNCE};
 use super::items::*;
+use crate::accounts::{is_stale, unix_now, AccountInfo, AccountsManager, DEFAULT_MAX_ACCOUNTS, STALE_SESSION_DAYS};
+use crate::appearance::Appearance;
+use crate::i18n::tr;
+use crate::updates::UpdateChannel;
 
 /// Construye el menú completo de la aplicación
 pub fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Menu<R>> {
@@ -35,16 +44,70 @@ pub fn rebuild_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
     Ok(())
 }
 
+/// Programa una reconstrucción del menú, agrupando las que lleguen en ráfaga
+pub fn schedule_rebuild<R: Runtime>(app: &AppHandle<R>) {
+    static DEBOUNCER: Lazy<Debouncer> = Lazy::new(|| Debouncer::new(REBUILD_DEBOUNCE));
+
+    DEBOUNCER.schedule(app, |app| {
+        if let Err(e) = rebuild_menu(app) {
+            tracing::error!("Failed to rebuild menu: {}", e);
+        }
+    });
+}
+
+/// Marca en el menú la cuenta activa y desmarca el resto sin reconstruirlo
+///
+/// Retorna `false` si la cuenta activa no tiene un item con marca (el menú aún
+/// no existe, o la cuenta era inactiva y se muestra con su avatar); en ese
+/// caso hay que reconstruir el menú.
+pub fn sync_account_checks<R: Runtime>(app: &AppHandle<R>, active: Option<&str>) -> bool {
+    let Some(menu) = app.menu() else {
+        return false;
+    };
+
+    let active_id = active.map(account_menu_id);
+
+    match menu.items().and_then(|items| sync_checks(items, active_id.as_deref())) {
+        Ok(found) => found,
+        Err(e) => {
+            tracing::error!("Failed to update account checkmarks: {}", e);
+            false
+        }
+    }
+}
+
+/// Recorre los items (y submenús) marcando solo el item `active_id`
+///
+/// Retorna si `active_id` tenía un item con marca.
+fn sync_checks<R: Runtime>(items: Vec<MenuItemKind<R>>, active_id: Option<&str>) -> tauri::Result<bool> {
+    let mut found = false;
+
+    for item in items {
+        if let Some(check) = item.as_check_menuitem() {
+            let id = check.id().as_ref();
+            if id.starts_with(ACCOUNTS_ACCOUNT_PREFIX) {
+                let active = Some(id) == active_id;
+                check.set_checked(active)?;
+                found |= active;
+            }
+        } else if let Some(submenu) = item.as_submenu() {
+            found |= sync_checks(submenu.items()?, active_id)?;
+        }
+    }
+
+    Ok(found)
+}
+
 /// Menú principal de la aplicación (X)
 fn build_app_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    let settings = MenuItemBuilder::new("Ajustes...")
+    let settings = MenuItemBuilder::new(tr("menu.app.settings"))
         .id(APP_PREFERENCES)
         .accelerator("CmdOrCtrl+,")
         .build(app)?;
 
-    // Dark mode toggle no funciona con ventana directa a X.com - usar settings de X
+    let appearance = build_appearance_menu(app)?;
 
-    let switch_account = MenuItemBuilder::new("Cambiar de Cuenta")
+    let switch_account = MenuItemBuilder::new(tr("menu.app.switch_account"))
         .id(APP_SWITCH_ACCOUNT)
         .build(app)?;
 
@@ -59,6 +122,7 @@ fn build_app_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::
         .about(Some(about_metadata))
         .separator()
         .item(&settings)
+        .item(&appearance)
         .separator()
         .services()
         .separator()
@@ -72,33 +136,87 @@ fn build_app_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::
         .build()
 }
 
+/// Submenú Apariencia, con la opción actual marcada
+fn build_appearance_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
+    let current = crate::appearance::current();
+    let item = |id: &str, key: &str, appearance: Appearance| {
+        CheckMenuItemBuilder::new(tr(key))
+            .id(id)
+            .checked(current == appearance)
+            .build(app)
+    };
+
+    // Recorre sistema → oscura → clara
+    let toggle = MenuItemBuilder::new(tr("menu.app.appearance.toggle"))
+        .id(APP_TOGGLE_DARK_MODE)
+        .accelerator("CmdOrCtrl+Shift+D")
+        .build(app)?;
+
+    SubmenuBuilder::new(app, tr("menu.app.appearance"))
+        .item(&item(APP_APPEARANCE_SYSTEM, "menu.app.appearance.system", Appearance::System)?)
+        .separator()
+        .item(&item(APP_APPEARANCE_LIGHT, "menu.app.appearance.light", Appearance::Light)?)
+        .item(&item(APP_APPEARANCE_DARK, "menu.app.appearance.dark", Appearance::Dark)?)
+        .separator()
+        .item(&toggle)
+        .build()
+}
+
+/// Submenú Ayuda > Canal de actualizaciones, con el canal guardado marcado
+fn build_update_channel_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
+    let current = crate::updates::load_update_channel(&app.state::<AccountsManager>());
+    let item = |id: &str, key: &str, channel: UpdateChannel| {
+        CheckMenuItemBuilder::new(tr(key))
+            .id(id)
+            .checked(current == channel)
+            .build(app)
+    };
+
+    SubmenuBuilder::new(app, tr("menu.help.update_channel"))
+        .item(&item(HELP_UPDATE_CHANNEL_STABLE, "menu.help.update_channel.stable", UpdateChannel::Stable)?)
+        .item(&item(HELP_UPDATE_CHANNEL_BETA, "menu.help.update_channel.beta", UpdateChannel::Beta)?)
+        .build()
+}
+
 /// Menú Archivo
 fn build_file_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    let new_post = MenuItemBuilder::new("Nueva Publicación")
+    let new_post = MenuItemBuilder::new(tr("menu.file.new_post"))
         .id(FILE_NEW_POST)
         .accelerator("CmdOrCtrl+N")
         .build(app)?;
 
-    let save = MenuItemBuilder::new("Guardar")
+    let save = MenuItemBuilder::new(tr("menu.file.save"))
         .id(FILE_SAVE)
         .accelerator("CmdOrCtrl+S")
         .enabled(false)
         .build(app)?;
 
-    let close_window = MenuItemBuilder::new("Cerrar")
+    let export_pdf = MenuItemBuilder::new(tr("menu.file.export_pdf"))
+        .id(FILE_EXPORT_PDF)
+        .build(app)?;
+
+    let print = MenuItemBuilder::new(tr("menu.file.print"))
+        .id(FILE_PRINT)
+        .accelerator("CmdOrCtrl+P")
+        .build(app)?;
+
+    let close_window = MenuItemBuilder::new(tr("menu.file.close"))
         .id(FILE_CLOSE_WINDOW)
         .accelerator("CmdOrCtrl+W")
         .build(app)?;
 
-    let close_all = MenuItemBuilder::new("Cerrar Todo")
+    let close_all = MenuItemBuilder::new(tr("menu.file.close_all"))
         .id(FILE_CLOSE_ALL)
         .accelerator("CmdOrCtrl+Shift+W")
         .build(app)?;
 
-    SubmenuBuilder::new(app, "Archivo")
+    SubmenuBuilder::new(app, tr("menu.file"))
         .item(&new_post)
         .separator()
         .item(&save)
+        .item(&export_pdf)
+        .separator()
+        .item(&print)
         .separator()
         .item(&close_window)
         .item(&close_all)
@@ -107,80 +225,185 @@ fn build_file_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu:
 
 /// Menú Edición (con items nativos de macOS)
 fn build_edit_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    let find = MenuItemBuilder::new("Buscar")
+    let find = MenuItemBuilder::new(tr("menu.edit.find"))
         .id(EDIT_FIND)
         .accelerator("CmdOrCtrl+F")
         .build(app)?;
 
-    SubmenuBuilder::new(app, "Edición")
+    let find_next = MenuItemBuilder::new(tr("menu.edit.find_next"))
+        .id(EDIT_FIND_NEXT)
+        .accelerator("CmdOrCtrl+G")
+        .build(app)?;
+
+    let find_previous = MenuItemBuilder::new(tr("menu.edit.find_previous"))
+        .id(EDIT_FIND_PREVIOUS)
+        .accelerator("CmdOrCtrl+Shift+G")
+        .build(app)?;
+
+    // La búsqueda de X (Explorar), distinta de buscar en la página
+    let search_x = MenuItemBuilder::new(tr("menu.edit.search_x"))
+        .id(EDIT_SEARCH_X)
+        .accelerator("CmdOrCtrl+Shift+F")
+        .build(app)?;
+
+    let copy_url = MenuItemBuilder::new(tr("menu.edit.copy_url"))
+        .id(COPY_CURRENT_URL)
+        .accelerator("CmdOrCtrl+Shift+C")
+        .build(app)?;
+
+    SubmenuBuilder::new(app, tr("menu.edit"))
         .undo()
         .redo()
         .separator()
         .cut()
         .copy()
+        .item(&copy_url)
         .paste()
         .select_all()
         .separator()
         .item(&find)
+        .item(&find_next)
+        .item(&find_previous)
+        .item(&search_x)
         .build()
 }
 
 /// Menú Cuentas (dinámico, se reconstruye al cambiar)
 fn build_accounts_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    use crate::accounts;
-
-    let mut submenu = SubmenuBuilder::new(app, "Cuentas");
+    let mut submenu = SubmenuBuilder::new(app, tr("menu.accounts"));
 
     // Obtener lista de cuentas
-    match accounts::list_accounts() {
+    let manager = app.state::<AccountsManager>();
+
+    match manager.list_accounts(true) {
         Ok(accounts_list) => {
-            let active = accounts::get_active_account().ok().flatten();
+            let active = manager.get_active_account().ok().flatten();
 
             if accounts_list.is_empty() {
                 // Sin cuentas - solo mostrar "Agregar Cuenta..."
-                let add = MenuItemBuilder::new("Agregar Cuenta...")
+                let add = MenuItemBuilder::new(tr("menu.accounts.add"))
                     .id(ACCOUNTS_ADD)
                     .accelerator("CmdOrCtrl+Shift+N")
                     .build(app)?;
                 submenu = submenu.item(&add);
             } else {
-                // Mostrar cuentas (máximo 10)
-                for account_info in accounts_list.iter().take(10) {
-                    let is_active = active.as_ref() == Some(&account_info.username);
-                    let label = if is_active {
-                        format!("✓ @{}", account_info.username)
-                    } else {
-                        format!("@{}", account_info.username)
-                    };
-
-                    let item = MenuItemBuilder::new(&label)
-                        .id(account_menu_id(&account_info.username))
-                        .build(app)?;
-
+                // list_accounts ya devuelve las cuentas en el orden elegido por el
+                // usuario. Se muestran todas hasta el límite de cuentas; las que lo
+                // superen (si se bajó el límite) van al submenú "Más cuentas…"
+                let max_accounts = manager.max_accounts().unwrap_or(DEFAULT_MAX_ACCOUNTS);
+                let (inline, overflow) = split_inline_accounts(
+                    &accounts_list,
+                    active.as_deref(),
+                    max_accounts,
+                );
+
+                let separator_at = pinned_separator_index(&inline);
+                let data_dir = app.path().app_data_dir().ok();
+
+                // Cmd+Ctrl+1..9 siguen a las primeras cuentas tal como se muestran;
+                // al reconstruir el menú tras cada cambio se reasignan desde cero
+                for (position, account_info) in inline.into_iter().enumerate() {
+                    if Some(position) == separator_at {
+                        submenu = submenu.separator();
+                    }
+                    let item = build_account_item(
+                        app,
+                        account_info,
+                        active.as_deref(),
+                        data_dir.as_deref(),
+                        account_accelerator(position),
+                    )?;
                     submenu = submenu.item(&item);
                 }
 
+                if !overflow.is_empty() {
+                    let mut more = SubmenuBuilder::new(app, tr("menu.accounts.more"));
+                    for account_info in overflow {
+                        let item = build_account_item(app, account_info, active.as_deref(), data_dir.as_deref(), None)?;
+                        more = more.item(&item);
+                    }
+                    submenu = submenu.item(&more.build()?);
+                }
+
+                // Las archivadas no se pueden activar: al pulsarlas se desarchivan
+                let archived: Vec<&AccountInfo> = accounts_list.iter().filter(|a| a.archived).collect();
+                if !archived.is_empty() {
+                    let mut archived_menu = SubmenuBuilder::new(app, tr("menu.accounts.archived"));
+                    for account_info in archived {
+                        let item = MenuItemBuilder::new(account_label(account_info, false))
+                            .id(unarchive_menu_id(&account_info.username))
+                            .build(app)?;
+                        archived_menu = archived_menu.item(&item);
+                    }
+                    submenu = submenu.item(&archived_menu.build()?);
+                }
+
                 // Separador y opciones de gestión
                 submenu = submenu.separator();
 
-                let add = MenuItemBuilder::new("Agregar Cuenta...")
+                let add = MenuItemBuilder::new(tr("menu.accounts.add"))
                     .id(ACCOUNTS_ADD)
                     .accelerator("CmdOrCtrl+Shift+N")
                     .build(app)?;
 
-                let delete = MenuItemBuilder::new("Eliminar Cuenta Activa")
+                // Sin atajo: Cmd+Backspace borra texto en los campos de X y era
+                // fácil eliminar la cuenta sin querer
+                let delete = MenuItemBuilder::new(tr("menu.accounts.delete_active"))
                     .id(ACCOUNTS_DELETE_ACTIVE)
-                    .accelerator("CmdOrCtrl+Backspace")
                     .enabled(active.is_some())
                     .build(app)?;
 
-                submenu = submenu.item(&add).item(&delete);
+                // Eliminar cualquier cuenta sin tener que activarla antes
+                let mut delete_menu = SubmenuBuilder::new(app, tr("menu.accounts.delete"));
+                for account_info in &accounts_list {
+                    let item = MenuItemBuilder::new(account_label(account_info, false))
+                        .id(delete_menu_id(&account_info.username))
+                        .build(app)?;
+                    delete_menu = delete_menu.item(&item);
+                }
+
+                let duplicate = MenuItemBuilder::new(tr("menu.accounts.duplicate_window"))
+                    .id(ACCOUNTS_DUPLICATE_WINDOW)
+                    .enabled(active.is_some())
+                    .build(app)?;
+
+                let manage = MenuItemBuilder::new(tr("menu.accounts.manage"))
+                    .id(ACCOUNTS_MANAGE)
+                    .build(app)?;
+
+                submenu = submenu
+                    .item(&add)
+                    .item(&manage)
+                    .item(&delete)
+                    .item(&delete_menu.build()?)
+                    .separator()
+                    .item(&duplicate);
             }
+
+            // La ventana de incógnito no depende de ninguna cuenta
+            let incognito = MenuItemBuilder::new(tr("menu.accounts.incognito_window"))
+                .id(ACCOUNTS_INCOGNITO_WINDOW)
+                .accelerator("CmdOrCtrl+Alt+N")
+                .build(app)?;
+
+            submenu = submenu.item(&incognito);
+
+            // Copias de seguridad (importar también tiene sentido sin cuentas)
+            let export = MenuItemBuilder::new(tr("menu.accounts.export"))
+                .id(ACCOUNTS_EXPORT)
+                .enabled(!accounts_list.is_empty())
+                .build(app)?;
+
+            let import = MenuItemBuilder::new(tr("menu.accounts.import"))
+                .id(ACCOUNTS_IMPORT)
+                .build(app)?;
+
+            submenu = submenu.separator().item(&export).item(&import);
         }
         Err(e) => {
             tracing::error!("Failed to load accounts: {}", e);
             // En caso de error, mostrar solo "Agregar Cuenta..."
-            let add = MenuItemBuilder::new("Agregar Cuenta...")
+            let add = MenuItemBuilder::new(tr("menu.accounts.add"))
                 .id(ACCOUNTS_ADD)
                 .build(app)?;
             submenu = submenu.item(&add);
@@ -190,30 +413,200 @@ fn build_accounts_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::m
     submenu.build()
 }
 
+/// Separa las cuentas entre las mostradas directamente y las del submenú
+/// "Más cuentas…", conservando el orden recibido. Las archivadas no van en
+/// ninguna de las dos.
+///
+/// La cuenta activa siempre queda visible: si cae fuera de las primeras
+/// `inline_limit`, ocupa el último hueco visible.
+fn split_inline_accounts<'a>(
+    accounts: &'a [AccountInfo],
+    active: Option<&str>,
+    inline_limit: usize,
+) -> (Vec<&'a AccountInfo>, Vec<&'a AccountInfo>) {
+    let accounts: Vec<&AccountInfo> = accounts.iter().filter(|a| !a.archived).collect();
+    let active_index = accounts.iter().position(|a| Some(a.username.as_str()) == active);
+
+    let promoted = match active_index {
+        Some(index) if index >= inline_limit => Some(index),
+        _ => None,
+    };
+
+    let regular_slots = if promoted.is_some() {
+        inline_limit.saturating_sub(1)
+    } else {
+        inline_limit
+    };
+
+    let mut inline = Vec::new();
+    let mut overflow = Vec::new();
+
+    for (index, &account) in accounts.iter().enumerate() {
+        if Some(index) == promoted {
+            continue;
+        }
+        if inline.len() < regular_slots {
+            inline.push(account);
+        } else {
+            overflow.push(account);
+        }
+    }
+
+    if let Some(index) = promoted {
+        inline.push(accounts[index]);
+    }
+
+    (inline, overflow)
+}
+
+/// Posición de la primera cuenta no fijada, si hay fijadas antes que ella
+///
+/// Ahí va el separador entre las cuentas fijadas y el resto.
+fn pinned_separator_index(inline: &[&AccountInfo]) -> Option<usize> {
+    let first_unpinned = inline.iter().position(|a| !a.pinned)?;
+    (first_unpinned > 0).then_some(first_unpinned)
+}
+
+/// Número de cuentas con atajo de teclado (Cmd+Ctrl+1..9)
+const ACCOUNT_SHORTCUTS: usize = 9;
+
+/// Atajo para la cuenta en la posición `position` (0-based) del menú
+fn account_accelerator(position: usize) -> Option<String> {
+    (position < ACCOUNT_SHORTCUTS).then(|| format!("Cmd+Ctrl+{}", position + 1))
+}
+
+/// Item de menú para una cuenta
+///
+/// La cuenta activa lleva la marca nativa; el resto muestra su avatar (o un
+/// icono genérico si aún no se ha descargado). macOS no admite icono y marca
+/// en el mismo item. Las miniaturas se generan al descargar el avatar, aquí
+/// solo se lee el archivo.
+///
+/// El atajo dispara el mismo id que el clic, así que `setup_menu_handlers`
+/// lo trata como un cambio de cuenta normal.
+fn build_account_item<R: Runtime>(
+    app: &AppHandle<R>,
+    account_info: &AccountInfo,
+    active: Option<&str>,
+    data_dir: Option<&std::path::Path>,
+    accelerator: Option<String>,
+) -> tauri::Result<MenuItemKind<R>> {
+    let stale = account_info.needs_relogin || is_stale(account_info, unix_now(), STALE_SESSION_DAYS);
+    let label = account_label(account_info, stale);
+    let id = account_menu_id(&account_info.username);
+    // Con la sesión caducada el atajo solo llevaría a la página de login
+    let accelerator = accelerator.filter(|_| !session_expired(account_info));
+
+    if is_active_account(account_info, active) {
+        let mut item = CheckMenuItemBuilder::new(label).id(id).checked(true);
+        if let Some(accelerator) = accelerator {
+            item = item.accelerator(accelerator);
+        }
+        return Ok(MenuItemKind::Check(item.build(app)?));
+    }
+
+    let icon = data_dir.and_then(|dir| crate::profile::avatar_icon(dir, &account_info.uuid));
+    let mut item = IconMenuItemBuilder::new(label).id(id);
+    item = match icon {
+        Some(icon) => item.icon(icon),
+        None => item.native_icon(NativeIcon::User),
+    };
+    if let Some(accelerator) = accelerator {
+        item = item.accelerator(accelerator);
+    }
+
+    Ok(MenuItemKind::Icon(item.build(app)?))
+}
+
+/// Etiqueta de una cuenta en el menú: "Nombre (@handle)" si se conoce el
+/// nombre visible, si no solo el @handle
+///
+/// Las cuentas sin usar en mucho tiempo (posible sesión caducada) o que
+/// necesitan volver a iniciar sesión llevan "⚠️", y las que X ha rechazado
+/// al comprobar su sesión, " (sesión caducada)".
+fn account_label(account_info: &AccountInfo, stale: bool) -> String {
+    let mut name = match account_info.display_name.as_deref() {
+        Some(display_name) if !display_name.is_empty() => {
+            format!("{} (@{})", display_name, account_info.username)
+        }
+        _ => format!("@{}", account_info.username),
+    };
+
+    if let Some(label) = account_info.label.as_deref() {
+        name = format!("{} {}", label, name);
+    }
+
+    if stale {
+        name = format!("⚠️ {}", name);
+    }
+
+    if session_expired(account_info) {
+        name = format!("{} {}", name, tr("menu.accounts.session_expired"));
+    }
+
+    name
+}
+
+/// Indica si la última comprobación de la sesión de la cuenta falló
+fn session_expired(account_info: &AccountInfo) -> bool {
+    account_info.session_valid == Some(false)
+}
+
+/// Indica si el item de `account_info` debe aparecer marcado
+fn is_active_account(account_info: &AccountInfo, active: Option<&str>) -> bool {
+    active == Some(account_info.username.as_str())
+}
+
 /// Menú Visualización
 fn build_view_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    let reload = MenuItemBuilder::new("Recargar")
+    let reload = MenuItemBuilder::new(tr("menu.view.reload"))
         .id(VIEW_RELOAD)
         .accelerator("CmdOrCtrl+R")
         .build(app)?;
 
-    let fullscreen = MenuItemBuilder::new("Pantalla Completa")
+    let reload_ignoring_cache = MenuItemBuilder::new(tr("menu.view.reload_ignoring_cache"))
+        .id(VIEW_RELOAD_IGNORING_CACHE)
+        .accelerator("CmdOrCtrl+Shift+R")
+        .build(app)?;
+
+    let fullscreen = MenuItemBuilder::new(tr("menu.view.fullscreen"))
         .id(VIEW_FULLSCREEN)
         .accelerator("Ctrl+Cmd+F")
         .build(app)?;
 
-    // Zoom y text size no funcionan con ventana directa a X.com por CORS
-    // Se pueden usar los controles nativos de X.com
+    // Zoom nativo del WebView (no depende del origen de la página)
+    let zoom_in = MenuItemBuilder::new(tr("menu.view.zoom_in"))
+        .id(VIEW_ZOOM_IN)
+        .accelerator("CmdOrCtrl+Plus")
+        .build(app)?;
+
+    let zoom_out = MenuItemBuilder::new(tr("menu.view.zoom_out"))
+        .id(VIEW_ZOOM_OUT)
+        .accelerator("CmdOrCtrl+-")
+        .build(app)?;
 
-    SubmenuBuilder::new(app, "Visualización")
+    let zoom_reset = MenuItemBuilder::new(tr("menu.view.zoom_reset"))
+        .id(VIEW_ZOOM_RESET)
+        .accelerator("CmdOrCtrl+0")
+        .build(app)?;
+
+    // El tamaño de texto sigue sin funcionar con ventana directa a X.com por CORS
+
+    SubmenuBuilder::new(app, tr("menu.view"))
         .item(&reload)
+        .item(&reload_ignoring_cache)
+        .separator()
+        .item(&zoom_in)
+        .item(&zoom_out)
+        .item(&zoom_reset)
+        .separator()
         .item(&fullscreen)
         .build()
 }
 
 /// Menú de Navegación (específico de X)
 fn build_navigation_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    let explore = MenuItemBuilder::new("Explorar")
+    let explore = MenuItemBuilder::new(tr("menu.navigation.explore"))
         .id(NAV_EXPLORE)
         .accelerator("CmdOrCtrl+1")
         .build(app)?;
@@ -223,33 +616,58 @@ fn build_navigation_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri:
         .accelerator("CmdOrCtrl+2")
         .build(app)?;
 
-    let notifications = MenuItemBuilder::new("Notificaciones")
+    let notifications = MenuItemBuilder::new(tr("menu.navigation.notifications"))
         .id(NAV_NOTIFICATIONS)
         .accelerator("CmdOrCtrl+3")
         .build(app)?;
 
-    let messages = MenuItemBuilder::new("Mensajes")
+    let messages = MenuItemBuilder::new(tr("menu.navigation.messages"))
         .id(NAV_MESSAGES)
         .accelerator("CmdOrCtrl+4")
         .build(app)?;
 
-    let bookmarks = MenuItemBuilder::new("Elementos Guardados")
+    let bookmarks = MenuItemBuilder::new(tr("menu.navigation.bookmarks"))
         .id(NAV_BOOKMARKS)
         .accelerator("CmdOrCtrl+L")
         .build(app)?;
 
-    let lists = MenuItemBuilder::new("Listas")
+    let lists = MenuItemBuilder::new(tr("menu.navigation.lists"))
         .id(NAV_LISTS)
         .build(app)?;
 
-    let profile = MenuItemBuilder::new("Perfil")
+    let profile = MenuItemBuilder::new(tr("menu.navigation.profile"))
         .id(NAV_PROFILE)
-        .accelerator("CmdOrCtrl+P")
+        .accelerator("CmdOrCtrl+Shift+P")
+        .build(app)?;
+
+    let back = MenuItemBuilder::new(tr("menu.navigation.back"))
+        .id(NAV_BACK)
+        .accelerator("CmdOrCtrl+[")
+        .build(app)?;
+
+    let forward = MenuItemBuilder::new(tr("menu.navigation.forward"))
+        .id(NAV_FORWARD)
+        .accelerator("CmdOrCtrl+]")
+        .build(app)?;
+
+    let scroll_top = MenuItemBuilder::new(tr("menu.navigation.scroll_top"))
+        .id(SCROLL_TOP)
+        .accelerator("CmdOrCtrl+Up")
+        .build(app)?;
+
+    let scroll_bottom = MenuItemBuilder::new(tr("menu.navigation.scroll_bottom"))
+        .id(SCROLL_BOTTOM)
+        .accelerator("CmdOrCtrl+Down")
         .build(app)?;
 
-    // Scroll no funciona con ventana directa a X.com por CORS
+    let scroll_timeline_top = MenuItemBuilder::new(tr("menu.navigation.scroll_timeline_top"))
+        .id(SCROLL_TIMELINE_TOP)
+        .build(app)?;
 
-    SubmenuBuilder::new(app, "Navegación")
+    SubmenuBuilder::new(app, tr("menu.navigation"))
+        .item(&back)
+        .item(&forward)
+        .separator()
         .item(&explore)
         .item(&grok)
         .item(&notifications)
@@ -258,14 +676,23 @@ fn build_navigation_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri:
         .item(&bookmarks)
         .item(&lists)
         .item(&profile)
+        .separator()
+        .item(&scroll_top)
+        .item(&scroll_bottom)
+        .item(&scroll_timeline_top)
         .build()
 }
 
 /// Menú Ventana (Window) - Gestionado por macOS
 fn build_window_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    SubmenuBuilder::new(app, "Ventana")
+    let reset_state = MenuItemBuilder::new(tr("menu.window.reset_state"))
+        .id(WINDOW_RESET_STATE)
+        .build(app)?;
+
+    SubmenuBuilder::new(app, tr("menu.window"))
         .minimize()
         .maximize()
+        .item(&reset_state)
         .separator()
         .close_window()
         .build()
@@ -273,11 +700,282 @@ fn build_window_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::men
 
 /// Menú Ayuda
 fn build_help_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<tauri::menu::Submenu<R>> {
-    let check_updates = MenuItemBuilder::new("Buscar Actualizaciones...")
-        .id("check_updates")
+    let check_updates = MenuItemBuilder::new(tr("menu.help.check_updates"))
+        .id(HELP_CHECK_UPDATES)
+        .build(app)?;
+
+    // Sin gestor de cuentas todavía, lo que se usa es el valor por defecto
+    let auto_update_check = app
+        .try_state::<AccountsManager>()
+        .is_none_or(|manager| manager.settings().update_policy.checks_on_launch());
+    let auto_update_check = CheckMenuItemBuilder::new(tr("menu.help.auto_update_check"))
+        .id(HELP_AUTO_UPDATE_CHECK)
+        .checked(auto_update_check)
         .build(app)?;
 
-    SubmenuBuilder::new(app, "Ayuda")
-        .item(&check_updates)
+    let update_channel = build_update_channel_menu(app)?;
+
+    let rotate_keys = MenuItemBuilder::new(tr("menu.help.rotate_keys"))
+        .id(HELP_ROTATE_KEYS)
+        .build(app)?;
+
+    let cleanup_keychain = MenuItemBuilder::new(tr("menu.help.cleanup_keychain"))
+        .id(HELP_CLEANUP_KEYCHAIN)
+        .build(app)?;
+
+    // Diagnóstico para soporte: al final del menú, sin atajo
+    let verify_credentials = MenuItemBuilder::new(tr("menu.help.verify_credentials"))
+        .id(HELP_VERIFY_CREDENTIALS)
+        .build(app)?;
+
+    let diagnostics = MenuItemBuilder::new(tr("menu.help.diagnostics"))
+        .id(HELP_DIAGNOSTICS)
+        .build(app)?;
+
+    let mut menu = SubmenuBuilder::new(app, tr("menu.help"));
+
+    // Versión nueva encontrada en esta sesión: arriba del todo y destacada
+    // para que no pase desapercibida
+    if let Some(update) = app.try_state::<crate::updates::AvailableUpdate>().and_then(|state| state.get()) {
+        let update_to = MenuItemBuilder::new(format!("⬆️ {} v{}…", tr("menu.help.update_to"), update.latest))
+            .id(update_menu_id(&update.latest))
+            .build(app)?;
+        menu = menu.item(&update_to).separator();
+    }
+
+    menu.item(&check_updates)
+        .item(&auto_update_check)
+        .item(&update_channel)
+        .separator()
+        .item(&rotate_keys)
+        .item(&cleanup_keychain)
+        .separator()
+        .item(&verify_credentials)
+        .item(&diagnostics)
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(count: usize) -> Vec<AccountInfo> {
+        (0..count)
+            .map(|i| AccountInfo {
+                username: format!("user{}", i),
+                uuid: format!("uuid-{}", i),
+                created_at: 0,
+                last_used: 0,
+                last_login: 0,
+                display_name: None,
+                avatar_url: None,
+                order: i as i32,
+                needs_relogin: false,
+                pinned: false,
+                archived: false,
+                label: None,
+                color: None,
+                proxy_url: None,
+                session_valid: None,
+                last_validated: 0,
+            })
+            .collect()
+    }
+
+    fn usernames(list: &[&AccountInfo]) -> Vec<String> {
+        list.iter().map(|a| a.username.clone()).collect()
+    }
+
+    #[test]
+    fn test_split_without_overflow() {
+        let list = accounts(3);
+        let (inline, overflow) = split_inline_accounts(&list, Some("user1"), 10);
+
+        assert_eq!(inline.len(), 3);
+        assert!(overflow.is_empty());
+    }
+
+    #[test]
+    fn test_split_overflow_keeps_order() {
+        let list = accounts(15);
+        let (inline, overflow) = split_inline_accounts(&list, Some("user0"), 10);
+
+        assert_eq!(inline.len(), 10);
+        assert_eq!(usernames(&overflow), vec!["user10", "user11", "user12", "user13", "user14"]);
+    }
+
+    #[test]
+    fn test_split_exactly_at_limit() {
+        let list = accounts(DEFAULT_MAX_ACCOUNTS);
+        let (inline, overflow) = split_inline_accounts(&list, Some("user9"), DEFAULT_MAX_ACCOUNTS);
+
+        assert_eq!(inline.len(), DEFAULT_MAX_ACCOUNTS);
+        assert!(overflow.is_empty());
+        assert_eq!(inline.last().unwrap().username, "user9");
+    }
+
+    #[test]
+    fn test_lowered_limit_keeps_accounts_reachable() {
+        let list = accounts(5);
+        let (inline, overflow) = split_inline_accounts(&list, Some("user4"), 3);
+
+        // Ninguna cuenta desaparece del menú
+        assert_eq!(usernames(&inline), vec!["user0", "user1", "user4"]);
+        assert_eq!(usernames(&overflow), vec!["user2", "user3"]);
+    }
+
+    #[test]
+    fn test_active_account_is_always_inline() {
+        let list = accounts(15);
+        let (inline, overflow) = split_inline_accounts(&list, Some("user12"), 10);
+
+        assert_eq!(inline.len(), 10);
+        assert_eq!(inline.last().unwrap().username, "user12");
+        assert!(!overflow.iter().any(|a| a.username == "user12"));
+        // user9 cede su hueco a la cuenta activa
+        assert_eq!(overflow.first().unwrap().username, "user9");
+        assert_eq!(inline.len() + overflow.len(), 15);
+    }
+
+    #[test]
+    fn test_account_label_prefers_display_name() {
+        let mut list = accounts(1);
+        assert_eq!(account_label(&list[0], false), "@user0");
+
+        list[0].display_name = Some("User Zero".to_string());
+        assert_eq!(account_label(&list[0], false), "User Zero (@user0)");
+        assert_eq!(account_label(&list[0], true), "⚠️ User Zero (@user0)");
+
+        list[0].display_name = Some(String::new());
+        assert_eq!(account_label(&list[0], false), "@user0");
+    }
+
+    #[test]
+    fn test_account_label_marks_expired_session() {
+        let mut list = accounts(1);
+
+        list[0].session_valid = Some(true);
+        assert_eq!(account_label(&list[0], false), "@user0");
+        assert!(!session_expired(&list[0]));
+
+        list[0].session_valid = Some(false);
+        assert_eq!(
+            account_label(&list[0], false),
+            format!("@user0 {}", tr("menu.accounts.session_expired"))
+        );
+        assert!(session_expired(&list[0]));
+    }
+
+    #[test]
+    fn test_first_account_is_checked() {
+        let store: &'static crate::secrets::InMemoryStore = Box::leak(Box::new(crate::secrets::InMemoryStore::new()));
+        let manager = AccountsManager::new(store);
+        manager.add_account("alice", None, None).unwrap();
+
+        let accounts = manager.list_accounts(false).unwrap();
+        let active = manager.get_active_account().unwrap();
+        assert!(is_active_account(&accounts[0], active.as_deref()));
+
+        // La marca ya no forma parte de la etiqueta
+        assert_eq!(account_label(&accounts[0], false), "@alice");
+
+        manager.add_account("bob", None, None).unwrap();
+        let accounts = manager.list_accounts(false).unwrap();
+        let checked: Vec<&str> = accounts
+            .iter()
+            .filter(|a| is_active_account(a, active.as_deref()))
+            .map(|a| a.username.as_str())
+            .collect();
+        assert_eq!(checked, vec!["alice"]);
+    }
+
+    #[test]
+    fn test_account_label_shows_user_label() {
+        let mut list = accounts(1);
+        list[0].label = Some("🏢".to_string());
+        assert_eq!(account_label(&list[0], false), "🏢 @user0");
+        assert_eq!(account_label(&list[0], true), "⚠️ 🏢 @user0");
+
+        list[0].label = None;
+        assert_eq!(account_label(&list[0], false), "@user0");
+    }
+
+    /// Atajos tal como los asigna `build_accounts_menu`
+    fn shortcuts(list: &[AccountInfo], active: Option<&str>) -> Vec<(String, String)> {
+        let (inline, _) = split_inline_accounts(list, active, 10);
+
+        inline
+            .iter()
+            .enumerate()
+            .filter_map(|(position, account)| {
+                account_accelerator(position).map(|accel| (accel, account_menu_id(&account.username)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_account_shortcuts_follow_display_order() {
+        let list = accounts(12);
+        let shortcuts = shortcuts(&list, Some("user11"));
+
+        assert_eq!(shortcuts.len(), 9);
+        assert_eq!(shortcuts[0], ("Cmd+Ctrl+1".to_string(), account_menu_id("user0")));
+        assert_eq!(shortcuts[8], ("Cmd+Ctrl+9".to_string(), account_menu_id("user8")));
+        // La cuenta activa promovida ocupa el décimo hueco, sin atajo
+        assert_eq!(account_accelerator(9), None);
+    }
+
+    #[test]
+    fn test_account_shortcuts_skip_archived_and_follow_changes() {
+        let mut list = accounts(4);
+        list[1].archived = true;
+
+        let ids = |shortcuts: Vec<(String, String)>| shortcuts.into_iter().map(|(_, id)| id).collect::<Vec<_>>();
+
+        assert_eq!(
+            shortcuts(&list, None),
+            vec![
+                ("Cmd+Ctrl+1".to_string(), account_menu_id("user0")),
+                ("Cmd+Ctrl+2".to_string(), account_menu_id("user2")),
+                ("Cmd+Ctrl+3".to_string(), account_menu_id("user3")),
+            ]
+        );
+
+        // Tras eliminar una cuenta los atajos se recalculan sin huecos
+        list.remove(0);
+        assert_eq!(ids(shortcuts(&list, None)), vec![account_menu_id("user2"), account_menu_id("user3")]);
+
+        // Tras reordenar (fijar user3 arriba) el 1 apunta a la nueva primera cuenta
+        list.rotate_right(1);
+        assert_eq!(shortcuts(&list, None)[0], ("Cmd+Ctrl+1".to_string(), account_menu_id("user3")));
+    }
+
+    #[test]
+    fn test_separator_after_pinned_accounts() {
+        let mut list = accounts(4);
+        let (inline, _) = split_inline_accounts(&list, None, 10);
+        assert_eq!(pinned_separator_index(&inline), None);
+
+        list[0].pinned = true;
+        list[1].pinned = true;
+        let (inline, _) = split_inline_accounts(&list, None, 10);
+        assert_eq!(pinned_separator_index(&inline), Some(2));
+
+        // Todas fijadas: no hay nada que separar
+        list.iter_mut().for_each(|a| a.pinned = true);
+        let (inline, _) = split_inline_accounts(&list, None, 10);
+        assert_eq!(pinned_separator_index(&inline), None);
+    }
+
+    #[test]
+    fn test_overflow_accounts_have_valid_menu_ids() {
+        let list = accounts(12);
+        let (_, overflow) = split_inline_accounts(&list, None, 10);
+
+        for account in overflow {
+            let id = account_menu_id(&account.username);
+            assert!(id.starts_with(ACCOUNTS_ACCOUNT_PREFIX));
+            assert_eq!(id.trim_start_matches(ACCOUNTS_ACCOUNT_PREFIX), account.username);
+        }
+    }
+}
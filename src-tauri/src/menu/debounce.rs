@@ -0,0 +1,86 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Agrupa reconstrucciones del menú disparadas en ráfaga (p. ej. al importar
+// varias cuentas) en una sola, para evitar parpadeos.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime};
+
+/// Ventana de espera tras el último cambio antes de reconstruir
+pub const REBUILD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Debouncer "trailing edge": solo se ejecuta la última acción programada
+/// si no llega otra dentro de `delay`
+pub struct Debouncer {
+    generation: Arc<AtomicU64>,
+    delay: Duration,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            generation: Arc::new(AtomicU64::new(0)),
+            delay,
+        }
+    }
+
+    /// Programa `action`; cancela cualquier acción pendiente anterior
+    pub fn schedule<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        action: impl FnOnce(&AppHandle<R>) + Send + 'static,
+    ) {
+        let scheduled = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let generation = self.generation.clone();
+        let delay = self.delay;
+        let app = app.clone();
+
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            // Si se ha programado otra acción entretanto, esta se descarta
+            if generation.load(Ordering::SeqCst) == scheduled {
+                action(&app);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_burst_triggers_single_rebuild() {
+        let app = tauri::test::mock_app();
+        let debouncer = Debouncer::new(Duration::from_millis(50));
+        let rebuilds = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..3 {
+            let rebuilds = rebuilds.clone();
+            debouncer.schedule(app.handle(), move |_| {
+                rebuilds.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 1);
+
+        // Un cambio posterior a la ventana dispara otra reconstrucción
+        let counter = rebuilds.clone();
+        debouncer.schedule(app.handle(), move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,137 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Buscar en la página (Cmd+F, Cmd+G, Cmd+Shift+G)
+//
+// Cmd+F inyecta en la página una barra de búsqueda (find_overlay.js) que
+// busca con `window.find` y muestra cuántas coincidencias hay. La barra pide
+// cada búsqueda con el comando `find_in_page`, el mismo que usan Cmd+G y
+// Cmd+Shift+G desde el menú.
+
+use serde::Deserialize;
+use tauri::{AppHandle, Runtime, WebviewWindow};
+
+use crate::error::AppError;
+
+/// Dominios de X en los que se puede abrir la búsqueda en la página
+const FIND_HOSTS: &[&str] = &["x.com", "twitter.com"];
+/// Barra de búsqueda; define `window.__xmacFind` (una sola vez por página)
+const FIND_OVERLAY_JS: &str = include_str!("find_overlay.js");
+
+/// Sentido de la búsqueda
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FindDirection {
+    Next,
+    Previous,
+}
+
+/// Qué hace Cmd+F según la página que muestra la ventana
+#[derive(Debug, PartialEq)]
+enum FindAction {
+    /// Página de X: se inyecta la barra de búsqueda
+    InPage,
+    /// Otro origen: no se inyecta JS en páginas ajenas
+    Unsupported,
+}
+
+/// Decide si se puede buscar en la página de `url`
+///
+/// Solo se inyecta JS en las páginas de X servidas por HTTPS, las mismas en
+/// las que ya se ejecuta el detector de login.
+fn find_action(url: &tauri::Url) -> FindAction {
+    let host = url.host_str().unwrap_or_default();
+    let is_x = FIND_HOSTS
+        .iter()
+        .any(|allowed| host == *allowed || host.ends_with(&format!(".{}", allowed)));
+
+    if url.scheme() == "https" && is_x {
+        FindAction::InPage
+    } else {
+        FindAction::Unsupported
+    }
+}
+
+/// JS que busca `query` (o, sin ella, lo último buscado) con la barra
+///
+/// El texto va como literal JSON, así que comillas o barras no pueden salir
+/// del string.
+fn find_js(query: Option<&str>, direction: FindDirection) -> String {
+    let query = serde_json::to_string(&query).expect("strings always serialize");
+    format!(
+        "{}\nwindow.__xmacFind.search({}, {});",
+        FIND_OVERLAY_JS,
+        query,
+        direction == FindDirection::Previous
+    )
+}
+
+/// Ventana con foco (o la principal) si muestra una página de X
+fn find_window<R: Runtime>(app: &AppHandle<R>) -> Result<WebviewWindow<R>, AppError> {
+    let window = crate::window::focused_or_main(app).ok_or("No window to find in")?;
+    let url = window
+        .url()
+        .map_err(|e| AppError::Internal(format!("Failed to read URL for find: {}", e)))?;
+
+    match find_action(&url) {
+        FindAction::InPage => Ok(window),
+        FindAction::Unsupported => Err(format!("Find in page is only available on X pages, not on {}", url).into()),
+    }
+}
+
+/// Muestra la barra de búsqueda (Cmd+F)
+pub fn show_overlay<R: Runtime>(app: &AppHandle<R>) -> Result<(), AppError> {
+    find_window(app)?
+        .eval(format!("{}\nwindow.__xmacFind.show();", FIND_OVERLAY_JS))
+        .map_err(|e| AppError::Internal(format!("Failed to open find in page: {}", e)))
+}
+
+/// Busca `query` en la página (o repite la última búsqueda si es `None`) y
+/// actualiza el contador de la barra
+pub fn find_in_page<R: Runtime>(app: &AppHandle<R>, query: Option<&str>, direction: FindDirection) -> Result<(), AppError> {
+    find_window(app)?
+        .eval(find_js(query, direction))
+        .map_err(|e| AppError::Internal(format!("Failed to find in page: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_only_on_x_pages() {
+        let action = |url: &str| find_action(&url.parse().unwrap());
+
+        assert_eq!(action("https://x.com/home"), FindAction::InPage);
+        assert_eq!(action("https://mobile.twitter.com/i/bookmarks"), FindAction::InPage);
+
+        assert_eq!(action("http://x.com/home"), FindAction::Unsupported);
+        assert_eq!(action("https://notx.com/"), FindAction::Unsupported);
+        assert_eq!(action("https://x.com.evil.example/"), FindAction::Unsupported);
+        assert_eq!(action("about:blank"), FindAction::Unsupported);
+    }
+
+    #[test]
+    fn test_find_js() {
+        let call = |query: Option<&str>, direction| find_js(query, direction).lines().last().unwrap().to_string();
+
+        assert_eq!(call(Some("hilo"), FindDirection::Next), r#"window.__xmacFind.search("hilo", false);"#);
+        assert_eq!(call(None, FindDirection::Previous), "window.__xmacFind.search(null, true);");
+        // Las comillas quedan dentro del literal
+        assert_eq!(
+            call(Some("'); alert(1); //\""), FindDirection::Next),
+            r#"window.__xmacFind.search("'); alert(1); //\"", false);"#
+        );
+        assert!(find_js(Some("x"), FindDirection::Next).starts_with(FIND_OVERLAY_JS));
+    }
+
+    #[test]
+    fn test_direction_from_frontend() {
+        assert_eq!(serde_json::from_str::<FindDirection>(r#""next""#).unwrap(), FindDirection::Next);
+        assert_eq!(serde_json::from_str::<FindDirection>(r#""previous""#).unwrap(), FindDirection::Previous);
+        assert!(serde_json::from_str::<FindDirection>(r#""up""#).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// Buscador en la página de X (Cmd+F), inyectado por find.rs
+//
+// Barra flotante con el texto, el contador de coincidencias y botones de
+// anterior/siguiente. Va dentro de un shadow root cerrado: ni los estilos de X
+// la afectan ni `window.find` encuentra su propio texto.
+(() => {
+    if (window.__xmacFind) {
+        return;
+    }
+
+    const invoke = window.__TAURI__?.core?.invoke;
+    let host = null;
+    let input = null;
+    let counter = null;
+    let lastQuery = '';
+    let current = 0;
+
+    const STYLE = `
+        .bar { display: flex; align-items: center; gap: 6px; padding: 6px 8px;
+               background: rgba(22, 24, 28, 0.95); border: 1px solid #2f3336;
+               border-radius: 10px; box-shadow: 0 4px 16px rgba(0, 0, 0, 0.4);
+               font: 13px -apple-system, BlinkMacSystemFont, sans-serif; color: #e7e9ea; }
+        input { width: 200px; padding: 4px 8px; border: 1px solid #536471; border-radius: 6px;
+                background: #000; color: #e7e9ea; font: inherit; outline: none; }
+        input:focus { border-color: #1d9bf0; }
+        .count { min-width: 44px; text-align: center; color: #71767b; }
+        button { width: 24px; height: 24px; border: none; border-radius: 6px;
+                 background: transparent; color: #e7e9ea; font: 16px sans-serif; cursor: pointer; }
+        button:hover { background: #2f3336; }
+    `;
+
+    /** Coincidencias (sin distinguir mayúsculas) en el texto visible */
+    function countMatches(query) {
+        const text = (document.body?.innerText || '').toLowerCase();
+        const needle = query.toLowerCase();
+        let count = 0;
+        for (let i = text.indexOf(needle); i !== -1; i = text.indexOf(needle, i + needle.length)) {
+            count++;
+        }
+        return count;
+    }
+
+    /** Pide la búsqueda a la app (`find_in_page`), o la hace aquí si no hay IPC */
+    function request(backwards) {
+        const query = input.value;
+        if (!invoke) {
+            search(query, backwards);
+            return;
+        }
+
+        invoke('find_in_page', { query, direction: backwards ? 'previous' : 'next' })
+            .catch(() => search(query, backwards));
+    }
+
+    function build() {
+        host = document.createElement('div');
+        host.style.cssText = 'position: fixed; top: 12px; right: 12px; z-index: 2147483647;';
+
+        const root = host.attachShadow({ mode: 'closed' });
+        root.innerHTML = `<style>${STYLE}</style>
+            <div class="bar">
+                <input type="search" placeholder="Buscar en la página" autocomplete="off">
+                <span class="count"></span>
+                <button class="previous" title="Anterior (⇧⌘G)">‹</button>
+                <button class="next" title="Siguiente (⌘G)">›</button>
+                <button class="close" title="Cerrar (Esc)">×</button>
+            </div>`;
+
+        input = root.querySelector('input');
+        counter = root.querySelector('.count');
+
+        input.addEventListener('keydown', (event) => {
+            // Que X no interprete lo que se escribe como atajos de teclado
+            event.stopPropagation();
+
+            if (event.key === 'Enter') {
+                event.preventDefault();
+                request(event.shiftKey);
+            } else if (event.key === 'Escape') {
+                event.preventDefault();
+                hide();
+            }
+        });
+        root.querySelector('.previous').addEventListener('click', () => request(true));
+        root.querySelector('.next').addEventListener('click', () => request(false));
+        root.querySelector('.close').addEventListener('click', hide);
+
+        document.addEventListener('keydown', (event) => {
+            if (event.key === 'Escape' && host.isConnected) {
+                hide();
+            }
+        });
+    }
+
+    function show() {
+        if (!host) {
+            build();
+        }
+        if (!host.isConnected) {
+            document.body.appendChild(host);
+        }
+
+        input.focus();
+        input.select();
+    }
+
+    function hide() {
+        host?.remove();
+        window.getSelection()?.removeAllRanges();
+    }
+
+    /**
+     * Busca `query` (la última si es null, como Cmd+G) y muestra "actual/total"
+     * Sin texto que buscar abre la barra
+     */
+    function search(query, backwards) {
+        query = query ?? lastQuery;
+        if (!query) {
+            show();
+            return;
+        }
+
+        if (query !== lastQuery) {
+            lastQuery = query;
+            current = 0;
+        }
+
+        const total = countMatches(query);
+        if (total > 0 && window.find(query, false, backwards, true, false, false, false)) {
+            current = backwards ? (current <= 1 ? total : current - 1) : (current >= total ? 1 : current + 1);
+        } else {
+            current = 0;
+        }
+
+        if (input && input.value !== query) {
+            input.value = query;
+        }
+        if (counter) {
+            counter.textContent = `${current}/${total}`;
+        }
+    }
+
+    window.__xmacFind = { show, hide, search };
+})();
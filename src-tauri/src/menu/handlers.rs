@@ -6,8 +6,30 @@
 //
 // Event handlers para menús nativos de macOS
 
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, FilePath, MessageDialogButtons, MessageDialogKind};
+use super::find::{self, FindDirection};
 use super::items::*;
+use super::nav_config::{self, NavTarget};
+use super::navigation::{self, History, Scroll};
+use super::print;
+use crate::accounts::AccountsManager;
+use crate::appearance::Appearance;
+use crate::updates::{self, UpdateChannel, UpdateInfo};
+use crate::window::{focused_account_window, focused_or_main};
+use crate::zoom::ZoomChange;
+
+/// Evento que pide al frontend confirmar la limpieza del Keychain
+const CONFIRM_KEYCHAIN_CLEANUP_EVENT: &str = "confirm-keychain-cleanup";
+/// Evento con el resultado del diagnóstico de credenciales
+const CREDENTIALS_DIAGNOSTIC_EVENT: &str = "credentials-diagnostic";
+/// Evento con la ruta elegida para exportar las cuentas
+const ACCOUNTS_EXPORT_EVENT: &str = "accounts-export-requested";
+/// Evento con la ruta de la copia de cuentas a importar
+const ACCOUNTS_IMPORT_EVENT: &str = "accounts-import-requested";
+/// Nombre propuesto para la copia de seguridad de las cuentas
+const BACKUP_FILE_NAME: &str = "x-cuentas.json";
 
 /// Configura los handlers para eventos de menú
 pub fn setup_menu_handlers<R: Runtime>(app: &AppHandle<R>) {
@@ -17,56 +39,43 @@ pub fn setup_menu_handlers<R: Runtime>(app: &AppHandle<R>) {
 
         match event_id {
             // Navegación de X
-            NAV_EXPLORE => navigate_to(app, URL_NAV_EXPLORE),
-            NAV_GROK => navigate_to(app, URL_NAV_GROK),
-            NAV_NOTIFICATIONS => navigate_to(app, URL_NAV_NOTIFICATIONS),
-            NAV_MESSAGES => navigate_to(app, URL_NAV_MESSAGES),
-            NAV_BOOKMARKS => navigate_to(app, URL_NAV_BOOKMARKS),
-            NAV_LISTS => navigate_to(app, URL_NAV_LISTS),
-            NAV_PROFILE => navigate_to(app, URL_NAV_PROFILE),
-
-            // Scroll - no funciona con ventana directa de X.com por CORS
-            SCROLL_TOP => {
-                tracing::warn!("Scroll commands not supported when loading X.com directly");
-            },
-            SCROLL_BOTTOM => {
-                tracing::warn!("Scroll commands not supported when loading X.com directly");
-            },
+            NAV_EXPLORE => navigate_to(app, NavTarget::Explore),
+            NAV_GROK => navigate_to(app, NavTarget::Grok),
+            NAV_NOTIFICATIONS => navigate_to(app, NavTarget::Notifications),
+            NAV_MESSAGES => navigate_to(app, NavTarget::Messages),
+            NAV_BOOKMARKS => navigate_to(app, NavTarget::Bookmarks),
+            NAV_LISTS => navigate_to(app, NavTarget::Lists),
+            NAV_PROFILE => navigate_to(app, NavTarget::Home),
+            NAV_BACK => navigation::go(app, History::Back),
+            NAV_FORWARD => navigation::go(app, History::Forward),
+
+            // Scroll (la ventana es X.com, no un iframe)
+            SCROLL_TOP => navigation::scroll(app, Scroll::Top),
+            SCROLL_BOTTOM => navigation::scroll(app, Scroll::Bottom),
+            SCROLL_TIMELINE_TOP => navigation::scroll(app, Scroll::TimelineTop),
 
             // Vista
             VIEW_RELOAD => {
-                if let Some(window) = app.get_webview_window("main") {
+                if let Some(window) = focused_or_main(app) {
                     let _ = window.eval("location.reload();");
                 }
             },
+            VIEW_RELOAD_IGNORING_CACHE => navigation::reload_ignoring_cache(app),
             VIEW_FULLSCREEN => {
-                if let Some(window) = app.get_webview_window("main") {
+                if let Some(window) = focused_or_main(app) {
                     let _ = window.set_fullscreen(!window.is_fullscreen().unwrap_or(false));
                 }
             },
-            VIEW_ZOOM_IN => {
-                tracing::warn!("Zoom commands not supported when loading X.com directly");
-            },
-            VIEW_ZOOM_OUT => {
-                tracing::warn!("Zoom commands not supported when loading X.com directly");
-            },
-            VIEW_ZOOM_RESET => {
-                tracing::warn!("Zoom commands not supported when loading X.com directly");
-            },
-            VIEW_TEXT_LARGER => {
-                tracing::warn!("Text size commands not supported when loading X.com directly");
-            },
-            VIEW_TEXT_SMALLER => {
-                tracing::warn!("Text size commands not supported when loading X.com directly");
-            },
-            VIEW_TEXT_RESET => {
-                tracing::warn!("Text size commands not supported when loading X.com directly");
-            },
+            VIEW_ZOOM_IN => handle_zoom(app, ZoomChange::In),
+            VIEW_ZOOM_OUT => handle_zoom(app, ZoomChange::Out),
+            VIEW_ZOOM_RESET => handle_zoom(app, ZoomChange::Reset),
 
             // Archivo
-            FILE_NEW_POST => navigate_to(app, URL_COMPOSE),
+            FILE_NEW_POST => handle_new_post(app),
+            FILE_PRINT => print::print_page(app),
+            FILE_EXPORT_PDF => print::export_pdf_dialog(app),
             FILE_CLOSE_WINDOW => {
-                if let Some(window) = app.get_webview_window("main") {
+                if let Some(window) = crate::window::focused_window(app) {
                     let _ = window.close();
                 }
             },
@@ -75,22 +84,46 @@ pub fn setup_menu_handlers<R: Runtime>(app: &AppHandle<R>) {
             },
 
             // App
-            APP_PREFERENCES => navigate_to(app, URL_SETTINGS),
-            APP_TOGGLE_DARK_MODE => {
-                tracing::warn!("Dark mode toggle not supported when loading X.com directly - use X settings");
+            APP_PREFERENCES => navigate_to(app, NavTarget::Settings),
+            APP_APPEARANCE_SYSTEM => handle_appearance(app, Appearance::System),
+            APP_APPEARANCE_LIGHT => handle_appearance(app, Appearance::Light),
+            APP_APPEARANCE_DARK => handle_appearance(app, Appearance::Dark),
+            APP_TOGGLE_DARK_MODE => handle_appearance(app, crate::appearance::current().next()),
+            APP_SWITCH_ACCOUNT => navigate_to(app, NavTarget::Home),
+
+            // Ventana: olvidar el tamaño y la posición guardados
+            WINDOW_RESET_STATE => {
+                if let Err(e) = crate::window_state::reset(app) {
+                    tracing::error!("Failed to reset window state: {}", e);
+                }
             },
-            APP_SWITCH_ACCOUNT => navigate_to(app, URL_NAV_PROFILE),
 
-            // Búsqueda
-            EDIT_FIND => navigate_to(app, URL_NAV_EXPLORE),
+            // Copiar la URL de la ventana con foco (p. ej. la publicación abierta)
+            COPY_CURRENT_URL => handle_copy_current_url(app),
 
-            // Check updates - Abrir directamente la página de releases
-            "check_updates" => {
-                let _ = tauri_plugin_opener::open_url(
-                    "https://github.com/686f6c61/Xcom-mac-silicon/releases",
-                    None::<String>,
-                );
-            },
+            // Búsqueda en la página
+            EDIT_FIND => handle_find(app, None),
+            EDIT_FIND_NEXT => handle_find(app, Some(FindDirection::Next)),
+            EDIT_FIND_PREVIOUS => handle_find(app, Some(FindDirection::Previous)),
+            EDIT_SEARCH_X => navigate_to(app, NavTarget::Explore),
+
+            // Buscar actualizaciones: diálogo nativo con el resultado
+            HELP_CHECK_UPDATES => handle_check_updates(app),
+            HELP_UPDATE_CHANNEL_STABLE => handle_update_channel(app, UpdateChannel::Stable),
+            HELP_UPDATE_CHANNEL_BETA => handle_update_channel(app, UpdateChannel::Beta),
+            HELP_AUTO_UPDATE_CHECK => handle_auto_update_check(app),
+
+            // Rotación de claves de cifrado
+            HELP_ROTATE_KEYS => handle_rotate_keys(app),
+
+            // Limpieza de entradas huérfanas del Keychain
+            HELP_CLEANUP_KEYCHAIN => handle_cleanup_keychain(app),
+
+            // Diagnóstico de credenciales
+            HELP_VERIFY_CREDENTIALS => handle_verify_credentials(app),
+
+            // Informe de diagnóstico al portapapeles
+            HELP_DIAGNOSTICS => handle_diagnostics(app),
 
             // Cuentas - Agregar
             ACCOUNTS_ADD => handle_add_account(app),
@@ -98,11 +131,42 @@ pub fn setup_menu_handlers<R: Runtime>(app: &AppHandle<R>) {
             // Cuentas - Eliminar cuenta activa
             ACCOUNTS_DELETE_ACTIVE => handle_delete_active_account(app),
 
+            // Cuentas - Abrir la cuenta activa en otra ventana
+            ACCOUNTS_DUPLICATE_WINDOW => handle_duplicate_active_account(app),
+
+            // Cuentas - Gestor de cuentas
+            ACCOUNTS_MANAGE => {
+                if let Err(e) = crate::window::open_accounts_manager(app) {
+                    tracing::error!("Failed to open accounts manager: {}", e);
+                }
+            },
+
+            // Ventana sin sesión con datos efímeros
+            ACCOUNTS_INCOGNITO_WINDOW => {
+                if let Err(e) = crate::window::open_incognito_window(app) {
+                    tracing::error!("Failed to open incognito window: {}", e);
+                }
+            },
+
+            // Cuentas - Copias de seguridad (el frontend pide ruta y passphrase)
+            ACCOUNTS_EXPORT => request_backup_dialog(app, ACCOUNTS_EXPORT_EVENT),
+            ACCOUNTS_IMPORT => request_backup_dialog(app, ACCOUNTS_IMPORT_EVENT),
+
             _ => {
                 // Verificar si es un evento de cambio de cuenta
                 if event_id.starts_with(ACCOUNTS_ACCOUNT_PREFIX) {
                     let username = event_id.trim_start_matches(ACCOUNTS_ACCOUNT_PREFIX);
                     handle_switch_account(app, username);
+                } else if event_id.starts_with(ACCOUNTS_DELETE_PREFIX) {
+                    let username = event_id.trim_start_matches(ACCOUNTS_DELETE_PREFIX);
+                    handle_delete_account(app, username);
+                } else if let Some(version) = event_id.strip_prefix(HELP_UPDATE_TO_PREFIX) {
+                    handle_update_to(app, version);
+                } else if event_id.starts_with(ACCOUNTS_UNARCHIVE_PREFIX) {
+                    let username = event_id.trim_start_matches(ACCOUNTS_UNARCHIVE_PREFIX);
+                    if let Err(e) = app.state::<AccountsManager>().unarchive_account(username) {
+                        tracing::error!("Failed to unarchive account: {}", e);
+                    }
                 } else {
                     tracing::warn!("Unhandled menu event: {}", event_id);
                 }
@@ -111,23 +175,201 @@ pub fn setup_menu_handlers<R: Runtime>(app: &AppHandle<R>) {
     });
 }
 
-/// Ejecuta JavaScript en la ventana principal
+/// Ejecuta JavaScript en la ventana con foco
 /// Nota: Como la ventana carga directamente X.com, no podemos ejecutar JS arbitrario por CORS
 fn execute_js<R: Runtime>(app: &AppHandle<R>, js_code: &str) {
-    if let Some(window) = app.get_webview_window("main") {
+    if let Some(window) = focused_or_main(app) {
         if let Err(e) = window.eval(js_code) {
             tracing::error!("Failed to execute JS: {}", e);
         }
     }
 }
 
-/// Navega la ventana a una URL específica
-fn navigate_to<R: Runtime>(app: &AppHandle<R>, url: &str) {
-    if let Some(window) = app.get_webview_window("main") {
-        let js = format!("window.location.href = '{}';", url);
-        if let Err(e) = window.eval(&js) {
-            tracing::error!("Failed to navigate: {}", e);
+/// Maneja Apariencia > Sistema / Clara / Oscura
+fn handle_appearance<R: Runtime>(app: &AppHandle<R>, appearance: Appearance) {
+    if let Err(e) = crate::appearance::set_appearance(app, &app.state::<AccountsManager>(), appearance) {
+        tracing::error!("Failed to set appearance: {}", e);
+    }
+}
+
+/// "Actualizar a vX.Y.Z…": abre la página de la release encontrada
+fn handle_update_to<R: Runtime>(app: &AppHandle<R>, version: &str) {
+    let Some(update) = app.state::<updates::AvailableUpdate>().get().filter(|info| info.latest == version) else {
+        tracing::warn!("Update {} is no longer available", version);
+        return;
+    };
+
+    if let Err(e) = tauri_plugin_opener::open_url(&update.release_url, None::<String>) {
+        tracing::error!("Failed to open release page: {}", e);
+    }
+}
+
+/// "Buscar Actualizaciones al Abrir": activa o desactiva la comprobación
+/// automática (la política `off`), conservando el intervalo
+fn handle_auto_update_check<R: Runtime>(app: &AppHandle<R>) {
+    let manager = app.state::<AccountsManager>();
+    let current = manager.settings();
+
+    if let Err(e) = updates::set_auto_update_check(
+        app,
+        &manager,
+        !current.update_policy.checks_on_launch(),
+        current.update_check_interval_hours,
+    ) {
+        tracing::error!("Failed to change automatic update check: {}", e);
+    }
+}
+
+/// Cambia el canal de actualizaciones desde Ayuda > Canal de actualizaciones
+fn handle_update_channel<R: Runtime>(app: &AppHandle<R>, channel: UpdateChannel) {
+    if let Err(e) = updates::set_update_channel(app, &app.state::<AccountsManager>(), channel) {
+        tracing::error!("Failed to set update channel: {}", e);
+    }
+}
+
+/// Maneja "Buscar en la página" (Cmd+F) y buscar siguiente/anterior
+/// (Cmd+G / Cmd+Shift+G)
+///
+/// Sin ventana, o fuera de las páginas de X, no hace nada.
+fn handle_find<R: Runtime>(app: &AppHandle<R>, direction: Option<FindDirection>) {
+    let result = match direction {
+        None => find::show_overlay(app),
+        Some(direction) => find::find_in_page(app, None, direction),
+    };
+
+    if let Err(e) = result {
+        tracing::info!("Ignoring find in page: {}", e);
+    }
+}
+
+/// URL que muestra la ventana con foco
+///
+/// No se filtra la portada genérica (`x.com/home`): también se copia.
+fn current_url<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    let window = focused_or_main(app).ok_or("No window to read the URL from")?;
+
+    window
+        .url()
+        .map(|url| url.to_string())
+        .map_err(|e| format!("Failed to read URL: {}", e))
+}
+
+/// Maneja Acercar / Alejar / Tamaño real sobre la ventana con foco
+fn handle_zoom<R: Runtime>(app: &AppHandle<R>, change: ZoomChange) {
+    let Some(window) = focused_or_main(app) else {
+        return;
+    };
+
+    match crate::zoom::change_zoom(app, &window, &app.state::<AccountsManager>(), change) {
+        Ok(zoom) => tracing::info!("Zoom set to {:.1} on {}", zoom, window.label()),
+        Err(e) => tracing::error!("Failed to change zoom: {}", e),
+    }
+}
+
+/// Título de los diálogos de "Buscar Actualizaciones..."
+const UPDATES_DIALOG_TITLE: &str = "Buscar actualizaciones";
+
+/// Texto del diálogo de "Buscar Actualizaciones..."
+fn update_message(info: &UpdateInfo) -> String {
+    if info.update_available {
+        format!("Hay una nueva versión {} disponible (tienes la {}).", info.latest, info.current)
+    } else {
+        format!("Estás en la última versión ({}).", info.current)
+    }
+}
+
+/// "Buscar Actualizaciones...": comprueba la última release como
+/// `check_updates` y muestra el resultado en un diálogo nativo
+///
+/// Si hay una versión nueva, el diálogo ofrece abrir la página de la release.
+/// Un fallo de red también se muestra, como diálogo de error.
+fn handle_check_updates<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let channel = updates::load_update_channel(&app.state::<AccountsManager>());
+        let cache = crate::update_cache::cache_path(&app);
+        let info = match updates::check_for_updates(channel, cache.as_deref()).await {
+            Ok(info) => {
+                let manager = app.state::<AccountsManager>();
+                if let Err(e) = updates::record_check(&manager, chrono::Utc::now().timestamp()) {
+                    tracing::warn!("Failed to save update check time: {}", e);
+                }
+                updates::set_available_update(&app, &manager, &info);
+                info
+            }
+            Err(e) => {
+                tracing::error!("Update check failed: {}", e);
+                app.dialog()
+                    .message(format!("No se pudo comprobar si hay actualizaciones.\n\n{}", e))
+                    .title(UPDATES_DIALOG_TITLE)
+                    .kind(MessageDialogKind::Error)
+                    .show(|_| {});
+                return;
+            }
+        };
+
+        let dialog = app.dialog().message(update_message(&info)).title(UPDATES_DIALOG_TITLE);
+
+        if !info.update_available {
+            dialog.show(|_| {});
+            return;
+        }
+
+        dialog
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                "Ver la versión".to_string(),
+                "Cerrar".to_string(),
+            ))
+            .show(move |open| {
+                if !open {
+                    return;
+                }
+                if let Err(e) = tauri_plugin_opener::open_url(&info.release_url, None::<String>) {
+                    tracing::error!("Failed to open release page: {}", e);
+                }
+            });
+    });
+}
+
+/// Maneja "Copiar enlace de la publicación actual"
+fn handle_copy_current_url<R: Runtime>(app: &AppHandle<R>) {
+    let result = current_url(app).and_then(|url| {
+        app.clipboard()
+            .write_text(url.clone())
+            .map(|_| url)
+            .map_err(|e| format!("Failed to write clipboard: {}", e))
+    });
+
+    match result {
+        Ok(url) => tracing::info!("Copied current URL: {}", url),
+        Err(e) => tracing::error!("Failed to copy current URL: {}", e),
+    }
+}
+
+/// "Nueva Publicación": pulsa el botón Publicar de X, que abre el editor sin
+/// recargar la página, y si no está (otra página, X cambió) navega al editor
+fn handle_new_post<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match navigation::click_element_by_testid(&app, navigation::COMPOSE_BUTTON_TESTID).await {
+            Ok(true) => return,
+            Ok(false) => tracing::debug!("Compose button not found, navigating to the composer"),
+            Err(e) => tracing::debug!("{}, navigating to the composer", e),
         }
+
+        navigate_to(&app, NavTarget::Compose);
+    });
+}
+
+/// Navega la ventana con foco a un destino, según la configuración de navegación
+fn navigate_to<R: Runtime>(app: &AppHandle<R>, target: NavTarget) {
+    if focused_or_main(app).is_none() {
+        return;
+    }
+
+    if let Err(e) = navigation::open_in_app(app, &nav_config::resolve(app, target)) {
+        tracing::error!("Failed to navigate to {:?}: {}", target, e);
     }
 }
 
@@ -137,47 +379,218 @@ fn navigate_to<R: Runtime>(app: &AppHandle<R>, url: &str) {
 
 /// Maneja "Agregar Cuenta..." - Navega al flujo de login de X
 fn handle_add_account<R: Runtime>(app: &AppHandle<R>) {
-    if let Some(window) = app.get_webview_window("main") {
-        let _ = window.eval("window.location.href = 'https://x.com/i/flow/login';");
-        tracing::info!("Navigating to login page to add account");
+    if let Some(window) = focused_account_window(app) {
+        match navigation::navigate_window(app, &window, &nav_config::resolve(app, NavTarget::Login)) {
+            Ok(()) => tracing::info!("Navigating to login page to add account"),
+            Err(e) => tracing::error!("Failed to open the login page: {}", e),
+        }
     }
 }
 
-/// Maneja "Eliminar Cuenta Activa"
-fn handle_delete_active_account<R: Runtime>(app: &AppHandle<R>) {
-    let app_clone = app.clone();
+/// Pide con un diálogo nativo la ruta de la copia de seguridad y la envía a
+/// la ventana con foco en `event`
+///
+/// El frontend (`login-detector.js`) pide la passphrase e invoca
+/// `export_accounts_backup` o `import_accounts_backup`.
+fn request_backup_dialog<R: Runtime>(app: &AppHandle<R>, event: &'static str) {
+    let Some(window) = focused_account_window(app) else {
+        return;
+    };
 
-    tauri::async_runtime::spawn(async move {
-        match crate::accounts::get_active_account() {
-            Ok(Some(username)) => {
-                tracing::info!("Deleting active account: {}", username);
+    let target = window.clone();
+    let on_path = move |path: Option<FilePath>| {
+        // Cancelado
+        let Some(path) = path.and_then(|path| path.into_path().ok()) else {
+            return;
+        };
 
-                if let Err(e) = crate::accounts::remove_account(&username) {
-                    tracing::error!("Failed to delete account: {}", e);
-                } else {
-                    tracing::info!("Account deleted successfully: {}", username);
+        if let Err(e) = target.emit_to(target.label(), event, path.to_string_lossy()) {
+            tracing::error!("Failed to emit {}: {}", event, e);
+        }
+    };
 
-                    // Reconstruir menú
-                    if let Err(e) = crate::menu::builder::rebuild_menu(&app_clone) {
-                        tracing::error!("Failed to rebuild menu: {}", e);
-                    }
+    let dialog = app.dialog().file().set_parent(&window).add_filter("JSON", &["json"]);
+    if event == ACCOUNTS_EXPORT_EVENT {
+        dialog.set_file_name(BACKUP_FILE_NAME).save_file(on_path);
+    } else {
+        dialog.pick_file(on_path);
+    }
+}
 
-                    // Recargar ventana
-                    if let Some(window) = app_clone.get_webview_window("main") {
-                        let _ = window.eval("location.reload();");
-                    }
+/// Maneja "Duplicar cuenta activa a nueva ventana"
+fn handle_duplicate_active_account<R: Runtime>(app: &AppHandle<R>) {
+    match app.state::<AccountsManager>().get_active_account() {
+        Ok(Some(username)) => {
+            if let Err(e) = crate::window::open_account_window(app, &username) {
+                tracing::error!("Failed to open account window: {}", e);
+            }
+        }
+        Ok(None) => tracing::warn!("No active account to open in a new window"),
+        Err(e) => tracing::error!("Failed to get active account: {}", e),
+    }
+}
+
+/// Maneja "Rotar Claves de Cifrado..."
+///
+/// Argon2id tarda: se rota en un hilo aparte para no bloquear el menú.
+fn handle_rotate_keys<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        match crate::rotation::rotate_manager_keys(&app.state::<AccountsManager>()) {
+            Ok(report) if report.failures.is_empty() => {
+                tracing::info!("Keys rotated: {} items", report.rotated);
+            }
+            Ok(report) => {
+                for failure in &report.failures {
+                    tracing::error!("Key rotation failed for {}: {}", failure.item, failure.error);
                 }
             }
-            Ok(None) => {
-                tracing::warn!("No active account to delete");
+            Err(e) => tracing::error!("Key rotation failed: {}", e),
+        }
+    });
+}
+
+/// Maneja "Limpiar Entradas Huérfanas del Llavero..."
+///
+/// Solo calcula la vista previa: el frontend muestra el número de entradas
+/// con `confirm-keychain-cleanup` y llama a `cleanup_keychain` para borrarlas.
+fn handle_cleanup_keychain<R: Runtime>(app: &AppHandle<R>) {
+    let report = match crate::cleanup::cleanup_keychain(&app.state::<AccountsManager>(), true) {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!("Keychain cleanup failed: {}", e);
+            return;
+        }
+    };
+
+    let Some(window) = focused_account_window(app) else {
+        return;
+    };
+
+    if let Err(e) = window.emit_to(window.label(), CONFIRM_KEYCHAIN_CLEANUP_EVENT, report.orphaned.len()) {
+        tracing::error!("Failed to emit {}: {}", CONFIRM_KEYCHAIN_CLEANUP_EVENT, e);
+    }
+}
+
+/// Maneja "Diagnóstico de credenciales"
+///
+/// El frontend muestra qué cuentas no se pueden descifrar.
+fn handle_verify_credentials<R: Runtime>(app: &AppHandle<R>) {
+    let report = match app.state::<AccountsManager>().verify_credentials() {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!("Credentials verification failed: {}", e);
+            return;
+        }
+    };
+
+    let Some(window) = focused_account_window(app) else {
+        return;
+    };
+
+    if let Err(e) = window.emit_to(window.label(), CREDENTIALS_DIAGNOSTIC_EVENT, &report) {
+        tracing::error!("Failed to emit {}: {}", CREDENTIALS_DIAGNOSTIC_EVENT, e);
+    }
+}
+
+/// Título de los avisos del informe de diagnóstico
+const DIAGNOSTICS_DIALOG_TITLE: &str = "Informe de diagnóstico";
+
+/// Maneja "Copiar Informe de Diagnóstico": genera el informe, lo copia al
+/// portapapeles y lo confirma con un aviso
+///
+/// Verificar las credenciales deriva una clave por cuenta, así que se genera
+/// fuera del hilo principal.
+fn handle_diagnostics<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let log_dir = crate::logging::log_directory(&app.config().identifier);
+        let report = crate::diagnostics::generate_diagnostics(&app.state::<AccountsManager>(), log_dir.as_deref());
+
+        let (message, kind) = match app.clipboard().write_text(report) {
+            Ok(()) => {
+                tracing::info!("Copied diagnostics report to the clipboard");
+                (
+                    "Se ha copiado el informe al portapapeles. No incluye tokens ni sesiones, y los nombres de usuario van enmascarados.",
+                    MessageDialogKind::Info,
+                )
             }
             Err(e) => {
-                tracing::error!("Failed to get active account: {}", e);
+                tracing::error!("Failed to copy diagnostics report: {}", e);
+                ("No se pudo copiar el informe al portapapeles.", MessageDialogKind::Error)
             }
+        };
+
+        app.dialog().message(message).title(DIAGNOSTICS_DIALOG_TITLE).kind(kind).show(|_| {});
+    });
+}
+
+/// Maneja "Eliminar Cuenta Activa"
+fn handle_delete_active_account<R: Runtime>(app: &AppHandle<R>) {
+    let manager = app.state::<AccountsManager>();
+
+    match manager.get_active_account() {
+        Ok(Some(username)) => handle_delete_account(app, &username),
+        Ok(None) => {
+            tracing::warn!("No active account to delete");
+        }
+        Err(e) => {
+            tracing::error!("Failed to get active account: {}", e);
+        }
+    }
+}
+
+/// Elimina `username` tras confirmarlo con un aviso nativo
+///
+/// El aviso bloquea, así que se muestra fuera del hilo principal. El menú se
+/// reconstruye solo al guardar la lista sin la cuenta.
+fn handle_delete_account<R: Runtime>(app: &AppHandle<R>, username: &str) {
+    let app = app.clone();
+    let username = username.to_string();
+
+    tracing::info!("Requesting confirmation to delete account: {}", username);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = delete_if_confirmed(&username, confirm_account_deletion, |username| {
+            crate::remove_account_and_web_data(&app, username, false).map(|_| ())
+        });
+
+        match result {
+            Ok(true) => tracing::info!("Deleted account {} from the menu", username),
+            Ok(false) => tracing::info!("Deletion of {} cancelled", username),
+            Err(e) => tracing::error!("Failed to delete account {}: {}", username, e),
         }
     });
 }
 
+/// Ejecuta `delete` solo si `confirm` lo aprueba
+///
+/// # Returns
+/// `true` si se ha eliminado la cuenta
+fn delete_if_confirmed(
+    username: &str,
+    confirm: impl FnOnce(&str) -> bool,
+    delete: impl FnOnce(&str) -> Result<(), crate::error::AppError>,
+) -> Result<bool, crate::error::AppError> {
+    if !confirm(username) {
+        return Ok(false);
+    }
+
+    delete(username)?;
+    Ok(true)
+}
+
+/// Aviso nativo antes de borrar una cuenta
+fn confirm_account_deletion(username: &str) -> bool {
+    crate::dialog::confirm(
+        &format!("¿Eliminar la cuenta @{}?", username),
+        "Sus credenciales se borrarán del Keychain. Esta acción no se puede deshacer.",
+        "Eliminar",
+    )
+}
+
 /// Maneja cambio de cuenta (click en @username)
 fn handle_switch_account<R: Runtime>(app: &AppHandle<R>, username: &str) {
     let username = username.to_string();
@@ -186,21 +599,81 @@ fn handle_switch_account<R: Runtime>(app: &AppHandle<R>, username: &str) {
     tracing::info!("Switching to account: {}", username);
 
     tauri::async_runtime::spawn(async move {
-        if let Err(e) = crate::accounts::set_active_account(&username) {
+        let result = crate::activate_account(&app_clone, &username);
+
+        // macOS ya ha invertido la marca del item pulsado: se recoloca según la
+        // cuenta activa real (también si el cambio ha fallado)
+        let active = app_clone.state::<AccountsManager>().get_active_account().ok().flatten();
+        if !super::builder::sync_account_checks(&app_clone, active.as_deref()) {
+            super::builder::schedule_rebuild(&app_clone);
+        }
+
+        if let Err(e) = result {
             tracing::error!("Failed to switch account: {}", e);
             return;
         }
 
+        // La ventana se recarga con las cookies de la nueva cuenta
         tracing::info!("Account switched successfully to: {}", username);
+    });
+}
 
-        // Reconstruir menú para actualizar checkmark
-        if let Err(e) = crate::menu::builder::rebuild_menu(&app_clone) {
-            tracing::error!("Failed to rebuild menu: {}", e);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
 
-        // Recargar ventana para cargar sesión de la nueva cuenta
-        if let Some(window) = app_clone.get_webview_window("main") {
-            let _ = window.eval("location.reload();");
-        }
-    });
+    #[test]
+    fn test_copy_current_url_reads_focused_window() {
+        let app = tauri::test::mock_app();
+        assert!(current_url(app.handle()).is_err());
+
+        let url = "https://x.com/home".parse().unwrap();
+        WebviewWindowBuilder::new(&app, crate::window::MAIN_WINDOW, WebviewUrl::External(url))
+            .build()
+            .unwrap();
+
+        // La portada genérica también se copia tal cual
+        assert_eq!(current_url(app.handle()).unwrap(), "https://x.com/home");
+    }
+
+    #[test]
+    fn test_history_and_hard_reload_without_window() {
+        let app = tauri::test::mock_app();
+
+        // Sin ventanas no hacen nada (ni fallan)
+        navigation::go(app.handle(), History::Back);
+        navigation::go(app.handle(), History::Forward);
+        navigation::reload_ignoring_cache(app.handle());
+    }
+
+    #[test]
+    fn test_cancelled_deletion_keeps_account() {
+        let store: &'static crate::secrets::InMemoryStore = Box::leak(Box::new(crate::secrets::InMemoryStore::new()));
+        let manager = AccountsManager::new(store);
+        manager.add_account("alice", Some("token".to_string()), None).unwrap();
+        let delete = |username: &str| manager.remove_account(username, true);
+
+        assert_eq!(delete_if_confirmed("alice", |_| false, delete), Ok(false));
+        assert_eq!(manager.get_active_account().unwrap().as_deref(), Some("alice"));
+        assert!(crate::accounts::load_credentials(store, "alice").unwrap().is_some());
+
+        assert_eq!(delete_if_confirmed("alice", |_| true, delete), Ok(true));
+        assert!(manager.list_accounts(true).unwrap().is_empty());
+        assert!(crate::accounts::load_credentials(store, "alice").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_update_message() {
+        let info = |latest: &str| {
+            UpdateInfo::from_release(&serde_json::json!({ "tag_name": latest }), "0.5.0", UpdateChannel::Stable)
+                .unwrap()
+        };
+
+        assert_eq!(update_message(&info("v0.5.0")), "Estás en la última versión (0.5.0).");
+        assert_eq!(
+            update_message(&info("v0.6.0")),
+            "Hay una nueva versión 0.6.0 disponible (tienes la 0.5.0)."
+        );
+    }
 }
@@ -15,20 +15,22 @@ pub const NAV_BOOKMARKS: &str = "nav_bookmarks";
 pub const NAV_LISTS: &str = "nav_lists";
 pub const NAV_PROFILE: &str = "nav_profile";
 pub const NAV_MORE: &str = "nav_more";
+pub const NAV_BACK: &str = "nav_back";
+pub const NAV_FORWARD: &str = "nav_forward";
 
 // IDs de menú - Vista
 pub const VIEW_RELOAD: &str = "view_reload";
+pub const VIEW_RELOAD_IGNORING_CACHE: &str = "view_reload_ignoring_cache";
 pub const VIEW_ZOOM_IN: &str = "view_zoom_in";
 pub const VIEW_ZOOM_OUT: &str = "view_zoom_out";
 pub const VIEW_ZOOM_RESET: &str = "view_zoom_reset";
-pub const VIEW_TEXT_LARGER: &str = "view_text_larger";
-pub const VIEW_TEXT_SMALLER: &str = "view_text_smaller";
-pub const VIEW_TEXT_RESET: &str = "view_text_reset";
 pub const VIEW_FULLSCREEN: &str = "view_fullscreen";
 
 // IDs de menú - Archivo
 pub const FILE_NEW_POST: &str = "file_new_post";
 pub const FILE_SAVE: &str = "file_save";
+pub const FILE_PRINT: &str = "file_print";
+pub const FILE_EXPORT_PDF: &str = "file_export_pdf";
 pub const FILE_CLOSE_WINDOW: &str = "file_close_window";
 pub const FILE_CLOSE_ALL: &str = "file_close_all";
 
@@ -40,35 +42,117 @@ pub const EDIT_COPY: &str = "edit_copy";
 pub const EDIT_PASTE: &str = "edit_paste";
 pub const EDIT_SELECT_ALL: &str = "edit_select_all";
 pub const EDIT_FIND: &str = "edit_find";
+pub const EDIT_FIND_NEXT: &str = "edit_find_next";
+pub const EDIT_FIND_PREVIOUS: &str = "edit_find_previous";
+pub const EDIT_SEARCH_X: &str = "edit_search_x";
+pub const COPY_CURRENT_URL: &str = "copy_current_url";
 
 // IDs de menú - Configuración
 pub const APP_SETTINGS: &str = "app_settings";
 pub const APP_PREFERENCES: &str = "app_preferences";
+pub const APP_APPEARANCE_SYSTEM: &str = "app_appearance_system";
+pub const APP_APPEARANCE_LIGHT: &str = "app_appearance_light";
+pub const APP_APPEARANCE_DARK: &str = "app_appearance_dark";
 pub const APP_TOGGLE_DARK_MODE: &str = "app_toggle_dark_mode";
 pub const APP_SWITCH_ACCOUNT: &str = "app_switch_account";
 
 // IDs de menú - Scroll
 pub const SCROLL_TOP: &str = "scroll_top";
 pub const SCROLL_BOTTOM: &str = "scroll_bottom";
+pub const SCROLL_TIMELINE_TOP: &str = "scroll_timeline_top";
 
 // IDs de menú - Cuentas
 pub const ACCOUNTS_ADD: &str = "accounts_add";
 pub const ACCOUNTS_DELETE_ACTIVE: &str = "accounts_delete_active";
 pub const ACCOUNTS_MANAGE: &str = "accounts_manage";
+pub const ACCOUNTS_DUPLICATE_WINDOW: &str = "accounts_duplicate_window";
+pub const ACCOUNTS_INCOGNITO_WINDOW: &str = "accounts_incognito_window";
+pub const ACCOUNTS_EXPORT: &str = "accounts_export";
+pub const ACCOUNTS_IMPORT: &str = "accounts_import";
 pub const ACCOUNTS_ACCOUNT_PREFIX: &str = "accounts_account_";
+pub const ACCOUNTS_UNARCHIVE_PREFIX: &str = "accounts_unarchive_";
+/// No es solo `accounts_delete_` para no chocar con `ACCOUNTS_DELETE_ACTIVE`
+/// (la cuenta @active)
+pub const ACCOUNTS_DELETE_PREFIX: &str = "accounts_delete_account_";
 
 /// Genera el ID de menú para una cuenta específica
 pub fn account_menu_id(username: &str) -> String {
     format!("{}{}", ACCOUNTS_ACCOUNT_PREFIX, username)
 }
 
-// URLs para navegación directa (la ventana carga X.com directamente, no hay iframe)
-pub const URL_NAV_EXPLORE: &str = "https://x.com/explore";
-pub const URL_NAV_NOTIFICATIONS: &str = "https://x.com/notifications";
-pub const URL_NAV_MESSAGES: &str = "https://x.com/messages";
-pub const URL_NAV_BOOKMARKS: &str = "https://x.com/i/bookmarks";
-pub const URL_NAV_LISTS: &str = "https://x.com/i/lists";
-pub const URL_NAV_PROFILE: &str = "https://x.com/home";
-pub const URL_NAV_GROK: &str = "https://x.com/i/grok";
-pub const URL_SETTINGS: &str = "https://x.com/settings";
-pub const URL_COMPOSE: &str = "https://x.com/compose/tweet";
+/// Genera el ID de menú para desarchivar una cuenta
+pub fn unarchive_menu_id(username: &str) -> String {
+    format!("{}{}", ACCOUNTS_UNARCHIVE_PREFIX, username)
+}
+
+/// Genera el ID de menú para eliminar una cuenta
+pub fn delete_menu_id(username: &str) -> String {
+    format!("{}{}", ACCOUNTS_DELETE_PREFIX, username)
+}
+
+// IDs de menú - Ventana
+pub const WINDOW_RESET_STATE: &str = "window_reset_state";
+
+// IDs de menú - Ayuda
+pub const HELP_CHECK_UPDATES: &str = "check_updates";
+pub const HELP_UPDATE_CHANNEL_STABLE: &str = "help_update_channel_stable";
+pub const HELP_UPDATE_CHANNEL_BETA: &str = "help_update_channel_beta";
+pub const HELP_AUTO_UPDATE_CHECK: &str = "help_auto_update_check";
+pub const HELP_ROTATE_KEYS: &str = "help_rotate_keys";
+pub const HELP_CLEANUP_KEYCHAIN: &str = "help_cleanup_keychain";
+pub const HELP_VERIFY_CREDENTIALS: &str = "help_verify_credentials";
+pub const HELP_DIAGNOSTICS: &str = "help_diagnostics";
+/// "Actualizar a vX.Y.Z…", con la versión como sufijo
+pub const HELP_UPDATE_TO_PREFIX: &str = "update_to_";
+
+/// Genera el ID de menú para instalar la versión `version`
+pub fn update_menu_id(version: &str) -> String {
+    format!("{}{}", HELP_UPDATE_TO_PREFIX, version)
+}
+
+// Rutas para navegación directa (la ventana carga X.com directamente, no hay
+// iframe). Se resuelven sobre la base de `NavConfig`, x.com por defecto
+pub const PATH_NAV_EXPLORE: &str = "/explore";
+pub const PATH_NAV_NOTIFICATIONS: &str = "/notifications";
+pub const PATH_NAV_MESSAGES: &str = "/messages";
+pub const PATH_NAV_BOOKMARKS: &str = "/i/bookmarks";
+pub const PATH_NAV_LISTS: &str = "/i/lists";
+pub const PATH_NAV_HOME: &str = "/home";
+pub const PATH_NAV_GROK: &str = "/i/grok";
+pub const PATH_SETTINGS: &str = "/settings";
+pub const PATH_COMPOSE: &str = "/compose/tweet";
+pub const PATH_LOGIN: &str = "/i/flow/login";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_menu_ids_are_unique() {
+        let ids = [
+            NAV_EXPLORE, NAV_GROK, NAV_NOTIFICATIONS, NAV_MESSAGES, NAV_BOOKMARKS, NAV_LISTS,
+            NAV_PROFILE, NAV_MORE, NAV_BACK, NAV_FORWARD, VIEW_RELOAD, VIEW_RELOAD_IGNORING_CACHE, VIEW_ZOOM_IN, VIEW_ZOOM_OUT, VIEW_ZOOM_RESET,
+            VIEW_FULLSCREEN, FILE_NEW_POST,
+            FILE_SAVE, FILE_PRINT, FILE_EXPORT_PDF, FILE_CLOSE_WINDOW, FILE_CLOSE_ALL, EDIT_UNDO, EDIT_REDO, EDIT_CUT, EDIT_COPY,
+            EDIT_PASTE, EDIT_SELECT_ALL, EDIT_FIND, EDIT_FIND_NEXT, EDIT_FIND_PREVIOUS, EDIT_SEARCH_X, COPY_CURRENT_URL, APP_SETTINGS, APP_PREFERENCES,
+            APP_APPEARANCE_SYSTEM, APP_APPEARANCE_LIGHT, APP_APPEARANCE_DARK, APP_TOGGLE_DARK_MODE, APP_SWITCH_ACCOUNT, SCROLL_TOP, SCROLL_BOTTOM, SCROLL_TIMELINE_TOP, ACCOUNTS_ADD,
+            ACCOUNTS_DELETE_ACTIVE, ACCOUNTS_MANAGE, ACCOUNTS_DUPLICATE_WINDOW, ACCOUNTS_INCOGNITO_WINDOW, ACCOUNTS_EXPORT,
+            ACCOUNTS_IMPORT, WINDOW_RESET_STATE, HELP_CHECK_UPDATES, HELP_UPDATE_CHANNEL_STABLE, HELP_UPDATE_CHANNEL_BETA, HELP_AUTO_UPDATE_CHECK, HELP_ROTATE_KEYS, HELP_CLEANUP_KEYCHAIN,
+            HELP_VERIFY_CREDENTIALS, HELP_DIAGNOSTICS,
+        ];
+
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        // Ningún id fijo puede confundirse con el de una cuenta
+        assert!(!ids.iter().any(|id| id.starts_with(ACCOUNTS_ACCOUNT_PREFIX)));
+        assert!(!ids.iter().any(|id| id.starts_with(ACCOUNTS_UNARCHIVE_PREFIX)));
+        assert!(!ids.iter().any(|id| id.starts_with(ACCOUNTS_DELETE_PREFIX)));
+        assert!(!ids.iter().any(|id| id.starts_with(HELP_UPDATE_TO_PREFIX)));
+        assert_eq!(update_menu_id("1.2.0"), "update_to_1.2.0");
+        assert!(!unarchive_menu_id("alice").starts_with(ACCOUNTS_ACCOUNT_PREFIX));
+        assert!(!delete_menu_id("alice").starts_with(ACCOUNTS_ACCOUNT_PREFIX));
+        assert_ne!(delete_menu_id("active"), ACCOUNTS_DELETE_ACTIVE);
+    }
+}
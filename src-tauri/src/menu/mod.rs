@@ -9,9 +9,13 @@
 // Proporciona menús completos que replican la experiencia de la app oficial de X.
 
 pub mod builder;
+pub mod debounce;
+pub mod find;
 pub mod handlers;
 pub mod items;
+pub mod nav_config;
 pub mod navigation;
+pub mod print;
 
 pub use builder::{build_menu, rebuild_menu};
 pub use handlers::setup_menu_handlers;
@@ -0,0 +1,215 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Destinos de la navegación del menú
+//
+// Por defecto los items de navegación llevan a x.com. Para usar una instancia
+// alternativa (Nitter, un mirror...) se puede crear `navigation.json` en el
+// directorio de configuración de la aplicación:
+//
+//     { "base_url": "https://nitter.example", "paths": { "explore": "/search" } }
+//
+// Las rutas que no aparezcan en `paths` usan las de X. Si el archivo no es
+// válido se ignora entero y se sigue usando x.com.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::items::*;
+
+/// Archivo (en el directorio de configuración) con la configuración de navegación
+pub const NAV_CONFIG_FILE: &str = "navigation.json";
+/// Base por defecto de todas las rutas
+pub const DEFAULT_BASE_URL: &str = "https://x.com";
+
+/// Destinos de navegación del menú
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NavTarget {
+    Explore,
+    Grok,
+    Notifications,
+    Messages,
+    Bookmarks,
+    Lists,
+    Home,
+    Settings,
+    Compose,
+    Login,
+}
+
+impl NavTarget {
+    /// Ruta del destino en x.com
+    pub fn default_path(self) -> &'static str {
+        match self {
+            NavTarget::Explore => PATH_NAV_EXPLORE,
+            NavTarget::Grok => PATH_NAV_GROK,
+            NavTarget::Notifications => PATH_NAV_NOTIFICATIONS,
+            NavTarget::Messages => PATH_NAV_MESSAGES,
+            NavTarget::Bookmarks => PATH_NAV_BOOKMARKS,
+            NavTarget::Lists => PATH_NAV_LISTS,
+            NavTarget::Home => PATH_NAV_HOME,
+            NavTarget::Settings => PATH_SETTINGS,
+            NavTarget::Compose => PATH_COMPOSE,
+            NavTarget::Login => PATH_LOGIN,
+        }
+    }
+}
+
+/// Base y rutas a las que llevan los items de navegación
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct NavConfig {
+    /// Origen de todas las rutas (`https://host[:puerto]`)
+    pub base_url: String,
+    /// Rutas que sustituyen a las de x.com, por destino
+    pub paths: HashMap<NavTarget, String>,
+}
+
+impl Default for NavConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            paths: HashMap::new(),
+        }
+    }
+}
+
+impl NavConfig {
+    /// Comprueba que la base es una URL `https` con host y que las rutas
+    /// empiezan por `/`
+    pub fn validate(&self) -> Result<(), String> {
+        let base = tauri::Url::parse(&self.base_url)
+            .map_err(|e| format!("Invalid navigation base URL '{}': {}", self.base_url, e))?;
+
+        if base.scheme() != "https" || base.host_str().is_none_or(str::is_empty) {
+            return Err(format!("Navigation base URL must be https: '{}'", self.base_url));
+        }
+
+        if let Some((target, path)) = self.paths.iter().find(|(_, path)| !path.starts_with('/')) {
+            return Err(format!("Navigation path for {:?} must start with '/': '{}'", target, path));
+        }
+
+        Ok(())
+    }
+
+    /// URL completa de un destino
+    pub fn url(&self, target: NavTarget) -> String {
+        let path = self.paths.get(&target).map_or(target.default_path(), String::as_str);
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    /// URL del perfil de `handle` (ya validado con `validate_username`)
+    pub fn profile_url(&self, handle: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), handle)
+    }
+
+    /// Lee `NAV_CONFIG_FILE` de `config_dir`
+    ///
+    /// Sin archivo, o si no es válido, se usa la configuración por defecto.
+    pub fn load(config_dir: &Path) -> Self {
+        let path = config_dir.join(NAV_CONFIG_FILE);
+        let Ok(data) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        let config = serde_json::from_str::<NavConfig>(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+            .and_then(|config| config.validate().map(|_| config));
+
+        match config {
+            Ok(config) => {
+                tracing::info!("Using navigation base {}", config.base_url);
+                config
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring navigation config: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// URL de un destino según la configuración cargada al arrancar (x.com si no
+/// hay ninguna)
+pub fn resolve<R: Runtime>(app: &AppHandle<R>, target: NavTarget) -> String {
+    match app.try_state::<NavConfig>() {
+        Some(config) => config.url(target),
+        None => NavConfig::default().url(target),
+    }
+}
+
+/// URL del perfil de `handle` según la configuración cargada al arrancar
+pub fn resolve_profile<R: Runtime>(app: &AppHandle<R>, handle: &str) -> String {
+    match app.try_state::<NavConfig>() {
+        Some(config) => config.profile_url(handle),
+        None => NavConfig::default().profile_url(handle),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_urls_point_to_x() {
+        let config = NavConfig::default();
+        assert_eq!(config.url(NavTarget::Explore), "https://x.com/explore");
+        assert_eq!(config.url(NavTarget::Bookmarks), "https://x.com/i/bookmarks");
+        assert_eq!(config.url(NavTarget::Login), "https://x.com/i/flow/login");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_custom_base_and_paths() {
+        let config: NavConfig =
+            serde_json::from_str(r#"{ "base_url": "https://nitter.example/", "paths": { "notifications": "/alerts" } }"#)
+                .unwrap();
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.url(NavTarget::Explore), "https://nitter.example/explore");
+        assert_eq!(config.url(NavTarget::Notifications), "https://nitter.example/alerts");
+        assert_eq!(config.profile_url("alice"), "https://nitter.example/alice");
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = |base_url: &str| NavConfig {
+            base_url: base_url.to_string(),
+            ..Default::default()
+        };
+
+        assert!(config("https://mirror.example:8443").validate().is_ok());
+        assert!(config("http://nitter.example").validate().is_err());
+        assert!(config("nitter.example").validate().is_err());
+        assert!(config("https://").validate().is_err());
+
+        let mut relative = NavConfig::default();
+        relative.paths.insert(NavTarget::Explore, "explore".to_string());
+        assert!(relative.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_falls_back_to_default() {
+        let dir = std::env::temp_dir().join(format!("xmac-nav-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Sin archivo
+        assert_eq!(NavConfig::load(&dir), NavConfig::default());
+
+        // Base sin https
+        std::fs::write(dir.join(NAV_CONFIG_FILE), r#"{ "base_url": "http://nitter.example" }"#).unwrap();
+        assert_eq!(NavConfig::load(&dir), NavConfig::default());
+
+        std::fs::write(dir.join(NAV_CONFIG_FILE), r#"{ "base_url": "https://nitter.example" }"#).unwrap();
+        assert_eq!(NavConfig::load(&dir).url(NavTarget::Lists), "https://nitter.example/i/lists");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
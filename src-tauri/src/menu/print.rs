@@ -0,0 +1,204 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Imprimir (Cmd+P) y exportar como PDF
+//
+// Imprimir abre el diálogo de impresión del sistema para la ventana con foco.
+// Exportar pide la ruta con el diálogo de guardado y escribe el PDF que genera
+// el WKWebView con toda la altura de la página, no solo la parte visible. El
+// frontend puede exportar su propia ventana con el comando `export_page_pdf`.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Runtime, Url, WebviewWindow};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::error::AppError;
+
+/// Extensión de los ficheros exportados
+const PDF_EXTENSION: &str = "pdf";
+/// Nombre propuesto si la URL no da para más
+const DEFAULT_PDF_NAME: &str = "x";
+
+/// Nombre propuesto para el PDF de la página en `url`
+///
+/// Sale de la ruta de la página (`x.com/alice/status/1` →
+/// `x-alice-status-1.pdf`), con solo letras, números, `-` y `_`.
+pub fn pdf_file_name(url: Option<&Url>) -> String {
+    let segments: Vec<String> = url
+        .and_then(Url::path_segments)
+        .into_iter()
+        .flatten()
+        .map(|segment| {
+            segment
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+                .collect::<String>()
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let name = std::iter::once(DEFAULT_PDF_NAME.to_string())
+        .chain(segments)
+        .collect::<Vec<_>>()
+        .join("-");
+
+    format!("{}.{}", name, PDF_EXTENSION)
+}
+
+/// Comprueba la ruta de destino del PDF
+///
+/// Tiene que ser absoluta y acabar en `.pdf`: el comando se puede llamar
+/// desde la página, así que no se deja escribir sobre otro tipo de fichero.
+pub fn validate_pdf_path(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err(format!("PDF path must be absolute: {}", path.display()));
+    }
+
+    let is_pdf = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(PDF_EXTENSION));
+    if !is_pdf {
+        return Err(format!("PDF path must end in .pdf: {}", path.display()));
+    }
+
+    Ok(path.to_path_buf())
+}
+
+/// Abre el diálogo de impresión para la ventana con foco (o la principal)
+pub fn print_page<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = crate::window::focused_or_main(app) else {
+        tracing::debug!("No window to print");
+        return;
+    };
+
+    if let Err(e) = window.print() {
+        tracing::error!("Failed to print page: {}", e);
+    }
+}
+
+/// Pide dónde guardar el PDF de la ventana con foco (o la principal) y lo
+/// exporta
+pub fn export_pdf_dialog<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = crate::window::focused_or_main(app) else {
+        tracing::debug!("No window to export");
+        return;
+    };
+
+    let file_name = pdf_file_name(window.url().ok().as_ref());
+    let target = window.clone();
+
+    app.dialog()
+        .file()
+        .set_parent(&window)
+        .set_file_name(file_name)
+        .add_filter("PDF", &[PDF_EXTENSION])
+        .save_file(move |path| {
+            // Cancelado
+            let Some(path) = path.and_then(|path| path.into_path().ok()) else {
+                return;
+            };
+
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = export_page_pdf(&target, &path).await {
+                    tracing::error!("Failed to export PDF: {}", e);
+                }
+            });
+        });
+}
+
+/// Escribe en `path` el PDF de la página que muestra `window`
+///
+/// # Returns
+/// Ruta del fichero escrito
+pub async fn export_page_pdf<R: Runtime>(window: &WebviewWindow<R>, path: &Path) -> Result<PathBuf, AppError> {
+    let path = validate_pdf_path(path)?;
+    let pdf = create_pdf(window).await?;
+
+    std::fs::write(&path, pdf).map_err(|e| AppError::Internal(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    tracing::info!("Exported {} as PDF", window.label());
+
+    Ok(path)
+}
+
+/// Genera el PDF con `createPDFWithConfiguration:completionHandler:`
+///
+/// Sin configuración, WebKit captura todo el contenido de la página.
+#[cfg(target_os = "macos")]
+async fn create_pdf<R: Runtime>(window: &WebviewWindow<R>) -> Result<Vec<u8>, String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+
+    window
+        .with_webview(move |webview| {
+            use std::cell::Cell;
+
+            use block2::RcBlock;
+            use objc2_foundation::{NSData, NSError};
+            use objc2_web_kit::WKWebView;
+
+            let sender = Cell::new(Some(sender));
+            let handler = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+                // SAFETY: WebKit pasa punteros válidos (o nulos) durante la llamada
+                let result = match unsafe { (data.as_ref(), error.as_ref()) } {
+                    (Some(data), _) => Ok(data.to_vec()),
+                    (None, Some(error)) => Err(error.localizedDescription().to_string()),
+                    (None, None) => Err("WebKit returned no PDF data".to_string()),
+                };
+                if let Some(sender) = sender.take() {
+                    let _ = sender.send(result);
+                }
+            });
+
+            // SAFETY: `inner()` es el WKWebView de la ventana y `with_webview`
+            // ejecuta el closure en el hilo principal
+            unsafe {
+                let webview: &WKWebView = &*webview.inner().cast();
+                webview.createPDFWithConfiguration_completionHandler(None, &handler);
+            }
+        })
+        .map_err(|e| format!("Failed to access webview: {}", e))?;
+
+    receiver.await.map_err(|_| "PDF export was interrupted".to_string())?
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn create_pdf<R: Runtime>(_window: &WebviewWindow<R>) -> Result<Vec<u8>, String> {
+    Err("PDF export is only available on macOS".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_file_name() {
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        assert_eq!(pdf_file_name(Some(&url("https://x.com/home"))), "x-home.pdf");
+        assert_eq!(
+            pdf_file_name(Some(&url("https://x.com/alice/status/123?s=20"))),
+            "x-alice-status-123.pdf"
+        );
+        assert_eq!(pdf_file_name(Some(&url("https://x.com/"))), "x.pdf");
+        // Caracteres que no valen en un nombre de fichero
+        assert_eq!(pdf_file_name(Some(&url("https://x.com/search/a%2F..%3A"))), "x-search-a2F3A.pdf");
+        assert_eq!(pdf_file_name(None), "x.pdf");
+    }
+
+    #[test]
+    fn test_validate_pdf_path() {
+        assert!(validate_pdf_path(Path::new("/tmp/page.pdf")).is_ok());
+        assert!(validate_pdf_path(Path::new("/tmp/Page.PDF")).is_ok());
+
+        assert!(validate_pdf_path(Path::new("page.pdf")).is_err());
+        assert!(validate_pdf_path(Path::new("/tmp/page.txt")).is_err());
+        assert!(validate_pdf_path(Path::new("/tmp/page")).is_err());
+        assert!(validate_pdf_path(Path::new("/Users/alice/.zshrc")).is_err());
+        assert!(validate_pdf_path(Path::new("/tmp/.pdf")).is_err());
+    }
+}
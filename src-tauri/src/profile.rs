@@ -0,0 +1,414 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Perfil de las cuentas: nombre visible y avatar obtenidos de X
+//
+// Tras agregar una cuenta se descarga su página de perfil con las cookies de
+// la sesión guardada y se leen las metaetiquetas `og:title` y `og:image`. El
+// avatar se guarda en `<app_data_dir>/avatars/<uuid>.jpg` para que el menú y
+// el frontend no dependan de la red.
+//
+// Al descargarlo se genera también una miniatura redonda en PNG para el menú
+// Cuentas, así construir el menú solo tiene que leer un archivo pequeño.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::accounts::{load_credentials, AccountsManager};
+use crate::session::StoredSession;
+
+/// Timeout de las peticiones a X
+const PROFILE_TIMEOUT: Duration = Duration::from_secs(15);
+/// Directorio (dentro de app_data_dir) con los avatares descargados
+const AVATARS_DIR: &str = "avatars";
+/// Lado en píxeles de la miniatura del menú (iconos de 18 pt a 2x)
+const THUMBNAIL_SIZE: u32 = 36;
+
+/// Datos públicos del perfil extraídos de la página
+#[derive(Debug, Default, PartialEq)]
+pub struct ProfileInfo {
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Extrae nombre visible y avatar de las metaetiquetas Open Graph
+///
+/// X publica `og:title` como `"Nombre (@usuario) / X"`; solo se conserva el
+/// nombre.
+pub fn parse_profile_html(html: &str) -> ProfileInfo {
+    let display_name = meta_content(html, "og:title").map(|title| match title.rfind(" (@") {
+        Some(end) => title[..end].trim().to_string(),
+        None => title.trim().to_string(),
+    });
+
+    ProfileInfo {
+        display_name: display_name.filter(|name| !name.is_empty()),
+        avatar_url: meta_content(html, "og:image").filter(|url| url.starts_with("https://")),
+    }
+}
+
+/// Valor del atributo `content` de la etiqueta `<meta property="...">`
+fn meta_content(html: &str, property: &str) -> Option<String> {
+    let marker = format!("property=\"{}\"", property);
+    let position = html.find(&marker)?;
+
+    // La etiqueta completa, sea cual sea el orden de los atributos
+    let start = html[..position].rfind('<')?;
+    let end = position + html[position..].find('>')?;
+    let tag = &html[start..end];
+
+    let content_start = tag.find("content=\"")? + "content=\"".len();
+    let content_end = content_start + tag[content_start..].find('"')?;
+
+    Some(decode_entities(&tag[content_start..content_end]))
+}
+
+/// Decodifica las entidades HTML habituales en atributos
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Cabecera `Cookie` con las cookies de una sesión guardada
+pub(crate) fn cookie_header(session: &StoredSession) -> String {
+    session
+        .cookies
+        .iter()
+        .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Ruta del avatar descargado de una cuenta
+pub fn avatar_path(data_dir: &Path, uuid: &str) -> PathBuf {
+    data_dir.join(AVATARS_DIR).join(format!("{}.jpg", uuid))
+}
+
+/// Ruta de la miniatura del avatar para el menú
+pub fn avatar_thumbnail_path(data_dir: &Path, uuid: &str) -> PathBuf {
+    data_dir.join(AVATARS_DIR).join(format!("{}_menu.png", uuid))
+}
+
+/// Decodifica un PNG a RGBA de 8 bits
+fn decode_png(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info().map_err(|e| format!("Invalid PNG: {}", e))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(|e| format!("Invalid PNG: {}", e))?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buffer.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err("Unexpected indexed PNG after expansion".to_string()),
+    };
+
+    Ok((rgba, info.width, info.height))
+}
+
+/// Codifica una imagen RGBA de 8 bits como PNG
+fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+
+    Ok(bytes)
+}
+
+/// Recorta la imagen en un círculo (con el borde suavizado)
+fn round_mask(rgba: &mut [u8], width: u32, height: u32) {
+    let radius = width.min(height) as f32 / 2.0;
+    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+
+    for (index, pixel) in rgba.chunks_mut(4).enumerate() {
+        let x = (index as u32 % width) as f32 + 0.5;
+        let y = (index as u32 / width) as f32 + 0.5;
+        let distance = ((x - center_x).powi(2) + (y - center_y).powi(2)).sqrt();
+        let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+
+        pixel[3] = (pixel[3] as f32 * coverage).round() as u8;
+    }
+}
+
+/// Genera la miniatura redonda del avatar para el menú
+///
+/// El escalado (y la conversión de JPEG a PNG) lo hace `sips`, incluido en
+/// macOS; el recorte en círculo se aplica aquí.
+fn generate_thumbnail(avatar: &Path, thumbnail: &Path) -> Result<(), String> {
+    let scaled = thumbnail.with_extension("tmp.png");
+    let size = THUMBNAIL_SIZE.to_string();
+
+    let output = std::process::Command::new("sips")
+        .args(["-s", "format", "png", "-z", &size, &size])
+        .arg(avatar)
+        .arg("--out")
+        .arg(&scaled)
+        .output()
+        .map_err(|e| format!("Failed to run sips: {}", e))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&scaled);
+        return Err(format!("sips failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let bytes = std::fs::read(&scaled).map_err(|e| format!("Failed to read scaled avatar: {}", e));
+    let _ = std::fs::remove_file(&scaled);
+
+    let (mut rgba, width, height) = decode_png(&bytes?)?;
+    round_mask(&mut rgba, width, height);
+
+    std::fs::write(thumbnail, encode_png(&rgba, width, height)?)
+        .map_err(|e| format!("Failed to save avatar thumbnail: {}", e))
+}
+
+/// Icono del menú para una cuenta a partir de su miniatura
+///
+/// `None` si no hay miniatura o no se puede leer; el menú usa entonces un
+/// icono genérico.
+pub fn avatar_icon(data_dir: &Path, uuid: &str) -> Option<tauri::image::Image<'static>> {
+    let bytes = std::fs::read(avatar_thumbnail_path(data_dir, uuid)).ok()?;
+
+    match decode_png(&bytes) {
+        Ok((rgba, width, height)) => Some(tauri::image::Image::new_owned(rgba, width, height)),
+        Err(e) => {
+            tracing::warn!("Ignoring corrupt avatar thumbnail for {}: {}", uuid, e);
+            None
+        }
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(PROFILE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Descarga la página de perfil de `username` y extrae sus datos
+async fn fetch_profile(username: &str, session: Option<&StoredSession>) -> Result<ProfileInfo, String> {
+    let mut request = http_client()?
+        .get(format!("https://x.com/{}", username))
+        .header("User-Agent", "X-Mac-Client");
+
+    if let Some(session) = session {
+        request = request.header("Cookie", cookie_header(session));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Profile request failed: {}", response.status()));
+    }
+
+    let html = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read profile page: {}", e))?;
+
+    Ok(parse_profile_html(&html))
+}
+
+/// Descarga el avatar a `path`
+async fn download_avatar(url: &str, path: &Path) -> Result<(), String> {
+    let response = http_client()?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Avatar request failed: {}", response.status()));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read avatar: {}", e))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create avatars dir: {}", e))?;
+    }
+
+    std::fs::write(path, &bytes).map_err(|e| format!("Failed to save avatar: {}", e))
+}
+
+/// Obtiene el perfil de `username` desde X y lo guarda en su `AccountInfo`
+///
+/// Los campos que no se puedan extraer conservan el valor anterior.
+pub async fn refresh_profile<R: Runtime>(app: &AppHandle<R>, username: &str) -> Result<ProfileInfo, String> {
+    let manager = app.state::<AccountsManager>();
+    let username = &manager.resolve_username(username)?;
+    let account = manager
+        .list_accounts(true)?
+        .into_iter()
+        .find(|a| &a.username == username)
+        .ok_or(format!("Account '{}' not found", username))?;
+
+    let session = load_credentials(manager.store(), username)?
+        .and_then(|creds| creds.session_data)
+        .and_then(|data| StoredSession::parse(&data));
+
+    let profile = fetch_profile(username, session.as_ref()).await?;
+
+    // El avatar se descarga antes de guardar el perfil: al guardarlo se
+    // reconstruye el menú, que ya encuentra la miniatura
+    if let Some(url) = &profile.avatar_url {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+        let path = avatar_path(&data_dir, &account.uuid);
+
+        match download_avatar(url, &path).await {
+            Ok(()) => {
+                if let Err(e) = generate_thumbnail(&path, &avatar_thumbnail_path(&data_dir, &account.uuid)) {
+                    tracing::warn!("Failed to generate avatar thumbnail for {}: {}", username, e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to download avatar for {}: {}", username, e),
+        }
+    }
+
+    manager.update_account_profile(
+        username,
+        profile.display_name.clone().or(account.display_name),
+        profile.avatar_url.clone().or(account.avatar_url),
+    )?;
+
+    // El nombre visible de la cuenta activa aparece en el título
+    crate::window::update_window_identity(app);
+
+    tracing::info!("Updated profile for {}", username);
+
+    Ok(profile)
+}
+
+/// Lanza `refresh_profile` en segundo plano; los fallos de red solo se registran
+pub fn spawn_refresh<R: Runtime>(app: &AppHandle<R>, username: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh_profile(&app, &username).await {
+            tracing::warn!("Failed to fetch profile for {}: {}", username, e);
+        }
+    });
+}
+
+/// Borra el avatar descargado (y su miniatura) de una cuenta eliminada
+pub fn remove_avatar<R: Runtime>(app: &AppHandle<R>, uuid: &str) {
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+
+    for path in [avatar_path(&data_dir, uuid), avatar_thumbnail_path(&data_dir, uuid)] {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                tracing::warn!("Failed to remove avatar {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::StoredCookie;
+
+    #[test]
+    fn test_parse_profile_html() {
+        let html = r#"<html><head>
+            <meta property="og:title" content="Alice &amp; Co (@alice) / X">
+            <meta content="https://pbs.twimg.com/profile_images/1/a_400x400.jpg" property="og:image"/>
+        </head></html>"#;
+
+        let profile = parse_profile_html(html);
+        assert_eq!(profile.display_name.as_deref(), Some("Alice & Co"));
+        assert_eq!(
+            profile.avatar_url.as_deref(),
+            Some("https://pbs.twimg.com/profile_images/1/a_400x400.jpg")
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_html_without_meta() {
+        assert_eq!(parse_profile_html("<html></html>"), ProfileInfo::default());
+
+        // Avatares que no son https se descartan
+        let html = r#"<meta property="og:image" content="javascript:alert(1)">"#;
+        assert_eq!(parse_profile_html(html).avatar_url, None);
+    }
+
+    #[test]
+    fn test_round_mask_and_png_roundtrip() {
+        let size = 8;
+        let mut rgba = vec![200; (size * size * 4) as usize];
+        round_mask(&mut rgba, size, size);
+
+        // Esquinas transparentes, centro opaco
+        assert_eq!(rgba[3], 0);
+        let center = ((size / 2 * size + size / 2) * 4 + 3) as usize;
+        assert_eq!(rgba[center], 200);
+
+        let png = encode_png(&rgba, size, size).unwrap();
+        assert_eq!(decode_png(&png).unwrap(), (rgba, size, size));
+    }
+
+    #[test]
+    fn test_avatar_icon_ignores_missing_or_corrupt_files() {
+        let data_dir = std::env::temp_dir().join(format!("xmac-avatar-test-{}", std::process::id()));
+        std::fs::create_dir_all(data_dir.join(AVATARS_DIR)).unwrap();
+
+        assert!(avatar_icon(&data_dir, "missing").is_none());
+
+        std::fs::write(avatar_thumbnail_path(&data_dir, "corrupt"), b"not a png").unwrap();
+        assert!(avatar_icon(&data_dir, "corrupt").is_none());
+
+        let rgba = vec![255; 4 * 4 * 4];
+        std::fs::write(avatar_thumbnail_path(&data_dir, "ok"), encode_png(&rgba, 4, 4).unwrap()).unwrap();
+        let icon = avatar_icon(&data_dir, "ok").unwrap();
+        assert_eq!((icon.width(), icon.height()), (4, 4));
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_avatar_path_and_cookie_header() {
+        let path = avatar_path(Path::new("/data"), "1234");
+        assert_eq!(path, PathBuf::from("/data/avatars/1234.jpg"));
+
+        let cookie = |name: &str, value: &str| StoredCookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: None,
+            path: None,
+            expires: None,
+            secure: true,
+            http_only: true,
+        };
+        let session = StoredSession { cookies: vec![cookie("auth_token", "a"), cookie("ct0", "b")] };
+        assert_eq!(cookie_header(&session), "auth_token=a; ct0=b");
+    }
+}
@@ -0,0 +1,458 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Rotación de claves de encriptación
+//
+// Genera un nuevo secreto de derivación y re-encripta la lista de cuentas y
+// las credenciales de cada cuenta. La escritura es transaccional: los blobs
+// nuevos se escriben primero bajo nombres temporales y solo después se
+// intercambian; si algo falla antes de confirmar, las entradas originales
+// quedan intactas.
+//
+// Cada blob se vuelve a cifrar con los mismos parámetros de Argon2id con los
+// que estaba: la rotación cambia el secreto, no la configuración del KDF.
+//
+// La app rota con `rotate_manager_keys`, que mantiene bloqueada la lista de
+// cuentas del `AccountsManager` mientras dura: ningún cambio de cuentas puede
+// perderse ni guardarse con el secreto anterior.
+
+use rand::{rngs::OsRng, Rng};
+use serde::Serialize;
+
+use crate::accounts::{
+    accounts_list_backup_key, accounts_list_key, credentials_entries, derive_credentials_key, derive_master_key,
+    key_secret_key, load_accounts_list, load_key_secret, read_credentials_blob, AccountsList, AccountsManager,
+};
+use crate::error::AppError;
+use crate::kdf::{self, KdfParams};
+use crate::keycache;
+use crate::secrets::SecretStore;
+use crate::hash_key;
+
+/// Tamaño del secreto de derivación generado en cada rotación
+const KEY_SECRET_SIZE: usize = 32;
+
+/// Resultado de una rotación de claves
+#[derive(Serialize, Debug, Default)]
+pub struct RotationReport {
+    /// Número de entradas re-encriptadas (lista de cuentas + credenciales)
+    pub rotated: usize,
+    /// Entradas que no se pudieron desencriptar; si hay alguna no se escribe nada
+    pub failures: Vec<RotationFailure>,
+}
+
+/// Fallo al rotar una entrada concreta
+#[derive(Serialize, Debug)]
+pub struct RotationFailure {
+    pub item: String,
+    pub error: String,
+}
+
+/// Entrada lista para escribirse bajo las claves nuevas
+struct PendingEntry {
+    key: String,
+    temp_key: String,
+    old_value: Vec<u8>,
+    new_value: Vec<u8>,
+}
+
+/// Nombre temporal de una entrada durante la rotación
+pub(crate) fn temp_key(item: &str) -> String {
+    hash_key(&format!("rotating_{}", item))
+}
+
+/// Lee una entrada encriptada (base64 en UTF-8) y la desencripta
+fn read_entry(
+    store: &dyn SecretStore,
+    key: &str,
+    derive: impl FnOnce(&KdfParams) -> Result<[u8; 32], String>,
+) -> Result<Option<(Vec<u8>, String, KdfParams)>, String> {
+    let Some(raw) = store.get(key) else {
+        return Ok(None);
+    };
+
+    let encrypted = String::from_utf8(raw.clone()).map_err(|e| e.to_string())?;
+    let (params, _) = kdf::open(&encrypted)?;
+    let plaintext = kdf::decrypt_with(&encrypted, derive)?;
+
+    Ok(Some((raw, plaintext, params)))
+}
+
+/// Lee el blob cifrado de unas credenciales (reuniendo sus partes) y lo desencripta
+fn read_credentials(
+    store: &dyn SecretStore,
+    username: &str,
+    derive: impl FnOnce(&KdfParams) -> Result<[u8; 32], String>,
+) -> Result<Option<(Vec<u8>, String, KdfParams)>, String> {
+    let Some(blob) = read_credentials_blob(store, username)? else {
+        return Ok(None);
+    };
+
+    let encrypted = String::from_utf8(blob.clone()).map_err(|e| e.to_string())?;
+    let (params, _) = kdf::open(&encrypted)?;
+    let plaintext = kdf::decrypt_with(&encrypted, derive)?;
+
+    Ok(Some((blob, plaintext, params)))
+}
+
+/// Re-encripta todas las entradas bajo un secreto de derivación nuevo.
+///
+/// # Returns
+/// Un `RotationReport`. Si `failures` no está vacío no se ha escrito nada.
+///
+/// # Errors
+/// Retorna error si falla una escritura; en ese caso se restauran las
+/// entradas originales antes de retornar.
+pub fn rotate_keys(store: &dyn SecretStore) -> Result<RotationReport, String> {
+    let mut report = RotationReport::default();
+
+    let old_secret = load_key_secret(store);
+    let mut new_secret = [0u8; KEY_SECRET_SIZE];
+    OsRng.fill(&mut new_secret);
+
+    // Fase 1: desencriptar todo en memoria con las claves actuales
+    let list_key = accounts_list_key();
+
+    let list_entry = match read_entry(store, &list_key, |params| derive_master_key(old_secret.as_deref(), params)) {
+        Ok(entry) => entry,
+        Err(e) => {
+            report.failures.push(RotationFailure { item: "accounts_list".to_string(), error: e });
+            return Ok(report);
+        }
+    };
+
+    let accounts_list: Option<AccountsList> = match &list_entry {
+        Some((_, json, _)) => Some(
+            serde_json::from_str(json).map_err(|e| format!("Failed to parse accounts list: {}", e))?,
+        ),
+        None => None,
+    };
+
+    let mut pending = Vec::new();
+
+    let mut rotated = 0;
+
+    if let Some((old_value, json, params)) = list_entry {
+        rotated += 1;
+        pending.push(PendingEntry {
+            temp_key: temp_key("accounts_list"),
+            key: list_key,
+            old_value,
+            new_value: kdf::encrypt_with(&params, &json, |params| derive_master_key(Some(&new_secret), params))?
+                .into_bytes(),
+        });
+    }
+
+    for account in accounts_list.iter().flat_map(|list| list.accounts.iter()) {
+        let derive_old = |params: &KdfParams| derive_credentials_key(&account.username, old_secret.as_deref(), params);
+
+        match read_credentials(store, &account.username, derive_old) {
+            Ok(Some((old_blob, json, params))) => {
+                let new_blob = kdf::encrypt_with(&params, &json, |params| {
+                    derive_credentials_key(&account.username, Some(&new_secret), params)
+                })?
+                .into_bytes();
+
+                // Mismo texto y mismos parámetros => mismo tamaño cifrado => mismas partes
+                let old_entries = credentials_entries(&account.username, &old_blob);
+                let new_entries = credentials_entries(&account.username, &new_blob);
+
+                for ((key, old_value), (_, new_value)) in old_entries.into_iter().zip(new_entries) {
+                    pending.push(PendingEntry {
+                        temp_key: temp_key(&format!("credentials_{}", key)),
+                        key,
+                        old_value,
+                        new_value,
+                    });
+                }
+                rotated += 1;
+            }
+            Ok(None) => {
+                tracing::warn!("No credentials to rotate for account: {}", account.username);
+            }
+            Err(e) => report.failures.push(RotationFailure {
+                item: account.username.clone(),
+                error: e,
+            }),
+        }
+    }
+
+    if !report.failures.is_empty() {
+        tracing::error!("Key rotation aborted: {} items failed to decrypt", report.failures.len());
+        return Ok(report);
+    }
+
+    // Fase 2: escribir los blobs nuevos bajo nombres temporales
+    let secret_temp_key = temp_key("key_secret");
+    let mut written = Vec::new();
+
+    for entry in &pending {
+        if let Err(e) = store.set(&entry.temp_key, &entry.new_value) {
+            discard_temp_entries(store, &written);
+            return Err(format!("Key rotation aborted while staging: {}", e));
+        }
+        written.push(entry.temp_key.clone());
+    }
+
+    if let Err(e) = store.set(&secret_temp_key, &new_secret) {
+        discard_temp_entries(store, &written);
+        return Err(format!("Key rotation aborted while staging: {}", e));
+    }
+    written.push(secret_temp_key);
+
+    // Fase 3: intercambiar; el secreto nuevo se escribe al final como confirmación
+    for (index, entry) in pending.iter().enumerate() {
+        if let Err(e) = store.set(&entry.key, &entry.new_value) {
+            restore_entries(store, &pending[..index]);
+            discard_temp_entries(store, &written);
+            return Err(format!("Key rotation rolled back: {}", e));
+        }
+    }
+
+    if let Err(e) = store.set(&key_secret_key(), &new_secret) {
+        restore_entries(store, &pending);
+        discard_temp_entries(store, &written);
+        return Err(format!("Key rotation rolled back: {}", e));
+    }
+
+    discard_temp_entries(store, &written);
+
+    // La copia de seguridad sigue cifrada con la clave anterior y ya no serviría
+    store.delete(&accounts_list_backup_key());
+
+    // Las claves cacheadas corresponden al secreto anterior
+    keycache::invalidate_keys();
+    for account in accounts_list.iter().flat_map(|list| list.accounts.iter()) {
+        keycache::invalidate(&account.username);
+    }
+
+    report.rotated = rotated;
+    tracing::info!("Key rotation completed: {} items rotated", report.rotated);
+
+    Ok(report)
+}
+
+/// Rota las claves con la lista de cuentas de `manager` bloqueada
+///
+/// Al terminar, la lista en memoria se vuelve a leer del almacén con el
+/// secreto nuevo. Si la rotación no llega a escribir nada, la lista queda
+/// como estaba.
+pub fn rotate_manager_keys(manager: &AccountsManager) -> Result<RotationReport, AppError> {
+    manager.update(|store, list| {
+        let report = rotate_keys(store)?;
+        if report.failures.is_empty() {
+            *list = load_accounts_list(store)?.0;
+        }
+
+        Ok(report)
+    })
+}
+
+/// Elimina las entradas temporales escritas durante la rotación
+fn discard_temp_entries(store: &dyn SecretStore, keys: &[String]) {
+    for key in keys {
+        store.delete(key);
+    }
+}
+
+/// Vuelve a escribir los blobs originales de las entradas ya intercambiadas
+fn restore_entries(store: &dyn SecretStore, entries: &[PendingEntry]) {
+    for entry in entries {
+        if let Err(e) = store.set(&entry.key, &entry.old_value) {
+            tracing::error!("Failed to restore entry during rollback: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::{credentials_key, AccountInfo, Credentials};
+    use crate::secrets::InMemoryStore;
+    use crate::{decrypt_data, encrypt_data};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Store que falla únicamente en la escritura número `fail_at`
+    struct FailingStore {
+        inner: InMemoryStore,
+        writes: AtomicUsize,
+        fail_at: usize,
+    }
+
+    impl SecretStore for FailingStore {
+        fn get(&self, key: &str) -> Option<Vec<u8>> {
+            self.inner.get(key)
+        }
+
+        fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+            if self.writes.fetch_add(1, Ordering::SeqCst) == self.fail_at {
+                return Err("simulated keychain failure".to_string());
+            }
+            self.inner.set(key, value)
+        }
+
+        fn delete(&self, key: &str) {
+            self.inner.delete(key)
+        }
+
+        fn list_keys(&self) -> Vec<String> {
+            self.inner.list_keys()
+        }
+    }
+
+    fn seed(store: &dyn SecretStore, usernames: &[&str]) {
+        let list = AccountsList {
+            accounts: usernames
+                .iter()
+                .enumerate()
+                .map(|(i, u)| AccountInfo {
+                    username: u.to_string(),
+                    uuid: format!("uuid-{}", u),
+                    created_at: 1,
+                    last_used: 1,
+                    last_login: 0,
+                    display_name: None,
+                    avatar_url: None,
+                    order: i as i32,
+                    needs_relogin: false,
+                    pinned: false,
+                    archived: false,
+                    label: None,
+                    color: None,
+                    proxy_url: None,
+                    session_valid: None,
+                    last_validated: 0,
+                })
+                .collect(),
+            active_username: usernames.first().map(|u| u.to_string()),
+            ..Default::default()
+        };
+
+        let master = derive_master_key(None, &KdfParams::default()).unwrap();
+        let json = serde_json::to_string(&list).unwrap();
+        store.set(&accounts_list_key(), encrypt_data(&json, &master).unwrap().as_bytes()).unwrap();
+
+        for u in usernames {
+            let creds = Credentials {
+                username: u.to_string(),
+                uuid: format!("uuid-{}", u),
+                token: Some(format!("token-{}", u)),
+                session_data: None,
+                created_at: 1,
+                last_used: 1,
+            };
+            let key = derive_credentials_key(u, None, &KdfParams::default()).unwrap();
+            let json = serde_json::to_string(&creds).unwrap();
+            store.set(&credentials_key(u), encrypt_data(&json, &key).unwrap().as_bytes()).unwrap();
+        }
+    }
+
+    fn decrypt_with_current_keys(store: &dyn SecretStore, username: &str) -> Result<String, String> {
+        let secret = load_key_secret(store);
+        let raw = String::from_utf8(store.get(&credentials_key(username)).unwrap()).unwrap();
+        kdf::decrypt_with(&raw, |params| derive_credentials_key(username, secret.as_deref(), params))
+    }
+
+    #[test]
+    fn test_rotation_reencrypts_everything() {
+        let store = InMemoryStore::new();
+        seed(&store, &["alice", "bob"]);
+
+        let report = rotate_keys(&store).unwrap();
+
+        assert_eq!(report.rotated, 3);
+        assert!(report.failures.is_empty());
+        assert!(load_key_secret(&store).is_some());
+
+        // Solo quedan las entradas finales + el secreto (sin temporales)
+        assert_eq!(store.snapshot().len(), 4);
+
+        assert!(decrypt_with_current_keys(&store, "alice").unwrap().contains("token-alice"));
+
+        // Las claves antiguas ya no abren los datos
+        let old_key = derive_credentials_key("alice", None, &KdfParams::default()).unwrap();
+        let raw = String::from_utf8(store.get(&credentials_key("alice")).unwrap()).unwrap();
+        assert!(decrypt_data(&raw, &old_key).is_err());
+    }
+
+    #[test]
+    fn test_rotation_keeps_kdf_params() {
+        let store = InMemoryStore::new();
+        seed(&store, &["alice"]);
+
+        // Credenciales cifradas con parámetros propios
+        let custom = KdfParams { m_cost: 1024, t_cost: 1, p_cost: 1 };
+        let raw = String::from_utf8(store.get(&credentials_key("alice")).unwrap()).unwrap();
+        let json = kdf::decrypt_with(&raw, |params| derive_credentials_key("alice", None, params)).unwrap();
+        let sealed = kdf::encrypt_with(&custom, &json, |params| derive_credentials_key("alice", None, params)).unwrap();
+        store.set(&credentials_key("alice"), sealed.as_bytes()).unwrap();
+
+        let report = rotate_keys(&store).unwrap();
+        assert!(report.failures.is_empty());
+
+        let raw = String::from_utf8(store.get(&credentials_key("alice")).unwrap()).unwrap();
+        assert_eq!(kdf::open(&raw).unwrap().0, custom);
+        assert!(decrypt_with_current_keys(&store, "alice").unwrap().contains("token-alice"));
+    }
+
+    #[test]
+    fn test_rotation_twice() {
+        let store = InMemoryStore::new();
+        seed(&store, &["alice"]);
+
+        rotate_keys(&store).unwrap();
+        let report = rotate_keys(&store).unwrap();
+
+        assert_eq!(report.rotated, 2);
+        assert!(decrypt_with_current_keys(&store, "alice").is_ok());
+    }
+
+    #[test]
+    fn test_rotation_reports_corrupted_item_and_writes_nothing() {
+        let store = InMemoryStore::new();
+        seed(&store, &["alice", "bob"]);
+        store.set(&credentials_key("bob"), b"corrupted").unwrap();
+
+        let before = store.snapshot();
+        let report = rotate_keys(&store).unwrap();
+
+        assert_eq!(report.rotated, 0);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].item, "bob");
+        assert_eq!(store.snapshot(), before);
+    }
+
+    #[test]
+    fn test_rotation_rolls_back_on_write_failure() {
+        let inner = InMemoryStore::new();
+        seed(&inner, &["alice", "bob"]);
+        let before = inner.snapshot();
+
+        // 3 entradas + secreto en staging (4 escrituras), falla el segundo intercambio
+        let store = FailingStore { inner, writes: AtomicUsize::new(0), fail_at: 5 };
+
+        assert!(rotate_keys(&store).is_err());
+        assert_eq!(store.inner.snapshot(), before);
+    }
+
+    #[test]
+    fn test_manager_rotation_keeps_the_list_usable() {
+        let store: &'static InMemoryStore = Box::leak(Box::new(InMemoryStore::new()));
+        seed(store, &["alice"]);
+        let manager = AccountsManager::new(store);
+        assert_eq!(manager.get_active_account().unwrap().as_deref(), Some("alice"));
+
+        let report = rotate_manager_keys(&manager).unwrap();
+        assert_eq!(report.rotated, 2);
+
+        // Los cambios posteriores se guardan con el secreto nuevo
+        manager.add_account("bob", Some("t".to_string()), None).unwrap();
+        let reloaded = AccountsManager::new(store);
+        let names: Vec<String> = reloaded.list_accounts(true).unwrap().into_iter().map(|a| a.username).collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+        assert!(decrypt_with_current_keys(store, "alice").unwrap().contains("token-alice"));
+    }
+}
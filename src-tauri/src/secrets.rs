@@ -0,0 +1,309 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Abstracción del almacenamiento de secretos (macOS Keychain, Secret Service
+// en Linux y Credential Manager en Windows)
+
+/// Service ID bajo el que se guardan todas las entradas del Keychain
+#[cfg(any(target_os = "macos", feature = "cross-platform-secrets"))]
+pub const SERVICE: &str = "com.twitter.xmac";
+
+/// Almacén clave-valor de secretos
+///
+/// Las claves son los nombres de cuenta (ya hasheados) de las entradas del
+/// Keychain; los valores son los blobs encriptados tal cual se guardan.
+pub trait SecretStore: Send + Sync {
+    /// Lee una entrada, `None` si no existe
+    fn get(&self, key: &str) -> Option<Vec<u8>>;
+    /// Crea o reemplaza una entrada
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String>;
+    /// Elimina una entrada (no falla si no existe)
+    fn delete(&self, key: &str);
+    /// Nombres de todas las entradas del servicio
+    fn list_keys(&self) -> Vec<String>;
+}
+
+/// Almacén de secretos de la aplicación
+///
+/// En macOS es el Keychain del sistema. En Linux y Windows se usa el almacén
+/// del sistema si se compila con la feature `cross-platform-secrets`; sin
+/// ella, un almacén en memoria que no persiste entre ejecuciones.
+pub fn store() -> &'static dyn SecretStore {
+    #[cfg(target_os = "macos")]
+    {
+        &MacKeychainStore
+    }
+
+    #[cfg(all(not(target_os = "macos"), feature = "cross-platform-secrets"))]
+    {
+        static STORE: KeyringStore = KeyringStore { service: SERVICE };
+        &STORE
+    }
+
+    #[cfg(all(not(target_os = "macos"), not(feature = "cross-platform-secrets")))]
+    {
+        static STORE: std::sync::OnceLock<InMemoryStore> = std::sync::OnceLock::new();
+        STORE.get_or_init(|| {
+            tracing::warn!("No persistent secret store available; credentials will not be saved");
+            InMemoryStore::new()
+        })
+    }
+}
+
+/// Implementación sobre el Keychain de macOS (Security Framework)
+#[cfg(target_os = "macos")]
+pub struct MacKeychainStore;
+
+#[cfg(target_os = "macos")]
+impl SecretStore for MacKeychainStore {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        security_framework::passwords::get_generic_password(SERVICE, key).ok()
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        security_framework::passwords::set_generic_password(SERVICE, key, value)
+            .map_err(|e| format!("Keychain write failed: {}", e))
+    }
+
+    fn delete(&self, key: &str) {
+        let _ = security_framework::passwords::delete_generic_password(SERVICE, key);
+    }
+
+    fn list_keys(&self) -> Vec<String> {
+        use security_framework::item::{ItemClass, ItemSearchOptions, Limit, SearchResult};
+
+        let results = ItemSearchOptions::new()
+            .class(ItemClass::generic_password())
+            .service(SERVICE)
+            .load_attributes(true)
+            .limit(Limit::All)
+            .search()
+            .unwrap_or_default();
+
+        results
+            .iter()
+            .filter_map(|result| match result {
+                SearchResult::Dict(_) => result.simplify_dict()?.get("acct").cloned(),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Implementación sobre el crate `keyring` (Secret Service / Credential Manager)
+///
+/// Estos backends no permiten enumerar entradas, así que los nombres se
+/// mantienen en una entrada índice propia.
+#[cfg(all(not(target_os = "macos"), feature = "cross-platform-secrets"))]
+pub struct KeyringStore {
+    /// Servicio de las entradas (`SERVICE` salvo en los tests)
+    service: &'static str,
+}
+
+#[cfg(all(not(target_os = "macos"), feature = "cross-platform-secrets"))]
+impl KeyringStore {
+    /// Entrada que guarda la lista de nombres (JSON)
+    const INDEX_KEY: &'static str = "xmac_index";
+
+    fn entry(&self, key: &str) -> Option<keyring::Entry> {
+        keyring::Entry::new(self.service, key)
+            .map_err(|e| tracing::error!("Secret store unavailable: {}", e))
+            .ok()
+    }
+
+    fn read_index(&self) -> Vec<String> {
+        self.entry(Self::INDEX_KEY)
+            .and_then(|entry| entry.get_secret().ok())
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_index(&self, keys: &[String]) {
+        let Some(entry) = self.entry(Self::INDEX_KEY) else {
+            return;
+        };
+
+        match serde_json::to_vec(keys) {
+            Ok(data) => {
+                if let Err(e) = entry.set_secret(&data) {
+                    tracing::error!("Failed to update secret store index: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize secret store index: {}", e),
+        }
+    }
+}
+
+#[cfg(all(not(target_os = "macos"), feature = "cross-platform-secrets"))]
+impl SecretStore for KeyringStore {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.entry(key)?.get_secret().ok()
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        self.entry(key)
+            .ok_or_else(|| "Secret store unavailable".to_string())?
+            .set_secret(value)
+            .map_err(|e| format!("Secret store write failed: {}", e))?;
+
+        let mut keys = self.read_index();
+        if !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+            self.write_index(&keys);
+        }
+
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) {
+        if let Some(entry) = self.entry(key) {
+            let _ = entry.delete_credential();
+        }
+
+        let mut keys = self.read_index();
+        let len = keys.len();
+        keys.retain(|k| k != key);
+        if keys.len() != len {
+            self.write_index(&keys);
+        }
+    }
+
+    fn list_keys(&self) -> Vec<String> {
+        self.read_index()
+    }
+}
+
+/// Implementación en memoria (tests y plataformas sin Keychain)
+#[cfg(any(test, all(not(target_os = "macos"), not(feature = "cross-platform-secrets"))))]
+#[derive(Default)]
+pub struct InMemoryStore {
+    items: std::sync::Mutex<std::collections::HashMap<String, Vec<u8>>>,
+}
+
+#[cfg(any(test, all(not(target_os = "macos"), not(feature = "cross-platform-secrets"))))]
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copia de todas las entradas (para comparar estados en tests)
+    #[cfg(test)]
+    pub fn snapshot(&self) -> std::collections::HashMap<String, Vec<u8>> {
+        self.items.lock().unwrap().clone()
+    }
+}
+
+#[cfg(any(test, all(not(target_os = "macos"), not(feature = "cross-platform-secrets"))))]
+impl SecretStore for InMemoryStore {
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.items.lock().unwrap().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        self.items.lock().unwrap().insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) {
+        self.items.lock().unwrap().remove(key);
+    }
+
+    fn list_keys(&self) -> Vec<String> {
+        self.items.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_store_roundtrip() {
+        let store = InMemoryStore::new();
+        assert!(store.get("a").is_none());
+
+        store.set("a", b"1").unwrap();
+        store.set("b", b"2").unwrap();
+        assert_eq!(store.get("a").as_deref(), Some(&b"1"[..]));
+
+        let mut keys = store.list_keys();
+        keys.sort();
+        assert_eq!(keys, vec!["a", "b"]);
+
+        store.delete("a");
+        store.delete("missing");
+        assert!(store.get("a").is_none());
+        assert_eq!(store.list_keys(), vec!["b"]);
+    }
+
+    #[test]
+    fn test_in_memory_store_accounts_lifecycle() {
+        use crate::accounts;
+
+        let store: &'static InMemoryStore = Box::leak(Box::new(InMemoryStore::new()));
+        let manager = accounts::AccountsManager::new(store);
+
+        manager.add_account("alice", Some("token-a".to_string()), None).unwrap();
+        manager.add_account("bob", Some("token-b".to_string()), None).unwrap();
+        let usernames: Vec<_> = manager.list_accounts(true).unwrap().into_iter().map(|a| a.username).collect();
+        assert_eq!(usernames, vec!["alice", "bob"]);
+
+        manager.set_active_account("bob").unwrap();
+        assert_eq!(manager.get_active_account().unwrap().as_deref(), Some("bob"));
+
+        manager.remove_account("bob", true).unwrap();
+        assert_eq!(manager.get_active_account().unwrap().as_deref(), Some("alice"));
+        assert!(accounts::load_credentials(store, "bob").unwrap().is_none());
+
+        // Un manager nuevo sobre el mismo almacén ve el mismo estado
+        let reloaded = accounts::AccountsManager::new(store);
+        assert_eq!(reloaded.list_accounts(true).unwrap().len(), 1);
+        let creds = accounts::load_credentials(store, "alice").unwrap().unwrap();
+        assert_eq!(creds.token.as_deref(), Some("token-a"));
+    }
+
+    /// Requiere un Secret Service accesible (sesión de escritorio o
+    /// gnome-keyring en CI): `cargo test --features cross-platform-secrets -- --ignored`
+    #[cfg(all(not(target_os = "macos"), feature = "cross-platform-secrets"))]
+    #[test]
+    #[ignore]
+    fn test_keyring_store_accounts_lifecycle() {
+        use crate::accounts;
+
+        /// Borra todas las entradas del servicio de prueba (también si el test falla)
+        struct Cleanup(&'static KeyringStore);
+
+        impl Drop for Cleanup {
+            fn drop(&mut self) {
+                for key in self.0.list_keys() {
+                    self.0.delete(&key);
+                }
+                if let Some(entry) = self.0.entry(KeyringStore::INDEX_KEY) {
+                    let _ = entry.delete_credential();
+                }
+            }
+        }
+
+        // Servicio propio del test: no toca las cuentas reales de la app
+        let service = format!("{}.test-{}", SERVICE, uuid::Uuid::new_v4().simple());
+        let store: &'static KeyringStore = Box::leak(Box::new(KeyringStore {
+            service: Box::leak(service.into_boxed_str()),
+        }));
+        let _cleanup = Cleanup(store);
+
+        let manager = accounts::AccountsManager::new(store);
+        let username = format!("xmac_test_{}", uuid::Uuid::new_v4().simple());
+
+        manager.add_account(&username, Some("token".to_string()), None).unwrap();
+        assert!(manager.list_accounts(true).unwrap().iter().any(|a| a.username == username));
+
+        manager.set_active_account(&username).unwrap();
+        assert_eq!(manager.get_active_account().unwrap(), Some(username.clone()));
+
+        manager.remove_account(&username, true).unwrap();
+        assert!(accounts::load_credentials(store, &username).unwrap().is_none());
+    }
+}
@@ -0,0 +1,353 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Sesiones por cuenta: intercambio de cookies de x.com al cambiar de cuenta
+//
+// Todas las cuentas comparten el mismo almacén de cookies del WebView, así que
+// cambiar de cuenta implica guardar las cookies de la cuenta saliente en sus
+// credenciales (`session_data`), borrar las de x.com e inyectar las de la
+// cuenta entrante antes de recargar.
+
+use serde::{Deserialize, Serialize};
+use tauri::webview::cookie::time::OffsetDateTime;
+use tauri::webview::Cookie;
+use tauri::{Emitter, Runtime, Url, WebviewWindow};
+
+use crate::accounts::{load_credentials, store_credentials};
+use crate::secrets::SecretStore;
+
+/// Origen cuyas cookies forman la sesión de una cuenta
+const SESSION_URL: &str = "https://x.com";
+/// Página de login a la que se envía al usuario si la sesión no es válida
+const LOGIN_URL: &str = "https://x.com/i/flow/login";
+/// Cookie que identifica la sesión autenticada en X
+const AUTH_COOKIE: &str = "auth_token";
+/// Evento emitido cuando la sesión guardada de una cuenta ya no es válida
+pub const SESSION_EXPIRED_EVENT: &str = "session-expired";
+
+/// Cookie serializable guardada en `Credentials::session_data`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    /// Expiración en segundos Unix; `None` para cookies de sesión
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+/// Sesión de una cuenta (cookies de x.com)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct StoredSession {
+    pub cookies: Vec<StoredCookie>,
+}
+
+/// Resultado de restaurar la sesión de la cuenta entrante
+#[derive(Debug, PartialEq)]
+pub enum SwitchOutcome {
+    /// Cookies inyectadas y ventana recargada
+    Restored,
+    /// Sin sesión válida: se ha enviado al usuario al login
+    Expired,
+}
+
+impl StoredSession {
+    /// Construye la sesión a partir de las cookies del WebView
+    pub fn from_cookies(cookies: &[Cookie<'_>]) -> Self {
+        Self {
+            cookies: cookies
+                .iter()
+                .map(|cookie| StoredCookie {
+                    name: cookie.name().to_string(),
+                    value: cookie.value().to_string(),
+                    domain: cookie.domain().map(str::to_string),
+                    path: cookie.path().map(str::to_string),
+                    expires: cookie.expires_datetime().map(|d| d.unix_timestamp()),
+                    secure: cookie.secure().unwrap_or(false),
+                    http_only: cookie.http_only().unwrap_or(false),
+                })
+                .collect(),
+        }
+    }
+
+    /// Interpreta `session_data`; los formatos antiguos (JSON de localStorage)
+    /// no contienen cookies y se tratan como sesión ausente
+    pub fn parse(session_data: &str) -> Option<Self> {
+        serde_json::from_str(session_data).ok()
+    }
+
+    /// Una sesión es utilizable si tiene `auth_token` y no ha expirado
+    pub fn is_valid_at(&self, now: i64) -> bool {
+        self.cookies.iter().any(|cookie| {
+            cookie.name == AUTH_COOKIE
+                && !cookie.value.is_empty()
+                && cookie.expires.is_none_or(|expires| expires > now)
+        })
+    }
+
+    /// Cookies listas para inyectar en el WebView (se omiten las expiradas)
+    fn to_cookies(&self, now: i64) -> Vec<Cookie<'static>> {
+        self.cookies
+            .iter()
+            .filter(|stored| stored.expires.is_none_or(|expires| expires > now))
+            .map(|stored| {
+                let mut cookie = Cookie::new(stored.name.clone(), stored.value.clone());
+                cookie.set_domain(stored.domain.clone().unwrap_or_else(|| ".x.com".to_string()));
+                cookie.set_path(stored.path.clone().unwrap_or_else(|| "/".to_string()));
+                cookie.set_secure(stored.secure);
+                cookie.set_http_only(stored.http_only);
+
+                if let Some(expires) = stored.expires.and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok()) {
+                    cookie.set_expires(expires);
+                }
+
+                cookie
+            })
+            .collect()
+    }
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn session_url() -> Url {
+    Url::parse(SESSION_URL).expect("valid session url")
+}
+
+/// Lee las cookies actuales de x.com del WebView
+pub fn capture_session<R: Runtime>(window: &WebviewWindow<R>) -> Result<StoredSession, String> {
+    let cookies = window
+        .cookies_for_url(session_url())
+        .map_err(|e| format!("Failed to read cookies: {}", e))?;
+
+    Ok(StoredSession::from_cookies(&cookies))
+}
+
+/// Lee la sesión actual y la serializa para `session_data`
+///
+/// Falla si el WebView no tiene una sesión iniciada (sin `auth_token`). Los
+/// valores de las cookies nunca se registran en los logs.
+pub fn capture_session_data<R: Runtime>(window: &WebviewWindow<R>) -> Result<String, String> {
+    let session = capture_session(window)?;
+    if !session.is_valid_at(now()) {
+        return Err("No active x.com session to capture".to_string());
+    }
+
+    tracing::info!("Captured {} x.com cookies", session.cookies.len());
+
+    serde_json::to_string(&session).map_err(|e| format!("Failed to serialize session: {}", e))
+}
+
+/// Guarda las cookies actuales en las credenciales de `username`
+fn save_session<R: Runtime>(window: &WebviewWindow<R>, store: &dyn SecretStore, username: &str) -> Result<(), String> {
+    let Some(mut credentials) = load_credentials(store, username)? else {
+        return Ok(());
+    };
+
+    let session = capture_session(window)?;
+    if !session.is_valid_at(now()) {
+        // La cuenta saliente no tenía sesión iniciada: no pisar la guardada
+        return Ok(());
+    }
+
+    credentials.session_data = Some(
+        serde_json::to_string(&session).map_err(|e| format!("Failed to serialize session: {}", e))?,
+    );
+
+    Ok(store_credentials(store, &credentials)?)
+}
+
+/// Borra todas las cookies de x.com del WebView
+fn clear_session<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    let cookies = window
+        .cookies_for_url(session_url())
+        .map_err(|e| format!("Failed to read cookies: {}", e))?;
+
+    for cookie in cookies {
+        window
+            .delete_cookie(cookie)
+            .map_err(|e| format!("Failed to delete cookie: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Indica si al eliminar `removed` hay que borrar los datos web del WebView
+///
+/// Solo se borran si la cuenta eliminada es la que está cargada, salvo que se
+/// pida conservarlos explícitamente.
+pub fn should_clear_web_data(removed: &str, loaded: Option<&str>, keep_web_data: bool) -> bool {
+    !keep_web_data && loaded == Some(removed)
+}
+
+/// Borra las cookies y el localStorage de x.com del WebView
+///
+/// El localStorage solo se puede borrar desde la propia página, que en la
+/// ventana principal siempre es de x.com.
+pub fn clear_session_data<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    clear_session(window)?;
+
+    window
+        .eval("try { localStorage.clear(); sessionStorage.clear(); } catch (e) {}")
+        .map_err(|e| format!("Failed to clear local storage: {}", e))
+}
+
+/// Borra cookies, localStorage y caché del WebView (incluido el avatar
+/// cacheado de la cuenta cargada)
+pub fn clear_web_data<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    clear_session_data(window)?;
+
+    window
+        .clear_all_browsing_data()
+        .map_err(|e| format!("Failed to clear web data: {}", e))
+}
+
+/// Navega a la página de login de X
+pub fn open_login<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    window
+        .navigate(Url::parse(LOGIN_URL).expect("valid login url"))
+        .map_err(|e| format!("Failed to open login: {}", e))
+}
+
+/// Cambia la sesión del WebView de `outgoing` a `incoming`
+///
+/// Guarda las cookies de la cuenta saliente, borra las de x.com e inyecta las
+/// de la entrante. Si la entrante no tiene una sesión válida, navega al login
+/// y emite `session-expired` con su username.
+pub fn switch_session<R: Runtime>(
+    window: &WebviewWindow<R>,
+    store: &dyn SecretStore,
+    outgoing: Option<&str>,
+    incoming: &str,
+) -> Result<SwitchOutcome, String> {
+    if let Some(outgoing) = outgoing.filter(|u| *u != incoming) {
+        if let Err(e) = save_session(window, store, outgoing) {
+            tracing::warn!("Failed to save session for {}: {}", outgoing, e);
+        }
+    }
+
+    restore_session(window, store, incoming)
+}
+
+/// Sustituye las cookies de x.com del WebView por las guardadas de `username`
+///
+/// Si no hay una sesión válida guardada, navega al login y emite
+/// `session-expired` con el username.
+pub fn restore_session<R: Runtime>(
+    window: &WebviewWindow<R>,
+    store: &dyn SecretStore,
+    username: &str,
+) -> Result<SwitchOutcome, String> {
+    let session = load_credentials(store, username)?
+        .and_then(|creds| creds.session_data)
+        .and_then(|data| StoredSession::parse(&data))
+        .filter(|session| session.is_valid_at(now()));
+
+    clear_session(window)?;
+
+    let Some(session) = session else {
+        tracing::warn!("No valid session stored for {}, redirecting to login", username);
+
+        if let Err(e) = window.emit(SESSION_EXPIRED_EVENT, username) {
+            tracing::error!("Failed to emit {}: {}", SESSION_EXPIRED_EVENT, e);
+        }
+
+        open_login(window)?;
+
+        return Ok(SwitchOutcome::Expired);
+    };
+
+    for cookie in session.to_cookies(now()) {
+        window
+            .set_cookie(cookie)
+            .map_err(|e| format!("Failed to set cookie: {}", e))?;
+    }
+
+    window
+        .eval("location.reload();")
+        .map_err(|e| format!("Failed to reload: {}", e))?;
+
+    Ok(SwitchOutcome::Restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(name: &str, expires: Option<i64>) -> StoredCookie {
+        StoredCookie {
+            name: name.to_string(),
+            value: "value".to_string(),
+            domain: Some(".x.com".to_string()),
+            path: Some("/".to_string()),
+            expires,
+            secure: true,
+            http_only: true,
+        }
+    }
+
+    #[test]
+    fn test_session_roundtrip_through_cookies() {
+        let mut auth = Cookie::new("auth_token", "secret");
+        auth.set_domain(".x.com");
+        auth.set_path("/");
+        auth.set_secure(true);
+        auth.set_http_only(true);
+        auth.set_expires(OffsetDateTime::from_unix_timestamp(2_000_000_000).unwrap());
+
+        let session = StoredSession::from_cookies(&[auth, Cookie::new("ct0", "csrf")]);
+        assert_eq!(session.cookies.len(), 2);
+        assert_eq!(session.cookies[0].expires, Some(2_000_000_000));
+        assert!(session.cookies[0].http_only);
+
+        let json = serde_json::to_string(&session).unwrap();
+        let parsed = StoredSession::parse(&json).unwrap();
+        assert_eq!(parsed, session);
+
+        let cookies = parsed.to_cookies(1_000_000_000);
+        assert_eq!(cookies[0].name(), "auth_token");
+        assert_eq!(cookies[0].value(), "secret");
+        assert_eq!(cookies[1].domain(), Some("x.com"));
+    }
+
+    #[test]
+    fn test_session_validity() {
+        let now = 1_000;
+
+        let valid = StoredSession { cookies: vec![stored("auth_token", Some(2_000)), stored("ct0", None)] };
+        assert!(valid.is_valid_at(now));
+
+        let expired = StoredSession { cookies: vec![stored("auth_token", Some(500)), stored("ct0", None)] };
+        assert!(!expired.is_valid_at(now));
+        // Las cookies expiradas no se inyectan
+        assert_eq!(expired.to_cookies(now).len(), 1);
+
+        let without_auth = StoredSession { cookies: vec![stored("ct0", None)] };
+        assert!(!without_auth.is_valid_at(now));
+
+        let session_cookie = StoredSession { cookies: vec![stored("auth_token", None)] };
+        assert!(session_cookie.is_valid_at(now));
+    }
+
+    #[test]
+    fn test_web_data_cleared_only_for_loaded_account() {
+        assert!(should_clear_web_data("alice", Some("alice"), false));
+        assert!(!should_clear_web_data("alice", Some("alice"), true));
+        // Podar una cuenta antigua mientras otra está activa no toca el WebView
+        assert!(!should_clear_web_data("alice", Some("bob"), false));
+        assert!(!should_clear_web_data("alice", None, false));
+    }
+
+    #[test]
+    fn test_legacy_session_data_is_ignored() {
+        // Formato anterior: JSON de localStorage 'user'
+        assert!(StoredSession::parse(r#"{"screen_name":"alice"}"#).is_none());
+        assert!(StoredSession::parse("not json").is_none());
+    }
+}
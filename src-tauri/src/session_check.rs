@@ -0,0 +1,179 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Comprobación de las sesiones guardadas contra X
+//
+// Las cookies de una cuenta acaban caducando o invalidándose (cierre de sesión
+// desde otro dispositivo, cambio de contraseña...) y cambiar a esa cuenta solo
+// lleva a la página de login. En segundo plano se pide una página que exige
+// sesión con las cookies guardadas de cada cuenta, como mucho una vez por hora
+// y cuenta, y el resultado se guarda en su `AccountInfo` para que el menú
+// Cuentas lo indique.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::accounts::{load_credentials, unix_now, AccountInfo, AccountsManager};
+use crate::profile::cookie_header;
+use crate::session::StoredSession;
+
+/// Página que redirige al login (o responde 401/403) sin una sesión válida
+const VALIDATION_URL: &str = "https://x.com/settings/account";
+/// Tiempo mínimo entre dos comprobaciones de la misma cuenta (segundos)
+pub const VALIDATION_INTERVAL_SECS: i64 = 60 * 60;
+/// Cada cuánto se buscan cuentas pendientes de comprobar
+const VALIDATION_TICK: Duration = Duration::from_secs(10 * 60);
+/// Timeout de la petición a X
+const VALIDATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Interpreta la respuesta de `VALIDATION_URL`
+///
+/// `Some(true)` si la sesión es válida, `Some(false)` si X la rechaza y `None`
+/// si no se puede saber (error del servidor, límite de peticiones...).
+fn classify_response(status: StatusCode, location: Option<&str>) -> Option<bool> {
+    if status.is_success() {
+        return Some(true);
+    }
+
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Some(false);
+    }
+
+    if status.is_redirection() {
+        return Some(!location.is_some_and(|location| location.contains("login")));
+    }
+
+    None
+}
+
+/// Indica si toca volver a comprobar la sesión de una cuenta
+///
+/// Las archivadas no se comprueban: no se pueden activar.
+fn needs_validation(account: &AccountInfo, now: i64) -> bool {
+    !account.archived && now - account.last_validated >= VALIDATION_INTERVAL_SECS
+}
+
+/// Comprueba una sesión guardada con una petición `HEAD` a X
+///
+/// Sin sesión o con `auth_token` caducado no hace falta preguntar a X.
+async fn check_session(session: Option<&StoredSession>) -> Result<Option<bool>, String> {
+    let Some(session) = session.filter(|session| session.is_valid_at(unix_now())) else {
+        return Ok(Some(false));
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(VALIDATION_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .head(VALIDATION_URL)
+        .header("User-Agent", "X-Mac-Client")
+        .header("Cookie", cookie_header(session))
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok());
+
+    Ok(classify_response(response.status(), location))
+}
+
+/// Comprueba si la sesión guardada de `username` sigue siendo válida y guarda
+/// el resultado en su `AccountInfo`
+///
+/// Retorna `None` si X no ha dado una respuesta concluyente; en ese caso se
+/// conserva el resultado anterior.
+pub async fn validate_session<R: Runtime>(app: &AppHandle<R>, username: &str) -> Result<Option<bool>, String> {
+    let manager = app.state::<AccountsManager>();
+
+    let session = load_credentials(manager.store(), username)?
+        .and_then(|creds| creds.session_data)
+        .and_then(|data| StoredSession::parse(&data));
+
+    let valid = check_session(session.as_ref()).await?;
+
+    match valid {
+        Some(valid) => {
+            manager.record_session_validity(username, valid)?;
+        }
+        None => tracing::warn!("Inconclusive session check for {}", username),
+    }
+
+    Ok(valid)
+}
+
+/// Comprueba en segundo plano las sesiones de todas las cuentas, cada una
+/// como mucho una vez por `VALIDATION_INTERVAL_SECS`
+pub fn spawn_validation<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let accounts = app.state::<AccountsManager>().list_accounts(false).unwrap_or_default();
+            let now = unix_now();
+
+            for account in accounts.iter().filter(|account| needs_validation(account, now)) {
+                if let Err(e) = validate_session(&app, &account.username).await {
+                    tracing::warn!("Failed to validate session for {}: {}", account.username, e);
+                }
+            }
+
+            tokio::time::sleep(VALIDATION_TICK).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_response() {
+        assert_eq!(classify_response(StatusCode::OK, None), Some(true));
+        assert_eq!(classify_response(StatusCode::UNAUTHORIZED, None), Some(false));
+        assert_eq!(classify_response(StatusCode::FORBIDDEN, None), Some(false));
+        assert_eq!(
+            classify_response(StatusCode::FOUND, Some("https://x.com/i/flow/login?redirect_after_login=%2Fsettings")),
+            Some(false)
+        );
+        assert_eq!(classify_response(StatusCode::FOUND, Some("/settings/account/")), Some(true));
+        assert_eq!(classify_response(StatusCode::TOO_MANY_REQUESTS, None), None);
+        assert_eq!(classify_response(StatusCode::SERVICE_UNAVAILABLE, None), None);
+    }
+
+    #[test]
+    fn test_needs_validation_at_most_once_per_hour() {
+        let store: &'static crate::secrets::InMemoryStore = Box::leak(Box::new(crate::secrets::InMemoryStore::new()));
+        let manager = AccountsManager::new(store);
+        manager.add_account("alice", None, None).unwrap();
+        let mut account = manager.list_accounts(true).unwrap().remove(0);
+        let now = unix_now();
+
+        // Nunca comprobada
+        assert!(needs_validation(&account, now));
+
+        account.last_validated = now - VALIDATION_INTERVAL_SECS + 60;
+        assert!(!needs_validation(&account, now));
+
+        account.last_validated = now - VALIDATION_INTERVAL_SECS;
+        assert!(needs_validation(&account, now));
+
+        account.archived = true;
+        assert!(!needs_validation(&account, now));
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_expired_session_is_invalid_without_request() {
+        assert_eq!(check_session(None).await.unwrap(), Some(false));
+        assert_eq!(check_session(Some(&StoredSession::default())).await.unwrap(), Some(false));
+    }
+}
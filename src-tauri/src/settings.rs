@@ -0,0 +1,596 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Preferencias de la aplicación que no son secretas
+//
+// Se guardan como JSON en `settings.json`, dentro del directorio de
+// configuración de la aplicación, en lugar de en el Keychain: no necesitan
+// cifrado y así se pueden leer y editar sin pasar por el almacén de
+// secretos. Las credenciales y la lista de cuentas siguen en el Keychain.
+//
+// Los campos que falten en el archivo toman su valor por defecto; si el
+// archivo no es JSON válido se ignora y se usan los valores por defecto.
+//
+// El user-agent se aplica al crear cada ventana de X: cambiarlo no afecta a
+// las ventanas abiertas hasta que se vuelven a crear.
+//
+// Las versiones anteriores guardaban el zoom, el idioma, la apariencia, el
+// atajo global y el estado de las actualizaciones en el Keychain;
+// `migrate_legacy` los pasa al archivo la primera vez.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::accounts::DEFAULT_MAX_ACCOUNTS;
+use crate::appearance::Appearance;
+use crate::error::AppError;
+use crate::i18n::Language;
+use crate::secrets::SecretStore;
+use crate::updates::{AutoUpdatePolicy, UpdateChannel, DEFAULT_CHECK_INTERVAL_HOURS, MAX_CHECK_INTERVAL_HOURS};
+use crate::zoom::{normalize_zoom, DEFAULT_ZOOM};
+
+/// Archivo (en el directorio de configuración) con las preferencias
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Entradas (sin hashear) del almacén de secretos donde versiones anteriores
+/// guardaban preferencias que ahora van en `SETTINGS_FILE`
+const LEGACY_ZOOM_KEY: &str = "zoom_level";
+const LEGACY_LANGUAGE_KEY: &str = "language";
+const LEGACY_APPEARANCE_KEY: &str = "appearance";
+const LEGACY_TOGGLE_SHORTCUT_KEY: &str = "toggle_shortcut";
+const LEGACY_UPDATE_POLICY_KEY: &str = "update_policy";
+const LEGACY_UPDATE_CHANNEL_KEY: &str = "update_channel";
+const LEGACY_SKIPPED_VERSION_KEY: &str = "skipped_version";
+const LEGACY_SNOOZED_UNTIL_KEY: &str = "snoozed_until";
+const LEGACY_LAST_CHECK_KEY: &str = "last_update_check";
+/// Campo de `SETTINGS_FILE` que desactivaba la comprobación al arrancar;
+/// ahora es la política `off`
+const LEGACY_AUTO_UPDATE_CHECK_FIELD: &str = "auto_update_check";
+const LEGACY_KEYS: &[&str] = &[
+    LEGACY_ZOOM_KEY,
+    LEGACY_LANGUAGE_KEY,
+    LEGACY_APPEARANCE_KEY,
+    LEGACY_TOGGLE_SHORTCUT_KEY,
+    LEGACY_UPDATE_POLICY_KEY,
+    LEGACY_UPDATE_CHANNEL_KEY,
+    LEGACY_SKIPPED_VERSION_KEY,
+    LEGACY_SNOOZED_UNTIL_KEY,
+    LEGACY_LAST_CHECK_KEY,
+];
+
+/// Preferencias de la aplicación
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Ordenar el menú de cuentas por uso más reciente en lugar del orden manual
+    pub sort_accounts_by_recent: bool,
+    /// Número máximo de cuentas que se pueden añadir
+    pub max_accounts: usize,
+    /// User-agent de las ventanas de X (`None`: el del WebView)
+    pub user_agent: Option<String>,
+    /// Horas mínimas entre dos comprobaciones automáticas
+    pub update_check_interval_hours: u32,
+    /// Último factor de zoom aplicado, para restaurarlo al arrancar
+    pub zoom: f64,
+    /// Idioma de los menús (`None`: el del sistema)
+    pub language: Option<Language>,
+    /// Apariencia de las ventanas
+    pub appearance: Appearance,
+    /// Atajo global que muestra u oculta la ventana principal (`None`: el de
+    /// por defecto)
+    pub toggle_shortcut: Option<String>,
+    /// Qué hacer con la comprobación de actualizaciones al arrancar (`Off`
+    /// la desactiva; el menú Ayuda siempre funciona)
+    pub update_policy: AutoUpdatePolicy,
+    /// Releases que cuentan como actualización
+    pub update_channel: UpdateChannel,
+    /// Versión de la que el usuario no quiere que se le avise
+    pub skipped_version: Option<String>,
+    /// Fin del aplazamiento de los avisos de actualización (segundos Unix)
+    pub snoozed_until: Option<i64>,
+    /// Última comprobación de actualizaciones correcta (segundos Unix)
+    pub last_update_check: Option<i64>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            sort_accounts_by_recent: false,
+            max_accounts: DEFAULT_MAX_ACCOUNTS,
+            user_agent: None,
+            update_check_interval_hours: DEFAULT_CHECK_INTERVAL_HOURS,
+            zoom: DEFAULT_ZOOM,
+            language: None,
+            appearance: Appearance::default(),
+            toggle_shortcut: None,
+            update_policy: AutoUpdatePolicy::default(),
+            update_channel: UpdateChannel::default(),
+            skipped_version: None,
+            snoozed_until: None,
+            last_update_check: None,
+        }
+    }
+}
+
+/// Cambio parcial de las preferencias: solo se aplican los campos presentes
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsUpdate {
+    pub sort_accounts_by_recent: Option<bool>,
+    pub max_accounts: Option<usize>,
+    /// `Some(None)` (`null` en JSON) vuelve al user-agent del WebView
+    #[serde(deserialize_with = "present")]
+    pub user_agent: Option<Option<String>>,
+    pub update_check_interval_hours: Option<u32>,
+    // El resto no se acepta en `update_settings`: cada uno tiene su propio
+    // comando, que además lo aplica
+    #[serde(skip)]
+    pub zoom: Option<f64>,
+    #[serde(skip)]
+    pub language: Option<Language>,
+    #[serde(skip)]
+    pub appearance: Option<Appearance>,
+    #[serde(skip)]
+    pub toggle_shortcut: Option<String>,
+    #[serde(skip)]
+    pub update_policy: Option<AutoUpdatePolicy>,
+    #[serde(skip)]
+    pub update_channel: Option<UpdateChannel>,
+    /// `Some(None)` deja de omitir la versión
+    #[serde(skip)]
+    pub skipped_version: Option<Option<String>>,
+    #[serde(skip)]
+    pub snoozed_until: Option<i64>,
+    #[serde(skip)]
+    pub last_update_check: Option<i64>,
+}
+
+/// Distingue un campo `null` (`Some(None)`) de uno ausente (`None`)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// Valor de `SettingsUpdate` que no se puede guardar
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsError {
+    /// El user-agent está vacío o no es ASCII imprimible
+    InvalidUserAgent(String),
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::InvalidUserAgent(user_agent) => write!(
+                f,
+                "Invalid field 'user_agent': '{}' must be non-empty printable ASCII",
+                user_agent
+            ),
+        }
+    }
+}
+
+impl From<SettingsError> for AppError {
+    fn from(error: SettingsError) -> Self {
+        AppError::InvalidInput(error.to_string())
+    }
+}
+
+/// Comprueba que `user_agent` no esté vacío y sea ASCII imprimible (va en
+/// una cabecera HTTP)
+pub fn validate_user_agent(user_agent: &str) -> Result<(), SettingsError> {
+    let printable = user_agent.chars().all(|c| c.is_ascii_graphic() || c == ' ');
+
+    if user_agent.trim().is_empty() || !printable {
+        return Err(SettingsError::InvalidUserAgent(user_agent.to_string()));
+    }
+
+    Ok(())
+}
+
+impl SettingsUpdate {
+    /// Rechaza los valores que no se pueden guardar
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(Some(user_agent)) = &self.user_agent {
+            validate_user_agent(user_agent)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AppSettings {
+    /// Aplica los campos presentes en `update`
+    ///
+    /// El límite de cuentas es al menos 1; el intervalo de comprobación de
+    /// actualizaciones, de 1 hora a `MAX_CHECK_INTERVAL_HOURS`, y el zoom se
+    /// ajusta con `normalize_zoom`. Devuelve si algo ha cambiado.
+    pub fn apply(&mut self, update: &SettingsUpdate) -> bool {
+        let previous = self.clone();
+
+        if let Some(recent) = update.sort_accounts_by_recent {
+            self.sort_accounts_by_recent = recent;
+        }
+        if let Some(limit) = update.max_accounts {
+            self.max_accounts = limit.max(1);
+        }
+        if let Some(user_agent) = &update.user_agent {
+            self.user_agent = user_agent.as_ref().map(|user_agent| user_agent.trim().to_string());
+        }
+        if let Some(hours) = update.update_check_interval_hours {
+            self.update_check_interval_hours = hours.clamp(1, MAX_CHECK_INTERVAL_HOURS);
+        }
+        if let Some(zoom) = update.zoom {
+            self.zoom = normalize_zoom(zoom);
+        }
+        if let Some(language) = update.language {
+            self.language = Some(language);
+        }
+        if let Some(appearance) = update.appearance {
+            self.appearance = appearance;
+        }
+        if let Some(accel) = &update.toggle_shortcut {
+            self.toggle_shortcut = Some(accel.trim().to_string());
+        }
+        if let Some(policy) = update.update_policy {
+            self.update_policy = policy;
+        }
+        if let Some(channel) = update.update_channel {
+            self.update_channel = channel;
+        }
+        if let Some(version) = &update.skipped_version {
+            self.skipped_version = version.clone();
+        }
+        if let Some(until) = update.snoozed_until {
+            self.snoozed_until = Some(until);
+        }
+        if let Some(now) = update.last_update_check {
+            self.last_update_check = Some(now);
+        }
+
+        *self != previous
+    }
+}
+
+/// Ruta de `SETTINGS_FILE` en `config_dir`
+pub fn settings_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(SETTINGS_FILE)
+}
+
+/// Lee las preferencias de `path`
+///
+/// Sin archivo, o si no es válido, se usan los valores por defecto. Un
+/// `auto_update_check: false` de versiones anteriores se lee como la política
+/// `off`.
+pub fn load_settings(path: &Path) -> AppSettings {
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return AppSettings::default(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", path.display(), e);
+            return AppSettings::default();
+        }
+    };
+
+    parse_settings(&data).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid settings file {}: {}", path.display(), e);
+        AppSettings::default()
+    })
+}
+
+fn parse_settings(data: &str) -> Result<AppSettings, serde_json::Error> {
+    let value: serde_json::Value = serde_json::from_str(data)?;
+    let launch_check_off = value.get(LEGACY_AUTO_UPDATE_CHECK_FIELD) == Some(&serde_json::Value::Bool(false));
+
+    let mut settings: AppSettings = serde_json::from_value(value)?;
+    if launch_check_off {
+        settings.update_policy = AutoUpdatePolicy::Off;
+    }
+
+    Ok(settings)
+}
+
+/// Escribe las preferencias en `path`
+///
+/// Se escribe primero un archivo temporal y luego se renombra, para no dejar
+/// un JSON a medias si la app se cierra durante la escritura.
+pub fn save_settings(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let data = serde_json::to_vec_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Preferencias cargadas y, si lo hay, el archivo donde se guardan
+#[derive(Default)]
+pub struct Settings {
+    path: Option<PathBuf>,
+    current: RwLock<AppSettings>,
+}
+
+impl Settings {
+    /// Carga las preferencias de `path`; los cambios se guardan en el mismo archivo
+    pub fn open(path: PathBuf) -> Self {
+        let current = load_settings(&path);
+        Self {
+            path: Some(path),
+            current: RwLock::new(current),
+        }
+    }
+
+    /// Preferencias por defecto que no se guardan en disco (si no se puede
+    /// resolver el directorio de configuración, y en los tests)
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Copia de las preferencias actuales
+    pub fn get(&self) -> AppSettings {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Aplica `update` y lo guarda
+    ///
+    /// Si algún valor no es válido o no se puede escribir el archivo, las
+    /// preferencias no cambian.
+    ///
+    /// # Returns
+    /// Preferencias resultantes
+    pub fn update(&self, update: &SettingsUpdate) -> Result<AppSettings, AppError> {
+        update.validate()?;
+
+        let mut current = self.current.write().unwrap();
+
+        let mut updated = current.clone();
+        if !updated.apply(update) {
+            return Ok(updated);
+        }
+
+        if let Some(path) = &self.path {
+            save_settings(path, &updated)?;
+        }
+        *current = updated.clone();
+
+        tracing::info!("Settings updated: {:?}", updated);
+
+        Ok(updated)
+    }
+}
+
+/// Texto guardado en la entrada `key` del almacén
+fn legacy_text(store: &dyn SecretStore, key: &str) -> Option<String> {
+    store.get(key).and_then(|data| String::from_utf8(data).ok())
+}
+
+/// Valor JSON guardado en la entrada `key` del almacén
+fn legacy_json<T: serde::de::DeserializeOwned>(store: &dyn SecretStore, key: &str) -> Option<T> {
+    store.get(key).and_then(|data| serde_json::from_slice(&data).ok())
+}
+
+/// Pasa a `settings` las preferencias que versiones anteriores guardaban en
+/// el Keychain y las borra de allí
+///
+/// Los valores que no se puedan leer se descartan. Las entradas solo se
+/// borran si las preferencias se han escrito; sin archivo (`in_memory`) ni
+/// se intenta, y se reintenta en el próximo arranque.
+pub fn migrate_legacy(store: &dyn SecretStore, settings: &Settings) {
+    let found: Vec<&str> = LEGACY_KEYS.iter().copied().filter(|key| store.get(key).is_some()).collect();
+    if found.is_empty() || settings.path.is_none() {
+        return;
+    }
+
+    let update = SettingsUpdate {
+        zoom: legacy_text(store, LEGACY_ZOOM_KEY).and_then(|value| value.parse().ok()),
+        language: legacy_text(store, LEGACY_LANGUAGE_KEY).and_then(|code| Language::parse(&code)),
+        appearance: legacy_text(store, LEGACY_APPEARANCE_KEY).and_then(|value| Appearance::parse(&value)),
+        toggle_shortcut: legacy_text(store, LEGACY_TOGGLE_SHORTCUT_KEY),
+        update_policy: legacy_json(store, LEGACY_UPDATE_POLICY_KEY),
+        update_channel: legacy_json(store, LEGACY_UPDATE_CHANNEL_KEY),
+        skipped_version: legacy_text(store, LEGACY_SKIPPED_VERSION_KEY).map(Some),
+        snoozed_until: legacy_json(store, LEGACY_SNOOZED_UNTIL_KEY),
+        last_update_check: legacy_json(store, LEGACY_LAST_CHECK_KEY),
+        ..Default::default()
+    };
+
+    match settings.update(&update) {
+        Ok(_) => {
+            for key in &found {
+                store.delete(key);
+            }
+            tracing::info!("Migrated {} preferences from the Keychain to the settings file", found.len());
+        }
+        Err(e) => tracing::warn!("Failed to migrate preferences from the Keychain: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("xmac-settings-test-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_load_with_defaults() {
+        let dir = test_dir("defaults");
+        let path = settings_path(&dir);
+
+        // Sin archivo (ni directorio)
+        assert_eq!(load_settings(&path), AppSettings::default());
+        assert_eq!(Settings::open(path.clone()).get().max_accounts, DEFAULT_MAX_ACCOUNTS);
+
+        // Campos que faltan
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, r#"{ "sort_accounts_by_recent": true }"#).unwrap();
+        assert_eq!(
+            load_settings(&path),
+            AppSettings {
+                sort_accounts_by_recent: true,
+                ..Default::default()
+            }
+        );
+
+        // Comprobación al arrancar desactivada con el campo antiguo
+        std::fs::write(&path, r#"{ "auto_update_check": false, "update_policy": "notify_only" }"#).unwrap();
+        assert_eq!(load_settings(&path).update_policy, AutoUpdatePolicy::Off);
+        std::fs::write(&path, r#"{ "auto_update_check": true, "update_policy": "notify_only" }"#).unwrap();
+        assert_eq!(load_settings(&path).update_policy, AutoUpdatePolicy::NotifyOnly);
+
+        // JSON inválido
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load_settings(&path), AppSettings::default());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_partial_update() {
+        let dir = test_dir("update");
+        let path = settings_path(&dir);
+        let settings = Settings::open(path.clone());
+
+        let updated = settings
+            .update(&SettingsUpdate {
+                max_accounts: Some(3),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(updated.max_accounts, 3);
+        assert!(!updated.sort_accounts_by_recent);
+
+        let updated = settings.update(&serde_json::from_str(r#"{ "sort_accounts_by_recent": true }"#).unwrap()).unwrap();
+        assert_eq!(
+            updated,
+            AppSettings {
+                sort_accounts_by_recent: true,
+                max_accounts: 3,
+                ..Default::default()
+            }
+        );
+
+        // Se guarda en disco y se recupera al volver a abrir
+        assert_eq!(Settings::open(path).get(), updated);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_validation() {
+        let mut settings = AppSettings::default();
+        assert!(!settings.apply(&SettingsUpdate::default()));
+        assert!(settings.apply(&SettingsUpdate {
+            max_accounts: Some(0),
+            ..Default::default()
+        }));
+        assert_eq!(settings.max_accounts, 1);
+
+        assert!(settings.apply(&SettingsUpdate {
+            update_check_interval_hours: Some(0),
+            ..Default::default()
+        }));
+        assert_eq!(settings.update_check_interval_hours, 1);
+        settings.apply(&SettingsUpdate {
+            update_check_interval_hours: Some(u32::MAX),
+            ..Default::default()
+        });
+        assert_eq!(settings.update_check_interval_hours, MAX_CHECK_INTERVAL_HOURS);
+
+        // Los campos con comando propio no se aceptan
+        assert!(serde_json::from_str::<SettingsUpdate>(r#"{ "zoom": 2 }"#).is_err());
+        assert!(serde_json::from_str::<SettingsUpdate>(r#"{ "skipped_version": "1.0.0" }"#).is_err());
+    }
+
+    #[test]
+    fn test_migrate_legacy_preferences() {
+        let dir = test_dir("legacy");
+        let path = settings_path(&dir);
+        let store = InMemoryStore::new();
+        store.set(LEGACY_ZOOM_KEY, b"1.3").unwrap();
+        store.set(LEGACY_LANGUAGE_KEY, b"es").unwrap();
+        store.set(LEGACY_APPEARANCE_KEY, b"dark").unwrap();
+        store.set(LEGACY_TOGGLE_SHORTCUT_KEY, b"Cmd+Alt+J").unwrap();
+        store.set(LEGACY_UPDATE_POLICY_KEY, br#""notify_only""#).unwrap();
+        store.set(LEGACY_UPDATE_CHANNEL_KEY, br#""beta""#).unwrap();
+        store.set(LEGACY_SKIPPED_VERSION_KEY, b"0.4.0").unwrap();
+        store.set(LEGACY_SNOOZED_UNTIL_KEY, b"1700000000").unwrap();
+        // Un valor ilegible se descarta sin impedir el resto
+        store.set(LEGACY_LAST_CHECK_KEY, b"yesterday").unwrap();
+
+        let settings = Settings::open(path.clone());
+        migrate_legacy(&store, &settings);
+
+        let expected = AppSettings {
+            zoom: 1.3,
+            language: Some(Language::Es),
+            appearance: Appearance::Dark,
+            toggle_shortcut: Some("Cmd+Alt+J".to_string()),
+            update_policy: AutoUpdatePolicy::NotifyOnly,
+            update_channel: UpdateChannel::Beta,
+            skipped_version: Some("0.4.0".to_string()),
+            snoozed_until: Some(1_700_000_000),
+            ..Default::default()
+        };
+        assert_eq!(settings.get(), expected);
+        assert_eq!(load_settings(&path), expected);
+        // Las entradas del Keychain se borran
+        assert!(store.snapshot().is_empty());
+
+        // Sin nada que migrar no se toca el archivo
+        migrate_legacy(&store, &settings);
+        assert_eq!(load_settings(&path), expected);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_legacy_needs_a_settings_file() {
+        let store = InMemoryStore::new();
+        store.set(LEGACY_ZOOM_KEY, b"1.3").unwrap();
+
+        // Sin archivo se perderían: se quedan en el Keychain
+        let settings = Settings::in_memory();
+        migrate_legacy(&store, &settings);
+        assert_eq!(settings.get().zoom, DEFAULT_ZOOM);
+        assert!(store.get(LEGACY_ZOOM_KEY).is_some());
+    }
+
+    #[test]
+    fn test_user_agent_validation() {
+        assert!(validate_user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)").is_ok());
+        for invalid in ["", "   ", "Mozilla/5.0 ñ", "Mozilla\n/5.0", "Mozilla\t5.0"] {
+            assert_eq!(validate_user_agent(invalid), Err(SettingsError::InvalidUserAgent(invalid.to_string())));
+        }
+
+        // Un valor inválido no cambia nada
+        let settings = Settings::in_memory();
+        let update = SettingsUpdate {
+            user_agent: Some(Some(String::new())),
+            ..Default::default()
+        };
+        assert!(matches!(settings.update(&update), Err(AppError::InvalidInput(_))));
+        assert_eq!(settings.get().user_agent, None);
+
+        // `null` vuelve al user-agent por defecto; sin el campo, no se toca
+        let set: SettingsUpdate = serde_json::from_str(r#"{ "user_agent": " Custom/1.0 " }"#).unwrap();
+        assert_eq!(settings.update(&set).unwrap().user_agent.as_deref(), Some("Custom/1.0"));
+        let other: SettingsUpdate = serde_json::from_str(r#"{ "max_accounts": 4 }"#).unwrap();
+        assert_eq!(settings.update(&other).unwrap().user_agent.as_deref(), Some("Custom/1.0"));
+        let clear: SettingsUpdate = serde_json::from_str(r#"{ "user_agent": null }"#).unwrap();
+        assert_eq!(clear.user_agent, Some(None));
+        assert_eq!(settings.update(&clear).unwrap().user_agent, None);
+    }
+}
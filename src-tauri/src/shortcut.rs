@@ -0,0 +1,170 @@
+//! Atajo de teclado global para mostrar u ocultar la ventana principal
+//!
+//! Funciona aunque la app no esté en primer plano. Por defecto es
+//! `Cmd+Shift+X`; el usuario puede cambiarlo con el comando
+//! `set_toggle_shortcut` y se guarda en las preferencias para los próximos
+//! arranques.
+
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+
+use crate::accounts::AccountsManager;
+use crate::error::AppError;
+use crate::settings::SettingsUpdate;
+
+/// Atajo por defecto (`Cmd` en macOS, `Ctrl` en el resto)
+pub const DEFAULT_TOGGLE_SHORTCUT: &str = "CmdOrCtrl+Shift+X";
+
+/// Atajo registrado actualmente (`None` si no se pudo registrar)
+#[derive(Default)]
+pub struct ToggleShortcut(Mutex<Option<Shortcut>>);
+
+/// Valida un atajo del estilo `Cmd+Shift+X` y lo convierte en `Shortcut`
+///
+/// Los modificadores van antes de la tecla, separados por `+`. Se exige al
+/// menos `Cmd`, `Ctrl` o `Alt`: un atajo global sin ellos (o solo con
+/// `Shift`) se comería la tecla en todas las aplicaciones.
+pub fn validate_shortcut(accel: &str) -> Result<Shortcut, String> {
+    let accel = accel.trim();
+    if accel.is_empty() {
+        return Err("Shortcut is empty".to_string());
+    }
+
+    let shortcut = Shortcut::from_str(accel).map_err(|e| format!("Invalid shortcut '{}': {}", accel, e))?;
+
+    if !shortcut.mods.intersects(Modifiers::SUPER | Modifiers::CONTROL | Modifiers::ALT) {
+        return Err(format!("Shortcut '{}' needs Cmd, Ctrl or Alt", accel));
+    }
+
+    Ok(shortcut)
+}
+
+/// Lee el atajo guardado (sin validar)
+pub fn load_shortcut(manager: &AccountsManager) -> Option<String> {
+    manager.settings().toggle_shortcut
+}
+
+/// Guarda el atajo para los próximos arranques
+pub fn save_shortcut(manager: &AccountsManager, accel: &str) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        toggle_shortcut: Some(accel.to_string()),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// Atajo a registrar al arrancar: el guardado si es válido, si no el de por
+/// defecto
+pub fn startup_shortcut(manager: &AccountsManager) -> Shortcut {
+    load_shortcut(manager)
+        .and_then(|accel| match validate_shortcut(&accel) {
+            Ok(shortcut) => Some(shortcut),
+            Err(e) => {
+                tracing::warn!("Ignoring saved toggle shortcut: {}", e);
+                None
+            }
+        })
+        .unwrap_or_else(|| validate_shortcut(DEFAULT_TOGGLE_SHORTCUT).expect("default shortcut is valid"))
+}
+
+/// Registra `shortcut` para mostrar u ocultar la ventana principal
+fn register<R: Runtime>(app: &AppHandle<R>, shortcut: Shortcut) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                if let Err(e) = crate::window::toggle_main_window(app) {
+                    tracing::error!("Failed to toggle main window: {}", e);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))
+}
+
+/// Registra el atajo guardado (o el de por defecto) al arrancar
+///
+/// Si otra aplicación ya lo usa solo se avisa: la app funciona igual sin él.
+pub fn init<R: Runtime>(app: &AppHandle<R>, manager: &AccountsManager) {
+    let shortcut = startup_shortcut(manager);
+    let registered = match register(app, shortcut) {
+        Ok(()) => Some(shortcut),
+        Err(e) => {
+            tracing::warn!("{}", e);
+            None
+        }
+    };
+
+    app.manage(ToggleShortcut(Mutex::new(registered)));
+}
+
+/// Cambia el atajo global y lo guarda
+///
+/// El nuevo se registra antes de soltar el anterior, así que si falla (por
+/// ejemplo, porque otra aplicación lo usa) el anterior sigue activo.
+pub fn set_toggle_shortcut<R: Runtime>(
+    app: &AppHandle<R>,
+    manager: &AccountsManager,
+    accel: &str,
+) -> Result<(), AppError> {
+    let shortcut = validate_shortcut(accel)?;
+    let state = app.state::<ToggleShortcut>();
+    let mut current = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    if *current != Some(shortcut) {
+        register(app, shortcut)?;
+        if let Some(previous) = current.take() {
+            if let Err(e) = app.global_shortcut().unregister(previous) {
+                tracing::warn!("Failed to unregister shortcut {}: {}", previous, e);
+            }
+        }
+        *current = Some(shortcut);
+    }
+
+    save_shortcut(manager, accel.trim())?;
+
+    tracing::info!("Toggle shortcut set to {}", shortcut);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+
+    #[test]
+    fn test_valid_shortcuts() {
+        assert!(validate_shortcut(DEFAULT_TOGGLE_SHORTCUT).is_ok());
+        assert!(validate_shortcut("Cmd+Shift+X").is_ok());
+        assert!(validate_shortcut("CmdOrCtrl+Alt+Space").is_ok());
+        assert!(validate_shortcut("ctrl+shift+k").is_ok());
+        assert!(validate_shortcut("  Alt+F1 ").is_ok());
+        assert_eq!(
+            validate_shortcut("Cmd+Shift+X").unwrap(),
+            validate_shortcut("Shift+Command+X").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_shortcuts() {
+        for accel in ["", "   ", "X", "Shift+X", "Cmd+Shift", "Cmd++X", "Cmd+Foo", "Cmd+X+Y", "Cmd+X+Shift"] {
+            assert!(validate_shortcut(accel).is_err(), "{:?} should be rejected", accel);
+        }
+    }
+
+    #[test]
+    fn test_startup_shortcut_falls_back_to_default() {
+        let manager = AccountsManager::new(Box::leak(Box::new(InMemoryStore::new())));
+        let default = validate_shortcut(DEFAULT_TOGGLE_SHORTCUT).unwrap();
+        assert_eq!(startup_shortcut(&manager), default);
+
+        save_shortcut(&manager, "Cmd+Alt+J").unwrap();
+        assert_eq!(startup_shortcut(&manager), validate_shortcut("Cmd+Alt+J").unwrap());
+
+        // Un valor guardado inválido no impide registrar el atajo
+        save_shortcut(&manager, "Shift+J").unwrap();
+        assert_eq!(startup_shortcut(&manager), default);
+    }
+}
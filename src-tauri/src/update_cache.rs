@@ -0,0 +1,231 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Caché en disco de las respuestas de la API de releases
+//
+// La API de GitHub sin autenticar admite 60 peticiones por hora y dirección
+// IP. Se guarda la última respuesta correcta de cada URL con su ETag: la
+// siguiente petición lleva `If-None-Match` y un 304 (que no cuenta para el
+// límite) se resuelve con la respuesta guardada. Si aun así se agota el
+// límite, se apunta hasta cuándo y no se vuelve a preguntar a GitHub antes.
+//
+// El archivo está en el directorio de caché de la app; si se pierde o no es
+// válido, simplemente se vuelve a pedir todo.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::AppError;
+
+/// Archivo (en el directorio de caché) con las respuestas guardadas
+pub const UPDATE_CACHE_FILE: &str = "update_cache.json";
+
+/// Última respuesta correcta de una URL
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub body: serde_json::Value,
+}
+
+/// Resultado de una petición a la API, ya sin errores
+#[derive(Debug, PartialEq)]
+pub enum ApiResponse {
+    /// 200 con el cuerpo y su ETag
+    Fresh {
+        body: serde_json::Value,
+        etag: Option<String>,
+    },
+    /// 304: la respuesta guardada sigue siendo válida
+    NotModified,
+    /// 403 con el límite agotado; `reset` es cuándo se renueva (segundos Unix)
+    RateLimited { reset: i64 },
+}
+
+/// Contenido de `UPDATE_CACHE_FILE`
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct UpdateCache {
+    /// Última respuesta correcta de cada URL
+    pub responses: HashMap<String, CachedResponse>,
+    /// Hasta cuándo (segundos Unix) no se puede volver a preguntar a GitHub
+    pub rate_limited_until: Option<i64>,
+}
+
+/// Ruta de la caché de `app`, si se puede resolver su directorio de caché
+pub fn cache_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    match app.path().app_cache_dir() {
+        Ok(dir) => Some(dir.join(UPDATE_CACHE_FILE)),
+        Err(e) => {
+            tracing::warn!("Cannot resolve the cache directory, update responses will not be cached: {}", e);
+            None
+        }
+    }
+}
+
+impl UpdateCache {
+    /// Lee la caché de `path`; sin archivo, o si no es válido, está vacía
+    pub fn load(path: &Path) -> Self {
+        let data = match std::fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&data).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid update cache {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Escribe la caché en `path` (archivo temporal y renombrado, como `settings`)
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        let data = serde_json::to_vec(self).map_err(|e| format!("Failed to serialize update cache: {}", e))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, data).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// ETag que enviar en `If-None-Match` al pedir `url`
+    pub fn etag(&self, url: &str) -> Option<&str> {
+        self.responses.get(url)?.etag.as_deref()
+    }
+
+    /// Fin del límite de peticiones, si todavía no ha pasado a las `now`
+    pub fn rate_limit(&self, now: i64) -> Option<i64> {
+        self.rate_limited_until.filter(|until| *until > now)
+    }
+
+    /// Convierte la respuesta de la API en el cuerpo que se usa y actualiza
+    /// la caché
+    ///
+    /// Con el límite agotado se usa la respuesta guardada, si la hay; sin
+    /// ella, el error es `AppError::RateLimited` con cuándo volver a probar.
+    pub fn resolve(&mut self, url: &str, response: ApiResponse) -> Result<serde_json::Value, AppError> {
+        match response {
+            ApiResponse::Fresh { body, etag } => {
+                self.rate_limited_until = None;
+                self.responses.insert(
+                    url.to_string(),
+                    CachedResponse {
+                        etag,
+                        body: body.clone(),
+                    },
+                );
+                Ok(body)
+            }
+            ApiResponse::NotModified => {
+                self.rate_limited_until = None;
+                self.responses
+                    .get(url)
+                    .map(|cached| cached.body.clone())
+                    .ok_or_else(|| AppError::Network("GitHub API returned 304 without a cached response".to_string()))
+            }
+            ApiResponse::RateLimited { reset } => {
+                self.rate_limited_until = Some(reset);
+                self.responses
+                    .get(url)
+                    .map(|cached| cached.body.clone())
+                    .ok_or(AppError::RateLimited(reset))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const URL: &str = "https://api.github.com/repos/owner/repo/releases/latest";
+
+    #[test]
+    fn test_not_modified_uses_cached_body() {
+        let mut cache = UpdateCache::default();
+        assert!(cache.resolve(URL, ApiResponse::NotModified).is_err());
+
+        let release = json!({ "tag_name": "v1.2.0" });
+        let body = cache
+            .resolve(
+                URL,
+                ApiResponse::Fresh {
+                    body: release.clone(),
+                    etag: Some("\"abc\"".to_string()),
+                },
+            )
+            .unwrap();
+        assert_eq!(body, release);
+        assert_eq!(cache.etag(URL), Some("\"abc\""));
+        assert_eq!(cache.etag("https://api.github.com/repos/owner/repo/releases"), None);
+
+        assert_eq!(cache.resolve(URL, ApiResponse::NotModified).unwrap(), release);
+    }
+
+    #[test]
+    fn test_rate_limit_falls_back_to_cache() {
+        let mut cache = UpdateCache::default();
+
+        // Sin respuesta guardada: error con cuándo volver a probar
+        assert_eq!(
+            cache.resolve(URL, ApiResponse::RateLimited { reset: 2_000 }),
+            Err(AppError::RateLimited(2_000))
+        );
+        assert_eq!(cache.rate_limit(1_000), Some(2_000));
+        assert_eq!(cache.rate_limit(2_000), None);
+
+        // Con respuesta guardada se usa esa
+        let release = json!({ "tag_name": "v1.2.0" });
+        cache.responses.insert(
+            URL.to_string(),
+            CachedResponse {
+                etag: None,
+                body: release.clone(),
+            },
+        );
+        assert_eq!(cache.resolve(URL, ApiResponse::RateLimited { reset: 3_000 }).unwrap(), release);
+        assert_eq!(cache.rate_limit(1_000), Some(3_000));
+
+        // Una respuesta correcta quita el límite
+        cache.resolve(URL, ApiResponse::NotModified).unwrap();
+        assert_eq!(cache.rate_limit(1_000), None);
+    }
+
+    #[test]
+    fn test_cache_persistence() {
+        let dir = std::env::temp_dir().join(format!("xmac-update-cache-test-{}", std::process::id()));
+        let path = dir.join(UPDATE_CACHE_FILE);
+
+        assert_eq!(UpdateCache::load(&path), UpdateCache::default());
+
+        let mut cache = UpdateCache::default();
+        cache
+            .resolve(
+                URL,
+                ApiResponse::Fresh {
+                    body: json!({ "tag_name": "v1.2.0" }),
+                    etag: Some("W/\"etag\"".to_string()),
+                },
+            )
+            .unwrap();
+        cache.rate_limited_until = Some(42);
+        cache.save(&path).unwrap();
+        assert_eq!(UpdateCache::load(&path), cache);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(UpdateCache::load(&path), UpdateCache::default());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
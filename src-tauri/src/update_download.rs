@@ -0,0 +1,466 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Descarga del instalador de una actualización
+//
+// Descarga a `~/Downloads` el `.dmg` para Apple Silicon de la última release,
+// emitiendo el progreso a la ventana principal. Antes de abrirlo comprueba el
+// tamaño que anuncia GitHub y, si la release publica un `.sha256`, la suma
+// SHA-256. Mientras se descarga se escribe en `<nombre>.part`, que se borra si
+// la descarga falla o se cancela.
+//
+// Solo se descarga el instalador de la última release del canal configurado:
+// la URL que pide el frontend tiene que ser la de ese asset, así que el
+// comando no sirve para bajar y abrir ficheros arbitrarios.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::Notify;
+
+use crate::accounts::AccountsManager;
+use crate::error::AppError;
+use crate::updates::{self, UpdateAsset};
+
+/// Evento con el progreso de la descarga (payload: `DownloadProgress`)
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "update-download-progress";
+
+/// Extensión del fichero mientras se descarga
+const PARTIAL_EXTENSION: &str = "part";
+/// Bytes descargados entre dos eventos de progreso
+const PROGRESS_INTERVAL: u64 = 256 * 1024;
+/// Timeout para conectar (la descarga en sí puede tardar lo que necesite)
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Progreso de la descarga
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct DownloadProgress {
+    pub bytes: u64,
+    pub total: u64,
+}
+
+/// Petición de cancelar una descarga
+#[derive(Default)]
+struct Cancel {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+impl Cancel {
+    fn request(&self) {
+        self.requested.store(true, Ordering::Relaxed);
+        // `notify_one` guarda el aviso aunque la descarga aún no esté esperando
+        self.notify.notify_one();
+    }
+}
+
+/// Descarga en curso: la señal con la que se cancela (`None` si no hay)
+#[derive(Default)]
+pub struct UpdateDownload(Mutex<Option<Arc<Cancel>>>);
+
+/// Registra la descarga en curso y la quita del estado al terminar
+struct ActiveDownload<'a> {
+    state: &'a UpdateDownload,
+    cancel: Arc<Cancel>,
+}
+
+impl<'a> ActiveDownload<'a> {
+    /// Empieza una descarga; falla si ya hay otra en curso
+    fn start(state: &'a UpdateDownload) -> Result<Self, AppError> {
+        let mut current = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if current.is_some() {
+            return Err(AppError::Internal("An update download is already in progress".to_string()));
+        }
+
+        let cancel = Arc::new(Cancel::default());
+        *current = Some(cancel.clone());
+
+        Ok(Self { state, cancel })
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.requested.load(Ordering::Relaxed)
+    }
+
+    /// Termina cuando se pide cancelar la descarga
+    async fn cancelled(&self) {
+        if !self.is_cancelled() {
+            self.cancel.notify.notified().await;
+        }
+    }
+}
+
+impl Drop for ActiveDownload<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut current) = self.state.0.lock() {
+            *current = None;
+        }
+    }
+}
+
+/// Nombre del fichero descargado a partir del nombre del asset
+///
+/// Se queda solo con el nombre (sin directorios) y exige la extensión
+/// `.dmg`, para no escribir fuera de `~/Downloads` ni otro tipo de fichero.
+pub fn download_file_name(asset_name: &str) -> Result<String, String> {
+    let name = Path::new(asset_name.trim())
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.starts_with('.'))
+        .ok_or_else(|| format!("Invalid installer name '{}'", asset_name))?;
+
+    if !name.to_ascii_lowercase().ends_with(".dmg") || name.len() <= ".dmg".len() {
+        return Err(format!("Installer must be a .dmg: '{}'", asset_name));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Suma SHA-256 de `file_name` en el contenido de un `.sha256`
+///
+/// Acepta tanto una suma sola como el formato de `shasum` (`<suma>  <nombre>`,
+/// una línea por fichero).
+pub fn parse_checksum(contents: &str, file_name: &str) -> Option<String> {
+    let is_sha256 = |hash: &str| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+
+    contents.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next().filter(|hash| is_sha256(hash))?;
+        match parts.next() {
+            // `shasum -b` marca el nombre con `*`
+            Some(name) if name.trim_start_matches('*') != file_name => None,
+            _ => Some(hash.to_ascii_lowercase()),
+        }
+    })
+}
+
+/// Fichero `.part` que se va escribiendo y se borra si no se completa
+struct PartialFile {
+    path: PathBuf,
+    file: std::fs::File,
+    hasher: Sha256,
+    written: u64,
+    completed: bool,
+}
+
+impl PartialFile {
+    /// Crea (o trunca) el fichero parcial de `target`
+    fn create(target: &Path) -> Result<Self, AppError> {
+        let path = target.with_extension(
+            target
+                .extension()
+                .map(|ext| format!("{}.{}", ext.to_string_lossy(), PARTIAL_EXTENSION))
+                .unwrap_or_else(|| PARTIAL_EXTENSION.to_string()),
+        );
+        let file = std::fs::File::create(&path)
+            .map_err(|e| AppError::Internal(format!("Failed to create {}: {}", path.display(), e)))?;
+
+        Ok(Self {
+            path,
+            file,
+            hasher: Sha256::new(),
+            written: 0,
+            completed: false,
+        })
+    }
+
+    fn write(&mut self, chunk: &[u8]) -> Result<(), AppError> {
+        self.file
+            .write_all(chunk)
+            .map_err(|e| AppError::Internal(format!("Failed to write {}: {}", self.path.display(), e)))?;
+        self.hasher.update(chunk);
+        self.written += chunk.len() as u64;
+
+        Ok(())
+    }
+
+    /// Comprueba tamaño y suma y mueve el fichero a `target`
+    fn finish(mut self, target: &Path, expected_size: u64, expected_sha256: Option<&str>) -> Result<(), AppError> {
+        if self.written != expected_size {
+            return Err(AppError::Internal(format!(
+                "Downloaded {} bytes, expected {}",
+                self.written, expected_size
+            )));
+        }
+
+        if let Some(expected) = expected_sha256 {
+            let actual = format!("{:x}", std::mem::take(&mut self.hasher).finalize());
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(AppError::Internal(format!(
+                    "SHA-256 mismatch: got {}, expected {}",
+                    actual, expected
+                )));
+            }
+        }
+
+        self.file
+            .sync_all()
+            .map_err(|e| AppError::Internal(format!("Failed to write {}: {}", self.path.display(), e)))?;
+        std::fs::rename(&self.path, target)
+            .map_err(|e| AppError::Internal(format!("Failed to move download to {}: {}", target.display(), e)))?;
+        self.completed = true;
+
+        Ok(())
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.completed {
+            if let Err(e) = std::fs::remove_file(&self.path) {
+                tracing::warn!("Failed to remove partial download {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Cliente HTTP para la descarga
+fn http_client() -> Result<reqwest::Client, AppError> {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Petición GET que falla si la respuesta no es 2xx
+async fn get(client: &reqwest::Client, url: &str) -> Result<reqwest::Response, AppError> {
+    let response = client
+        .get(url)
+        .header("User-Agent", "X-Mac-Client")
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("HTTP request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::Network(format!("Download failed with status {}", status)));
+    }
+
+    Ok(response)
+}
+
+/// Suma SHA-256 publicada para `asset`, si la release tiene `.sha256`
+async fn fetch_checksum(client: &reqwest::Client, asset: &UpdateAsset) -> Result<Option<String>, AppError> {
+    let Some(url) = &asset.checksum_url else {
+        return Ok(None);
+    };
+
+    let contents = get(client, url)
+        .await?
+        .text()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to read checksum: {}", e)))?;
+
+    parse_checksum(&contents, &asset.name)
+        .map(Some)
+        .ok_or_else(|| AppError::Serialization(format!("No SHA-256 checksum for {} in {}", asset.name, url)))
+}
+
+/// Descarga `asset` en `target`, emitiendo el progreso a la ventana principal
+async fn download_to<R: Runtime>(
+    app: &AppHandle<R>,
+    download: &ActiveDownload<'_>,
+    asset: &UpdateAsset,
+    target: &Path,
+) -> Result<(), AppError> {
+    let client = http_client()?;
+    let checksum = fetch_checksum(&client, asset).await?;
+    let mut response = get(&client, &asset.url).await?;
+
+    let mut partial = PartialFile::create(target)?;
+    let mut last_progress = 0;
+
+    loop {
+        // Cancelar no espera a que llegue el siguiente bloque
+        let chunk = tokio::select! {
+            chunk = response.chunk() => chunk.map_err(|e| AppError::Network(format!("Download interrupted: {}", e)))?,
+            _ = download.cancelled() => return Err(AppError::Internal("Update download cancelled".to_string())),
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
+
+        partial.write(&chunk)?;
+
+        if partial.written - last_progress >= PROGRESS_INTERVAL || partial.written == asset.size {
+            last_progress = partial.written;
+            emit_progress(app, partial.written, asset.size);
+        }
+    }
+
+    partial.finish(target, asset.size, checksum.as_deref())
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, bytes: u64, total: u64) {
+    if let Err(e) = app.emit_to(crate::window::MAIN_WINDOW, DOWNLOAD_PROGRESS_EVENT, DownloadProgress { bytes, total }) {
+        tracing::debug!("Failed to emit {}: {}", DOWNLOAD_PROGRESS_EVENT, e);
+    }
+}
+
+/// Descarga el instalador de la última release a `~/Downloads` y lo abre
+///
+/// `url` tiene que ser la del `.dmg` para Apple Silicon de la última release
+/// del canal configurado (`UpdateInfo::asset`).
+///
+/// # Returns
+/// Ruta del instalador descargado
+pub async fn download_update<R: Runtime>(app: &AppHandle<R>, url: &str) -> Result<PathBuf, AppError> {
+    let state = app.state::<UpdateDownload>();
+    let download = ActiveDownload::start(&state)?;
+
+    let channel = updates::load_update_channel(&app.state::<AccountsManager>());
+    let cache = crate::update_cache::cache_path(app);
+    let release = updates::fetch_latest_release(channel, cache.as_deref()).await?;
+    let asset = updates::pick_dmg_asset(&release)
+        .ok_or_else(|| AppError::Internal("The latest release has no Apple Silicon installer".to_string()))?;
+    if asset.url != url {
+        return Err(AppError::Internal(format!("{} is not the installer of the latest release", url)));
+    }
+
+    let directory = app
+        .path()
+        .download_dir()
+        .map_err(|e| AppError::Internal(format!("Cannot resolve the Downloads directory: {}", e)))?;
+    let target = directory.join(download_file_name(&asset.name)?);
+
+    tracing::info!("Downloading {} to {}", asset.name, target.display());
+    download_to(app, &download, &asset, &target).await?;
+    tracing::info!("Downloaded {}", target.display());
+
+    tauri_plugin_opener::open_path(&target, None::<&str>)
+        .map_err(|e| AppError::Internal(format!("Failed to open {}: {}", target.display(), e)))?;
+
+    Ok(target)
+}
+
+/// Cancela la descarga en curso, si la hay
+///
+/// # Returns
+/// `true` si había una descarga que cancelar
+pub fn cancel_update_download<R: Runtime>(app: &AppHandle<R>) -> Result<bool, AppError> {
+    let state = app.state::<UpdateDownload>();
+    let current = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    Ok(match current.as_ref() {
+        Some(cancel) => {
+            cancel.request();
+            tracing::info!("Cancelling update download");
+            true
+        }
+        None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xmac-download-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_download_file_name() {
+        assert_eq!(download_file_name("X_0.6.0_aarch64.dmg").unwrap(), "X_0.6.0_aarch64.dmg");
+        assert_eq!(download_file_name("../../X_arm64.DMG").unwrap(), "X_arm64.DMG");
+
+        assert!(download_file_name("X_aarch64.zip").is_err());
+        assert!(download_file_name(".dmg").is_err());
+        assert!(download_file_name("").is_err());
+        assert!(download_file_name("..").is_err());
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let hash = "a".repeat(64);
+
+        assert_eq!(parse_checksum(&format!("{}\n", hash), "X.dmg"), Some(hash.clone()));
+        assert_eq!(
+            parse_checksum(&format!("{}  other.dmg\n{}  X.dmg\n", "b".repeat(64), hash.to_uppercase()), "X.dmg"),
+            Some(hash.clone())
+        );
+        assert_eq!(parse_checksum(&format!("{} *X.dmg", hash), "X.dmg"), Some(hash.clone()));
+
+        assert_eq!(parse_checksum(&format!("{}  other.dmg", hash), "X.dmg"), None);
+        assert_eq!(parse_checksum("not a checksum", "X.dmg"), None);
+        assert_eq!(parse_checksum(&"a".repeat(63), "X.dmg"), None);
+    }
+
+    #[test]
+    fn test_partial_file_is_verified_and_moved() {
+        let dir = test_dir("ok");
+        let target = dir.join("X_aarch64.dmg");
+
+        let mut partial = PartialFile::create(&target).unwrap();
+        let part = partial.path.clone();
+        assert_eq!(part, dir.join("X_aarch64.dmg.part"));
+        partial.write(b"hello ").unwrap();
+        partial.write(b"world").unwrap();
+
+        let sha256 = format!("{:x}", Sha256::digest(b"hello world"));
+        partial.finish(&target, 11, Some(&sha256)).unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"hello world");
+        assert!(!part.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_partial_file_is_removed_on_failure() {
+        let dir = test_dir("fail");
+        let target = dir.join("X_aarch64.dmg");
+
+        // Tamaño distinto del anunciado
+        let mut partial = PartialFile::create(&target).unwrap();
+        let part = partial.path.clone();
+        partial.write(b"hello").unwrap();
+        assert!(partial.finish(&target, 11, None).is_err());
+        assert!(!part.exists());
+        assert!(!target.exists());
+
+        // Suma que no coincide
+        let mut partial = PartialFile::create(&target).unwrap();
+        partial.write(b"hello").unwrap();
+        assert!(partial.finish(&target, 5, Some(&"0".repeat(64))).is_err());
+        assert!(!part.exists());
+        assert!(!target.exists());
+
+        // Cancelada a medias
+        let mut partial = PartialFile::create(&target).unwrap();
+        partial.write(b"hel").unwrap();
+        drop(partial);
+        assert!(!part.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_one_download_at_a_time() {
+        let state = UpdateDownload::default();
+
+        let download = ActiveDownload::start(&state).unwrap();
+        assert!(ActiveDownload::start(&state).is_err());
+        assert!(!download.is_cancelled());
+
+        // La cancelación llega aunque se pida antes de esperarla
+        state.0.lock().unwrap().as_ref().unwrap().request();
+        assert!(download.is_cancelled());
+        tokio::time::timeout(Duration::from_secs(1), download.cancelled()).await.unwrap();
+
+        drop(download);
+        assert!(state.0.lock().unwrap().is_none());
+        assert!(ActiveDownload::start(&state).is_ok());
+    }
+}
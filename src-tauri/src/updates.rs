@@ -0,0 +1,1190 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Comprobación de actualizaciones en GitHub Releases
+//
+// El repositorio se puede cambiar al compilar con `XMAC_UPDATE_REPO=owner/repo`
+// para que los forks busquen actualizaciones en sus propias releases.
+//
+// Las respuestas se guardan en `update_cache` para no gastar el límite de
+// peticiones de la API, y si no hay conexión no se llega a preguntar.
+
+use std::future::Future;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::accounts::AccountsManager;
+use crate::error::AppError;
+use crate::settings::{AppSettings, SettingsUpdate};
+use crate::update_cache::{ApiResponse, UpdateCache};
+
+/// Evento emitido cuando hay una versión nueva (payload: `UpdateInfo`)
+pub const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+/// Horas entre comprobaciones automáticas por defecto
+pub const DEFAULT_CHECK_INTERVAL_HOURS: u32 = 24;
+/// Intervalo máximo entre comprobaciones automáticas (30 días)
+pub const MAX_CHECK_INTERVAL_HOURS: u32 = 30 * 24;
+
+/// Repositorio de releases por defecto
+const DEFAULT_UPDATE_REPO: &str = "686f6c61/Xcom-mac-silicon";
+/// Reintentos tras un error de conexión o timeout
+const MAX_RETRIES: u32 = 3;
+/// Espera antes del primer reintento; se duplica en cada uno (1s, 2s, 4s)
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Timeout de cada petición a la API de GitHub
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Servidor con el que se comprueba si hay conexión antes de preguntar
+const CONNECTIVITY_PROBE_HOST: (&str, u16) = ("api.github.com", 443);
+/// Tiempo máximo de la comprobación de conexión
+const CONNECTIVITY_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Tamaño máximo (bytes) de las notas de la release enviadas al frontend
+const MAX_CHANGELOG_BYTES: usize = 4 * 1024;
+/// Única web en la que se aceptan las URLs de release que devuelve la API
+const RELEASE_URL_PREFIX: &str = "https://github.com/";
+/// Aplazamiento máximo de los avisos (30 días)
+const MAX_SNOOZE_HOURS: u32 = 30 * 24;
+/// Arquitecturas que identifican el instalador para Apple Silicon
+const APPLE_SILICON_ARCHES: [&str; 2] = ["aarch64", "arm64"];
+/// Extensión del instalador
+const INSTALLER_EXTENSION: &str = ".dmg";
+/// Extensión de las sumas SHA-256 publicadas con la release
+const CHECKSUM_EXTENSION: &str = ".sha256";
+
+/// Instalador para Apple Silicon publicado con una release
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct UpdateAsset {
+    pub name: String,
+    /// URL de descarga (`browser_download_url`)
+    pub url: String,
+    /// Tamaño en bytes según GitHub
+    pub size: u64,
+    /// URL del `.sha256` publicado con el instalador, si lo hay
+    pub checksum_url: Option<String>,
+}
+
+/// Busca en `assets` de la release el `.dmg` para Apple Silicon (con
+/// `aarch64` o `arm64` en el nombre) y su `.sha256`
+///
+/// Solo se aceptan URLs de descarga de GitHub. Para la suma se prefiere
+/// `<instalador>.sha256`; si no, cualquier `.sha256` de la release.
+pub fn pick_dmg_asset(release: &serde_json::Value) -> Option<UpdateAsset> {
+    // (nombre, URL, tamaño) de los assets bien formados
+    let assets: Vec<(&str, &str, u64)> = release["assets"]
+        .as_array()?
+        .iter()
+        .filter_map(|asset| {
+            let url = asset["browser_download_url"].as_str()?;
+            url.starts_with(RELEASE_URL_PREFIX)
+                .then_some((asset["name"].as_str()?, url, asset["size"].as_u64()?))
+        })
+        .collect();
+
+    let (name, url, size) = assets.iter().copied().find(|(name, _, _)| {
+        let name = name.to_ascii_lowercase();
+        name.ends_with(INSTALLER_EXTENSION) && APPLE_SILICON_ARCHES.iter().any(|arch| name.contains(arch))
+    })?;
+
+    let checksum_name = format!("{}{}", name, CHECKSUM_EXTENSION);
+    let checksum_url = assets
+        .iter()
+        .find(|(other, _, _)| *other == checksum_name)
+        .or_else(|| assets.iter().find(|(other, _, _)| other.to_ascii_lowercase().ends_with(CHECKSUM_EXTENSION)))
+        .map(|(_, url, _)| url.to_string());
+
+    Some(UpdateAsset {
+        name: name.to_string(),
+        url: url.to_string(),
+        size,
+        checksum_url,
+    })
+}
+
+/// Resultado de comprobar si hay actualizaciones
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct UpdateInfo {
+    /// Versión en ejecución
+    pub current: String,
+    /// Versión de la última release
+    pub latest: String,
+    pub update_available: bool,
+    /// Página de la última release en GitHub
+    pub release_url: String,
+    /// Notas de la release en markdown (recortadas a `MAX_CHANGELOG_BYTES`)
+    pub release_notes: Option<String>,
+    /// Fecha de publicación (ISO 8601, tal como la devuelve GitHub)
+    pub published_at: Option<String>,
+    /// Canal con el que se ha comprobado
+    pub channel: UpdateChannel,
+    /// Instalador que se puede descargar con `download_update`
+    pub asset: Option<UpdateAsset>,
+}
+
+impl UpdateInfo {
+    /// Interpreta una release de la API de GitHub
+    ///
+    /// Si la respuesta no trae una URL de GitHub válida, `release_url` es la
+    /// página de releases de `update_repo()`.
+    pub fn from_release(release: &serde_json::Value, current: &str, channel: UpdateChannel) -> Result<Self, AppError> {
+        let latest = release["tag_name"]
+            .as_str()
+            .ok_or_else(|| AppError::Serialization("Missing tag_name in release response".to_string()))?
+            .trim_start_matches('v')
+            .to_string();
+
+        let release_notes = release["body"]
+            .as_str()
+            .map(str::trim)
+            .filter(|body| !body.is_empty())
+            .map(|body| truncate_changelog(body, MAX_CHANGELOG_BYTES));
+
+        let release_url = match release["html_url"].as_str() {
+            Some(url) if url.starts_with(RELEASE_URL_PREFIX) => url.to_string(),
+            _ => releases_page_url(update_repo())?,
+        };
+
+        Ok(Self {
+            current: current.to_string(),
+            update_available: is_newer(&latest, current, channel.includes_prereleases()),
+            latest,
+            release_url,
+            release_notes,
+            published_at: release["published_at"].as_str().map(str::to_string),
+            channel,
+            asset: pick_dmg_asset(release),
+        })
+    }
+}
+
+/// Interpreta una versión con o sin la `v` inicial de los tags
+fn parse_version(version: &str) -> Result<semver::Version, semver::Error> {
+    let version = version.trim();
+    semver::Version::parse(version.strip_prefix(['v', 'V']).unwrap_or(version))
+}
+
+/// Si la release `latest` es posterior a la versión en ejecución `current`
+///
+/// Compara según semver (`0.10.0` > `0.9.0`), así que una build de
+/// desarrollo más nueva que la última release no sugiere bajar de versión.
+/// Las pre-releases solo cuentan con `include_prereleases` (canal beta) o si
+/// `current` también lo es. Si alguna de las dos versiones no es válida,
+/// avisa en el log y no hay actualización.
+fn is_newer(latest: &str, current: &str, include_prereleases: bool) -> bool {
+    let (latest, current) = match (parse_version(latest), parse_version(current)) {
+        (Ok(latest), Ok(current)) => (latest, current),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::warn!("Cannot compare versions '{}' and '{}': {}", latest, current, e);
+            return false;
+        }
+    };
+
+    if !latest.pre.is_empty() && current.pre.is_empty() && !include_prereleases {
+        return false;
+    }
+
+    latest > current
+}
+
+/// Releases que se tienen en cuenta al buscar actualizaciones
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Solo releases publicadas (`releases/latest`)
+    #[default]
+    Stable,
+    /// También las pre-releases de GitHub
+    Beta,
+}
+
+impl UpdateChannel {
+    /// Si las pre-releases cuentan como actualización
+    pub fn includes_prereleases(self) -> bool {
+        self == Self::Beta
+    }
+}
+
+/// Lee el canal guardado (por defecto, `Stable`)
+pub fn load_update_channel(manager: &AccountsManager) -> UpdateChannel {
+    manager.settings().update_channel
+}
+
+/// Guarda el canal para las próximas comprobaciones
+pub fn save_update_channel(manager: &AccountsManager, channel: UpdateChannel) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        update_channel: Some(channel),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// Guarda el canal y actualiza las marcas del menú Ayuda
+pub fn set_update_channel<R: Runtime>(
+    app: &AppHandle<R>,
+    manager: &AccountsManager,
+    channel: UpdateChannel,
+) -> Result<(), AppError> {
+    save_update_channel(manager, channel)?;
+    crate::menu::builder::rebuild_menu(app).map_err(|e| AppError::Internal(format!("Failed to rebuild menu: {}", e)))?;
+
+    tracing::info!("Update channel set to {:?}", channel);
+
+    Ok(())
+}
+
+/// La release más nueva de la lista de `/releases`, pre-releases incluidas
+///
+/// Se ignoran los borradores y los tags que no son versiones; GitHub ordena
+/// por fecha de creación, así que se compara por versión.
+pub fn newest_release(releases: &serde_json::Value) -> Option<&serde_json::Value> {
+    releases
+        .as_array()?
+        .iter()
+        .filter(|release| !release["draft"].as_bool().unwrap_or(false))
+        .filter_map(|release| {
+            let version = parse_version(release["tag_name"].as_str()?).ok()?;
+            Some((version, release))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
+
+/// Qué hacer con la comprobación de actualizaciones al arrancar
+///
+/// La comprobación nunca abre el navegador por sí sola: emite
+/// `update-available` y el frontend decide, según la política, cómo avisar.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoUpdatePolicy {
+    /// No comprobar
+    Off,
+    /// Avisar de la versión nueva, sin ofrecer abrir nada
+    NotifyOnly,
+    /// Avisar y ofrecer abrir la página de la release
+    #[default]
+    OpenPage,
+}
+
+impl AutoUpdatePolicy {
+    /// Si hay que lanzar la comprobación al arrancar
+    pub fn checks_on_launch(self) -> bool {
+        self != Self::Off
+    }
+
+    /// Política con la comprobación al arrancar activada o no
+    ///
+    /// Al activarla desde `Off` se vuelve a la política por defecto; si ya
+    /// estaba activada se conserva.
+    pub fn with_launch_check(self, enabled: bool) -> Self {
+        match (enabled, self) {
+            (false, _) => Self::Off,
+            (true, Self::Off) => Self::default(),
+            (true, policy) => policy,
+        }
+    }
+}
+
+/// Lee la política guardada (por defecto, `OpenPage`)
+pub fn load_update_policy(manager: &AccountsManager) -> AutoUpdatePolicy {
+    manager.settings().update_policy
+}
+
+/// Guarda la política para los próximos arranques
+pub fn save_update_policy(manager: &AccountsManager, policy: AutoUpdatePolicy) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        update_policy: Some(policy),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// Versión omitida por el usuario, si la hay
+pub fn load_skipped_version(manager: &AccountsManager) -> Option<String> {
+    manager.settings().skipped_version
+}
+
+/// No volver a avisar de `version` en la comprobación automática
+///
+/// Devuelve la versión normalizada (sin la `v` inicial).
+pub fn skip_version(manager: &AccountsManager, version: &str) -> Result<String, AppError> {
+    let version = parse_version(version)
+        .map_err(|e| AppError::Internal(format!("Invalid version '{}': {}", version.trim(), e)))?
+        .to_string();
+    manager.update_settings(&SettingsUpdate {
+        skipped_version: Some(Some(version.clone())),
+        ..Default::default()
+    })?;
+
+    Ok(version)
+}
+
+/// Momento (segundos Unix) hasta el que están aplazados los avisos
+pub fn load_snoozed_until(manager: &AccountsManager) -> Option<i64> {
+    manager.settings().snoozed_until
+}
+
+/// Aplaza los avisos automáticos `hours` horas a partir de `now`
+///
+/// # Returns
+/// Momento (segundos Unix) en el que vuelven a mostrarse
+pub fn snooze(manager: &AccountsManager, hours: u32, now: i64) -> Result<i64, AppError> {
+    if hours == 0 || hours > MAX_SNOOZE_HOURS {
+        return Err(AppError::Internal(format!(
+            "Snooze must be between 1 and {} hours, got {}",
+            MAX_SNOOZE_HOURS, hours
+        )));
+    }
+
+    let until = now + i64::from(hours) * 3600;
+    manager.update_settings(&SettingsUpdate {
+        snoozed_until: Some(until),
+        ..Default::default()
+    })?;
+
+    Ok(until)
+}
+
+/// Activa o desactiva la comprobación al arrancar, con al menos
+/// `interval_hours` entre dos comprobaciones, y actualiza la marca del menú
+/// Ayuda
+///
+/// Desactivarla es lo mismo que la política `Off` (ver
+/// `AutoUpdatePolicy::with_launch_check`).
+///
+/// # Returns
+/// Preferencias resultantes
+pub fn set_auto_update_check<R: Runtime>(
+    app: &AppHandle<R>,
+    manager: &AccountsManager,
+    enabled: bool,
+    interval_hours: u32,
+) -> Result<AppSettings, AppError> {
+    let policy = load_update_policy(manager).with_launch_check(enabled);
+    let settings = manager.update_settings(&SettingsUpdate {
+        update_policy: Some(policy),
+        update_check_interval_hours: Some(interval_hours),
+        ..Default::default()
+    })?;
+    crate::menu::builder::rebuild_menu(app).map_err(|e| AppError::Internal(format!("Failed to rebuild menu: {}", e)))?;
+
+    tracing::info!(
+        "Automatic update check {} (every {}h)",
+        if settings.update_policy.checks_on_launch() { "enabled" } else { "disabled" },
+        settings.update_check_interval_hours
+    );
+
+    Ok(settings)
+}
+
+/// Momento (segundos Unix) de la última comprobación correcta
+pub fn load_last_check(manager: &AccountsManager) -> Option<i64> {
+    manager.settings().last_update_check
+}
+
+/// Apunta que en `now` se ha comprobado correctamente si hay actualizaciones
+pub fn record_check(manager: &AccountsManager, now: i64) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        last_update_check: Some(now),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// Si en `now` toca la comprobación automática: han pasado `interval_hours`
+/// desde la última correcta (`last`), o no se ha hecho nunca
+pub fn check_due(last: Option<i64>, interval_hours: u32, now: i64) -> bool {
+    match last {
+        // Un reloj atrasado no debe bloquear las comprobaciones
+        Some(last) if last <= now => now - last >= i64::from(interval_hours) * 3600,
+        _ => true,
+    }
+}
+
+/// Si la comprobación automática debe avisar de `info` en el momento `now`
+///
+/// Calla si no hay actualización, si el aviso está aplazado o si `latest` es
+/// la versión omitida. Si ha salido una versión aún más nueva que la omitida,
+/// la omisión deja de valer y se borra. La comprobación del menú no pasa por
+/// aquí: siempre informa.
+pub fn should_notify(manager: &AccountsManager, info: &UpdateInfo, now: i64) -> bool {
+    if !info.update_available {
+        return false;
+    }
+
+    if load_snoozed_until(manager).is_some_and(|until| now < until) {
+        tracing::info!("Update notification snoozed");
+        return false;
+    }
+
+    if let Some(skipped) = load_skipped_version(manager) {
+        if !is_newer(&info.latest, &skipped, true) {
+            tracing::info!("Version {} was skipped", skipped);
+            return false;
+        }
+
+        tracing::info!("Version {} is newer than skipped {}", info.latest, skipped);
+        let cleared = manager.update_settings(&SettingsUpdate {
+            skipped_version: Some(None),
+            ..Default::default()
+        });
+        if let Err(e) = cleared {
+            tracing::warn!("Failed to clear skipped version: {}", e);
+        }
+    }
+
+    true
+}
+
+/// Versión nueva encontrada en esta sesión, que el menú Ayuda ofrece
+/// instalar ("Actualizar a vX.Y.Z…")
+///
+/// Solo vive en memoria: al reiniciar con la versión nueva ya no hay nada que
+/// ofrecer.
+#[derive(Default)]
+pub struct AvailableUpdate(Mutex<Option<UpdateInfo>>);
+
+impl AvailableUpdate {
+    /// Versión ofrecida, si la hay
+    pub fn get(&self) -> Option<UpdateInfo> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Sustituye la versión ofrecida; retorna si ha cambiado cuál es
+    fn replace(&self, update: Option<UpdateInfo>) -> bool {
+        let mut current = self.0.lock().unwrap();
+        let changed = current.as_ref().map(|info| &info.latest) != update.as_ref().map(|info| &info.latest);
+        *current = update;
+        changed
+    }
+}
+
+/// Versión que el menú debe ofrecer según `info`: ninguna si no hay
+/// actualización o si es (como mucho) la omitida con `skip_version`
+fn offered_update(info: &UpdateInfo, skipped: Option<&str>) -> Option<UpdateInfo> {
+    let skipped = skipped.is_some_and(|skipped| !is_newer(&info.latest, skipped, true));
+    (info.update_available && !skipped).then(|| info.clone())
+}
+
+/// Guarda el resultado de una comprobación correcta para el menú Ayuda y lo
+/// reconstruye si cambia la versión ofrecida
+pub fn set_available_update<R: Runtime>(app: &AppHandle<R>, manager: &AccountsManager, info: &UpdateInfo) {
+    let offered = offered_update(info, load_skipped_version(manager).as_deref());
+    replace_available_update(app, offered);
+}
+
+/// Deja de ofrecer `version` en el menú Ayuda (se ha omitido)
+pub fn forget_available_update<R: Runtime>(app: &AppHandle<R>, version: &str) {
+    let Some(state) = app.try_state::<AvailableUpdate>() else {
+        return;
+    };
+    if state.get().is_some_and(|info| info.latest == version) {
+        replace_available_update(app, None);
+    }
+}
+
+fn replace_available_update<R: Runtime>(app: &AppHandle<R>, update: Option<UpdateInfo>) {
+    let Some(state) = app.try_state::<AvailableUpdate>() else {
+        return;
+    };
+    if !state.replace(update) {
+        return;
+    }
+
+    if let Err(e) = crate::menu::builder::rebuild_menu(app) {
+        tracing::error!("Failed to rebuild menu: {}", e);
+    }
+}
+
+/// Recorta `body` a `limit` bytes sin partir un carácter UTF-8
+fn truncate_changelog(body: &str, limit: usize) -> String {
+    if body.len() <= limit {
+        return body.to_string();
+    }
+
+    let mut end = limit;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}…", body[..end].trim_end())
+}
+
+/// Fallo de un intento: solo los transitorios se reintentan
+#[derive(Debug)]
+enum FetchError {
+    /// Error de conexión o timeout (p. ej. Wi-Fi al despertar el Mac)
+    Transient(AppError),
+    /// Respuesta HTTP de error o JSON inválido: reintentar no cambia nada
+    Fatal(AppError),
+}
+
+/// Repositorio `owner/repo` configurado al compilar (o el por defecto)
+pub fn update_repo() -> &'static str {
+    option_env!("XMAC_UPDATE_REPO").unwrap_or(DEFAULT_UPDATE_REPO)
+}
+
+/// Separa y valida un repositorio con formato `owner/repo`
+///
+/// GitHub solo admite letras, dígitos, `-`, `_` y `.` en ambos nombres.
+fn parse_repo(repo: &str) -> Result<(&str, &str), AppError> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+
+    match repo.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok((owner, name)),
+        _ => Err(AppError::Internal(format!(
+            "Invalid update repository '{}': expected 'owner/repo'",
+            repo
+        ))),
+    }
+}
+
+/// URL de la API con la última release de `repo`
+pub fn latest_release_url(repo: &str) -> Result<String, AppError> {
+    let (owner, name) = parse_repo(repo)?;
+    Ok(format!("https://api.github.com/repos/{}/{}/releases/latest", owner, name))
+}
+
+/// URL de la API con las releases de `repo` (pre-releases incluidas)
+pub fn releases_url(repo: &str) -> Result<String, AppError> {
+    let (owner, name) = parse_repo(repo)?;
+    Ok(format!("https://api.github.com/repos/{}/{}/releases", owner, name))
+}
+
+/// Página de releases de `repo` (la que se abre en el navegador)
+pub fn releases_page_url(repo: &str) -> Result<String, AppError> {
+    let (owner, name) = parse_repo(repo)?;
+    Ok(format!("https://github.com/{}/{}/releases", owner, name))
+}
+
+/// Espera antes del reintento `retry` (0-based)
+fn backoff_delay(retry: u32) -> Duration {
+    INITIAL_BACKOFF * 2u32.pow(retry)
+}
+
+/// Ejecuta `attempt` hasta que tenga éxito, falle con un error no transitorio
+/// o se agoten los reintentos
+///
+/// `sleep` se recibe como parámetro para poder probar la espera sin esperar.
+async fn retry_with_backoff<T, F, Fut, S, SFut>(mut attempt: F, mut sleep: S) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+    S: FnMut(Duration) -> SFut,
+    SFut: Future<Output = ()>,
+{
+    let mut retry = 0;
+
+    loop {
+        tracing::debug!("Update check attempt {}/{}", retry + 1, MAX_RETRIES + 1);
+
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(FetchError::Transient(e)) if retry < MAX_RETRIES => {
+                let delay = backoff_delay(retry);
+                tracing::debug!("Update check failed ({}), retrying in {:?}", e, delay);
+                sleep(delay).await;
+                retry += 1;
+            }
+            Err(FetchError::Transient(e)) | Err(FetchError::Fatal(e)) => return Err(e),
+        }
+    }
+}
+
+/// Si hay conexión: basta con poder abrir una conexión TCP con la API en
+/// `CONNECTIVITY_PROBE_TIMEOUT`
+pub async fn is_online() -> bool {
+    matches!(
+        tokio::time::timeout(CONNECTIVITY_PROBE_TIMEOUT, tokio::net::TcpStream::connect(CONNECTIVITY_PROBE_HOST)).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Cuándo se renueva el límite de peticiones si `status` y `headers` son los
+/// de un límite agotado (403 o 429 con `X-RateLimit-Remaining: 0`)
+fn rate_limit_reset(status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap) -> Option<i64> {
+    let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+
+    let limited = matches!(status, reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS)
+        && header("x-ratelimit-remaining") == Some("0");
+    if !limited {
+        return None;
+    }
+
+    header("x-ratelimit-reset")?.parse().ok()
+}
+
+/// Una petición a la API de releases
+///
+/// Con `etag` se envía `If-None-Match`, y GitHub responde 304 si no ha
+/// cambiado nada.
+async fn request_latest_release(
+    client: &reqwest::Client,
+    url: &str,
+    etag: Option<&str>,
+) -> Result<ApiResponse, FetchError> {
+    let mut request = client.get(url).header("User-Agent", "X-Mac-Client");
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| {
+            let error = AppError::Network(format!("HTTP request failed: {}", e));
+            if e.is_connect() || e.is_timeout() {
+                FetchError::Transient(error)
+            } else {
+                FetchError::Fatal(error)
+            }
+        })?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(ApiResponse::NotModified);
+    }
+    if let Some(reset) = rate_limit_reset(status, response.headers()) {
+        tracing::warn!("GitHub API rate limit exceeded until {}", reset);
+        return Ok(ApiResponse::RateLimited { reset });
+    }
+    if !status.is_success() {
+        tracing::error!("GitHub API returned status: {}", status);
+        return Err(FetchError::Fatal(AppError::Network(format!("GitHub API error: {}", status))));
+    }
+
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response
+        .json()
+        .await
+        .map_err(|e| FetchError::Fatal(AppError::Serialization(format!("Failed to parse JSON: {}", e))))?;
+
+    Ok(ApiResponse::Fresh { body, etag })
+}
+
+/// Pide `url` a la API pasando por la caché de `cache_path`
+///
+/// Mientras dure el límite de peticiones no se pregunta a GitHub. Sin
+/// conexión devuelve `AppError::Offline` sin llegar a intentarlo.
+async fn request_cached(url: &str, cache_path: Option<&Path>) -> Result<serde_json::Value, AppError> {
+    let mut cache = cache_path.map(UpdateCache::load).unwrap_or_default();
+
+    if let Some(reset) = cache.rate_limit(chrono::Utc::now().timestamp()) {
+        tracing::info!("GitHub API rate limit in effect until {}, using the cached response", reset);
+        return cache.resolve(url, ApiResponse::RateLimited { reset });
+    }
+
+    if !is_online().await {
+        return Err(AppError::Offline);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))?;
+
+    let etag = cache.etag(url).map(str::to_string);
+    let response =
+        retry_with_backoff(|| request_latest_release(&client, url, etag.as_deref()), tokio::time::sleep).await?;
+    if response == ApiResponse::NotModified {
+        tracing::info!("Latest release unchanged since the last check");
+    }
+
+    let body = cache.resolve(url, response);
+    if let Some(path) = cache_path {
+        if let Err(e) = cache.save(path) {
+            tracing::warn!("Failed to save update cache: {}", e);
+        }
+    }
+
+    body
+}
+
+/// Descarga la última release de `update_repo()` en `channel`, reintentando
+/// los errores de red transitorios
+///
+/// En el canal beta se pide la lista de releases y se elige la más nueva.
+/// Con `cache_path` (ver `update_cache::cache_path`) las respuestas se
+/// guardan en disco y solo se vuelven a descargar si han cambiado.
+pub async fn fetch_latest_release(
+    channel: UpdateChannel,
+    cache_path: Option<&Path>,
+) -> Result<serde_json::Value, AppError> {
+    let url = match channel {
+        UpdateChannel::Stable => latest_release_url(update_repo())?,
+        UpdateChannel::Beta => releases_url(update_repo())?,
+    };
+
+    let response = request_cached(&url, cache_path).await?;
+
+    match channel {
+        UpdateChannel::Stable => Ok(response),
+        UpdateChannel::Beta => newest_release(&response)
+            .cloned()
+            .ok_or_else(|| AppError::Serialization("No releases found".to_string())),
+    }
+}
+
+/// Compara la versión en ejecución con la última release de `update_repo()`
+/// en `channel`
+///
+/// No notifica nada: cada llamador decide cómo mostrar el resultado.
+pub async fn check_for_updates(channel: UpdateChannel, cache_path: Option<&Path>) -> Result<UpdateInfo, AppError> {
+    tracing::info!("Checking for updates in {} ({:?})...", update_repo(), channel);
+
+    let release = fetch_latest_release(channel, cache_path).await?;
+    let info = UpdateInfo::from_release(&release, env!("CARGO_PKG_VERSION"), channel)?;
+
+    tracing::info!("Current: {}, Latest: {}", info.current, info.latest);
+
+    if info.update_available {
+        tracing::info!("New version available: {}", info.latest);
+    } else {
+        tracing::info!("Already on latest version");
+    }
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+    use std::cell::{Cell, RefCell};
+
+    fn test_manager() -> AccountsManager {
+        AccountsManager::new(Box::leak(Box::new(InMemoryStore::new())))
+    }
+
+    #[test]
+    fn test_release_urls() {
+        assert_eq!(
+            latest_release_url(DEFAULT_UPDATE_REPO).unwrap(),
+            "https://api.github.com/repos/686f6c61/Xcom-mac-silicon/releases/latest"
+        );
+        assert_eq!(
+            latest_release_url("someone/x.mac_fork").unwrap(),
+            "https://api.github.com/repos/someone/x.mac_fork/releases/latest"
+        );
+        assert_eq!(
+            releases_page_url("someone/fork").unwrap(),
+            "https://github.com/someone/fork/releases"
+        );
+        assert_eq!(
+            releases_url(DEFAULT_UPDATE_REPO).unwrap(),
+            "https://api.github.com/repos/686f6c61/Xcom-mac-silicon/releases"
+        );
+    }
+
+    #[test]
+    fn test_malformed_repo_is_rejected() {
+        for repo in ["", "owner", "owner/", "/repo", "a/b/c", "owner/re po", "owner/..", "https://github.com/a/b"] {
+            assert!(latest_release_url(repo).is_err(), "{:?} should be rejected", repo);
+        }
+
+        let message = latest_release_url("owner").unwrap_err().to_string();
+        assert!(message.contains("'owner'"));
+        assert!(message.contains("owner/repo"));
+    }
+
+    #[test]
+    fn test_update_info_from_release() {
+        let release = serde_json::json!({
+            "tag_name": "v0.6.0",
+            "html_url": "https://github.com/686f6c61/Xcom-mac-silicon/releases/tag/v0.6.0",
+            "published_at": "2025-03-01T10:00:00Z",
+            "body": "## Novedades\n\n- Cuentas fijadas\n",
+            "assets": []
+        });
+
+        let info = UpdateInfo::from_release(&release, "0.5.0", UpdateChannel::Stable).unwrap();
+        assert_eq!(
+            info,
+            UpdateInfo {
+                current: "0.5.0".to_string(),
+                latest: "0.6.0".to_string(),
+                update_available: true,
+                release_url: "https://github.com/686f6c61/Xcom-mac-silicon/releases/tag/v0.6.0".to_string(),
+                release_notes: Some("## Novedades\n\n- Cuentas fijadas".to_string()),
+                published_at: Some("2025-03-01T10:00:00Z".to_string()),
+                channel: UpdateChannel::Stable,
+                asset: None,
+            }
+        );
+
+        let same = UpdateInfo::from_release(
+            &serde_json::json!({ "tag_name": "v0.5.0", "body": null }),
+            "0.5.0",
+            UpdateChannel::Stable,
+        )
+        .unwrap();
+        assert!(!same.update_available);
+        assert_eq!(same.release_notes, None);
+        assert_eq!(same.published_at, None);
+        assert_eq!(same.release_url, releases_page_url(update_repo()).unwrap());
+
+        assert!(UpdateInfo::from_release(&serde_json::json!({}), "0.5.0", UpdateChannel::Stable).is_err());
+    }
+
+    #[test]
+    fn test_pick_dmg_asset() {
+        let asset = |name: &str, size: u64| {
+            serde_json::json!({
+                "name": name,
+                "size": size,
+                "browser_download_url": format!("https://github.com/686f6c61/Xcom-mac-silicon/releases/download/v0.6.0/{}", name),
+            })
+        };
+        let release = serde_json::json!({
+            "tag_name": "v0.6.0",
+            "assets": [
+                asset("X_0.6.0_x64.dmg", 100),
+                asset("X_0.6.0_aarch64.dmg", 200),
+                asset("SHA256SUMS.sha256", 1),
+                asset("X_0.6.0_aarch64.dmg.sha256", 2),
+            ],
+        });
+
+        let picked = pick_dmg_asset(&release).unwrap();
+        assert_eq!(picked.name, "X_0.6.0_aarch64.dmg");
+        assert_eq!(picked.size, 200);
+        assert!(picked.url.ends_with("/X_0.6.0_aarch64.dmg"));
+        assert!(picked.checksum_url.unwrap().ends_with("/X_0.6.0_aarch64.dmg.sha256"));
+        assert_eq!(
+            UpdateInfo::from_release(&release, "0.5.0", UpdateChannel::Stable).unwrap().asset.unwrap().name,
+            "X_0.6.0_aarch64.dmg"
+        );
+
+        // `arm64`, sin suma propia
+        let release = serde_json::json!({ "assets": [asset("X-ARM64.DMG", 5), asset("SHA256SUMS.sha256", 1)] });
+        let picked = pick_dmg_asset(&release).unwrap();
+        assert_eq!(picked.name, "X-ARM64.DMG");
+        assert!(picked.checksum_url.unwrap().ends_with("/SHA256SUMS.sha256"));
+
+        // Sin instalador para Apple Silicon, o fuera de GitHub
+        assert!(pick_dmg_asset(&serde_json::json!({ "assets": [asset("X_x64.dmg", 1), asset("X_aarch64.zip", 1)] })).is_none());
+        let foreign = serde_json::json!({ "assets": [{
+            "name": "X_aarch64.dmg",
+            "size": 1,
+            "browser_download_url": "https://evil.example/X_aarch64.dmg",
+        }] });
+        assert!(pick_dmg_asset(&foreign).is_none());
+        assert!(pick_dmg_asset(&serde_json::json!({ "tag_name": "v0.6.0" })).is_none());
+    }
+
+    #[test]
+    fn test_version_comparison() {
+        // Más nueva, también con números de dos cifras
+        assert!(is_newer("0.6.0", "0.5.0", false));
+        assert!(is_newer("v0.10.0", "0.9.0", false));
+        assert!(is_newer("V1.0.0", "0.9.9", false));
+
+        // Igual o anterior (p. ej. una build de desarrollo)
+        assert!(!is_newer("v0.5.0", "0.5.0", false));
+        assert!(!is_newer("0.4.2", "0.5.0-dev", false));
+        assert!(!is_newer("0.9.0", "0.10.0", false));
+
+        // Pre-releases: solo si la build actual también lo es
+        assert!(!is_newer("0.6.0-beta.1", "0.5.0", false));
+        assert!(is_newer("0.6.0-beta.2", "0.6.0-beta.1", false));
+        assert!(is_newer("0.6.0", "0.6.0-beta.1", false));
+
+        // Tags que no son versiones
+        assert!(!is_newer("nightly", "0.5.0", false));
+        assert!(!is_newer("v0.6", "0.5.0", false));
+        assert!(!is_newer("", "0.5.0", false));
+        assert!(!is_newer("0.6.0", "dev", false));
+    }
+
+    #[test]
+    fn test_beta_channel_includes_prereleases() {
+        let release = serde_json::json!({ "tag_name": "v0.6.0-beta.1", "prerelease": true });
+
+        let stable = UpdateInfo::from_release(&release, "0.5.0", UpdateChannel::Stable).unwrap();
+        assert!(!stable.update_available);
+
+        let beta = UpdateInfo::from_release(&release, "0.5.0", UpdateChannel::Beta).unwrap();
+        assert!(beta.update_available);
+        assert_eq!(beta.channel, UpdateChannel::Beta);
+
+        // La versión final es posterior a sus betas
+        assert!(!is_newer("0.6.0-beta.1", "0.6.0", true));
+    }
+
+    #[test]
+    fn test_newest_release() {
+        let releases = serde_json::json!([
+            { "tag_name": "v0.6.0-beta.2", "prerelease": true, "draft": false },
+            { "tag_name": "v0.7.0", "prerelease": false, "draft": true },
+            { "tag_name": "nightly", "prerelease": true, "draft": false },
+            { "tag_name": "v0.10.0-rc.1", "prerelease": true, "draft": false },
+            { "tag_name": "v0.9.0", "prerelease": false, "draft": false },
+        ]);
+        assert_eq!(newest_release(&releases).unwrap()["tag_name"], "v0.10.0-rc.1");
+
+        assert!(newest_release(&serde_json::json!([])).is_none());
+        assert!(newest_release(&serde_json::json!({ "message": "Not Found" })).is_none());
+    }
+
+    #[test]
+    fn test_update_channel_persistence() {
+        assert_eq!(serde_json::to_string(&UpdateChannel::Stable).unwrap(), "\"stable\"");
+        assert_eq!(serde_json::from_str::<UpdateChannel>("\"beta\"").unwrap(), UpdateChannel::Beta);
+
+        let manager = test_manager();
+        assert_eq!(load_update_channel(&manager), UpdateChannel::Stable);
+
+        save_update_channel(&manager, UpdateChannel::Beta).unwrap();
+        assert_eq!(load_update_channel(&manager), UpdateChannel::Beta);
+    }
+
+    #[test]
+    fn test_malformed_tag_reports_no_update() {
+        let release = serde_json::json!({ "tag_name": "nightly-2025" });
+        let info = UpdateInfo::from_release(&release, "0.5.0", UpdateChannel::Stable).unwrap();
+        assert!(!info.update_available);
+        assert_eq!(info.latest, "nightly-2025");
+
+        let release = serde_json::json!({ "tag_name": "v0.4.2" });
+        let downgrade = UpdateInfo::from_release(&release, "0.5.0-dev", UpdateChannel::Stable).unwrap();
+        assert!(!downgrade.update_available);
+    }
+
+    #[test]
+    fn test_release_url_must_be_github() {
+        let release = serde_json::json!({ "tag_name": "v0.6.0", "html_url": "https://evil.example/download" });
+        let info = UpdateInfo::from_release(&release, "0.5.0", UpdateChannel::Stable).unwrap();
+        assert_eq!(info.release_url, releases_page_url(update_repo()).unwrap());
+
+        // Serializado con los nombres que usa el frontend
+        let json = serde_json::to_value(&info).unwrap();
+        for field in ["current", "latest", "update_available", "release_url", "release_notes", "published_at", "channel"] {
+            assert!(json.get(field).is_some(), "missing {}", field);
+        }
+    }
+
+    #[test]
+    fn test_changelog_is_truncated() {
+        let body = "ñ".repeat(MAX_CHANGELOG_BYTES);
+        let truncated = truncate_changelog(&body, MAX_CHANGELOG_BYTES);
+
+        assert!(truncated.len() <= MAX_CHANGELOG_BYTES + "…".len());
+        assert!(truncated.ends_with('…'));
+        assert_eq!(truncate_changelog("corto", MAX_CHANGELOG_BYTES), "corto");
+    }
+
+    #[test]
+    fn test_backoff_schedule() {
+        let schedule: Vec<Duration> = (0..MAX_RETRIES).map(backoff_delay).collect();
+
+        assert_eq!(
+            schedule,
+            vec![Duration::from_secs(1), Duration::from_secs(2), Duration::from_secs(4)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_only_transient_errors() {
+        let network = || AppError::Network("offline".to_string());
+
+        // Dos fallos de conexión y después éxito
+        let calls = Cell::new(0);
+        let slept = RefCell::new(Vec::new());
+        let result = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                let outcome = if calls.get() < 3 { Err(FetchError::Transient(network())) } else { Ok(calls.get()) };
+                async move { outcome }
+            },
+            |delay| {
+                slept.borrow_mut().push(delay);
+                async {}
+            },
+        )
+        .await;
+        assert_eq!(result, Ok(3));
+        assert_eq!(*slept.borrow(), vec![Duration::from_secs(1), Duration::from_secs(2)]);
+
+        // Se rinde tras MAX_RETRIES reintentos
+        let calls = Cell::new(0);
+        let result: Result<(), AppError> = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(FetchError::Transient(network())) }
+            },
+            |_| async {},
+        )
+        .await;
+        assert_eq!(result, Err(network()));
+        assert_eq!(calls.get(), MAX_RETRIES + 1);
+
+        // Un 404 no se reintenta
+        let calls = Cell::new(0);
+        let result: Result<(), AppError> = retry_with_backoff(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err(FetchError::Fatal(AppError::Network("GitHub API error: 404".to_string()))) }
+            },
+            |_| async {},
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_check_interval() {
+        let hour = 3600;
+        let now = 1_700_000_000;
+
+        assert!(check_due(None, 24, now));
+        assert!(!check_due(Some(now - 23 * hour), 24, now));
+        assert!(check_due(Some(now - 24 * hour), 24, now));
+        assert!(check_due(Some(now - 2 * hour), 1, now));
+        // Última comprobación "en el futuro" (el reloj ha cambiado)
+        assert!(check_due(Some(now + hour), 24, now));
+
+        let manager = test_manager();
+        assert_eq!(load_last_check(&manager), None);
+        record_check(&manager, now).unwrap();
+        assert_eq!(load_last_check(&manager), Some(now));
+    }
+
+    #[test]
+    fn test_rate_limit_reset() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+        use reqwest::StatusCode;
+
+        let headers = |remaining: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-ratelimit-remaining", HeaderValue::from_static(remaining));
+            headers.insert("x-ratelimit-reset", HeaderValue::from_static("1700000000"));
+            headers
+        };
+
+        assert_eq!(rate_limit_reset(StatusCode::FORBIDDEN, &headers("0")), Some(1_700_000_000));
+        assert_eq!(rate_limit_reset(StatusCode::TOO_MANY_REQUESTS, &headers("0")), Some(1_700_000_000));
+        // Un 403 con peticiones restantes es otro error
+        assert_eq!(rate_limit_reset(StatusCode::FORBIDDEN, &headers("12")), None);
+        assert_eq!(rate_limit_reset(StatusCode::OK, &headers("0")), None);
+        assert_eq!(rate_limit_reset(StatusCode::FORBIDDEN, &HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_update_policy_serialization() {
+        for (policy, json) in [
+            (AutoUpdatePolicy::Off, "\"off\""),
+            (AutoUpdatePolicy::NotifyOnly, "\"notify_only\""),
+            (AutoUpdatePolicy::OpenPage, "\"open_page\""),
+        ] {
+            assert_eq!(serde_json::to_string(&policy).unwrap(), json);
+            assert_eq!(serde_json::from_str::<AutoUpdatePolicy>(json).unwrap(), policy);
+        }
+        assert!(serde_json::from_str::<AutoUpdatePolicy>("\"always\"").is_err());
+    }
+
+    #[test]
+    fn test_update_policy_decisions() {
+        assert!(!AutoUpdatePolicy::Off.checks_on_launch());
+        assert!(AutoUpdatePolicy::NotifyOnly.checks_on_launch());
+        assert!(AutoUpdatePolicy::OpenPage.checks_on_launch());
+
+        assert_eq!(AutoUpdatePolicy::NotifyOnly.with_launch_check(false), AutoUpdatePolicy::Off);
+        assert_eq!(AutoUpdatePolicy::Off.with_launch_check(true), AutoUpdatePolicy::OpenPage);
+        assert_eq!(AutoUpdatePolicy::NotifyOnly.with_launch_check(true), AutoUpdatePolicy::NotifyOnly);
+        assert_eq!(AutoUpdatePolicy::Off.with_launch_check(false), AutoUpdatePolicy::Off);
+
+    }
+
+    #[test]
+    fn test_update_policy_persistence() {
+        let manager = test_manager();
+        // Sin guardar se mantiene el comportamiento anterior
+        assert_eq!(load_update_policy(&manager), AutoUpdatePolicy::OpenPage);
+
+        save_update_policy(&manager, AutoUpdatePolicy::Off).unwrap();
+        assert_eq!(load_update_policy(&manager), AutoUpdatePolicy::Off);
+    }
+
+    fn update_info(latest: &str) -> UpdateInfo {
+        let release = serde_json::json!({ "tag_name": latest });
+        UpdateInfo::from_release(&release, "0.3.0", UpdateChannel::Stable).unwrap()
+    }
+
+    #[test]
+    fn test_skipped_version_is_not_notified() {
+        let manager = test_manager();
+        let info = update_info("v0.4.0");
+        assert!(should_notify(&manager, &info, 0));
+
+        assert_eq!(skip_version(&manager, "v0.4.0").unwrap(), "0.4.0");
+        assert!(!should_notify(&manager, &info, 0));
+        assert!(!should_notify(&manager, &info, 0));
+        assert_eq!(load_skipped_version(&manager).as_deref(), Some("0.4.0"));
+
+        assert!(skip_version(&manager, "latest").is_err());
+        assert!(!should_notify(&manager, &update_info("v0.3.0"), 0));
+    }
+
+    #[test]
+    fn test_offered_update() {
+        let info = update_info("1.2.0");
+        assert_eq!(offered_update(&info, None), Some(info.clone()));
+        assert_eq!(offered_update(&info, Some("1.2.0")), None);
+        assert_eq!(offered_update(&info, Some("1.1.0")), Some(info.clone()));
+
+        let current = UpdateInfo {
+            update_available: false,
+            ..info
+        };
+        assert_eq!(offered_update(&current, None), None);
+
+        // Solo se reconstruye el menú si cambia la versión ofrecida
+        let state = AvailableUpdate::default();
+        assert!(!state.replace(None));
+        assert!(state.replace(Some(update_info("1.2.0"))));
+        assert!(!state.replace(Some(update_info("1.2.0"))));
+        assert!(state.replace(Some(update_info("1.3.0"))));
+        assert_eq!(state.get().unwrap().latest, "1.3.0");
+        assert!(state.replace(None));
+    }
+
+    #[test]
+    fn test_newer_than_skipped_clears_skip() {
+        let manager = test_manager();
+        skip_version(&manager, "0.4.0").unwrap();
+
+        assert!(should_notify(&manager, &update_info("v0.4.1"), 0));
+        assert_eq!(load_skipped_version(&manager), None);
+        assert!(should_notify(&manager, &update_info("v0.4.0"), 0));
+    }
+
+    #[test]
+    fn test_snooze_expires() {
+        let manager = test_manager();
+        let info = update_info("v0.4.0");
+        let now = 1_700_000_000;
+
+        assert_eq!(snooze(&manager, 24, now).unwrap(), now + 24 * 3600);
+        assert!(!should_notify(&manager, &info, now));
+        assert!(!should_notify(&manager, &info, now + 24 * 3600 - 1));
+        assert!(should_notify(&manager, &info, now + 24 * 3600));
+
+        assert!(snooze(&manager, 0, now).is_err());
+        assert!(snooze(&manager, MAX_SNOOZE_HOURS + 1, now).is_err());
+        assert_eq!(load_snoozed_until(&manager), Some(now + 24 * 3600));
+    }
+}
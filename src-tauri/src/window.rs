@@ -0,0 +1,551 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Gestión de ventanas: ventana principal, ventanas adicionales por cuenta y
+// ventanas auxiliares (incógnito y gestor de cuentas)
+//
+// Las ventanas de X usan el user-agent de las preferencias (`user_agent` en
+// settings.json) si hay uno; el gestor de cuentas es local y no lo necesita.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::accounts::{AccountInfo, AccountsManager};
+use crate::window_state;
+
+/// Label de la ventana principal (definida en tauri.conf.json)
+pub const MAIN_WINDOW: &str = "main";
+/// Prefijo de los labels de ventanas adicionales de cuenta
+pub const ACCOUNT_WINDOW_PREFIX: &str = "account-";
+/// Página que se abre en las ventanas de cuenta
+const ACCOUNT_WINDOW_URL: &str = "https://x.com/home";
+/// Label de la ventana de incógnito (solo hay una)
+pub const INCOGNITO_WINDOW: &str = "incognito";
+/// Página que se abre en la ventana de incógnito (no requiere sesión)
+const INCOGNITO_WINDOW_URL: &str = "https://x.com/explore";
+/// Label de la ventana del gestor de cuentas
+pub const ACCOUNTS_MANAGER_WINDOW: &str = "accounts-manager";
+/// Página local (en `frontendDist`) del gestor de cuentas
+const ACCOUNTS_MANAGER_PAGE: &str = "accounts-manager.html";
+/// Longitud máxima (en caracteres, sin la @) del handle en el icono del Dock
+#[cfg(any(test, target_os = "macos"))]
+const DOCK_BADGE_MAX_CHARS: usize = 8;
+/// Número máximo de notificaciones que se muestra tal cual en el Dock
+#[cfg(any(test, target_os = "macos"))]
+const MAX_BADGE_COUNT: u32 = 99;
+
+/// Notificaciones pendientes en X (las que informa la ventana principal)
+#[derive(Default)]
+pub struct BadgeCount(AtomicU32);
+
+/// Deriva el label de la ventana de una cuenta a partir de su username
+///
+/// Tauri solo admite alfanuméricos, `-`, `/`, `:` y `_` en los labels; el
+/// resto de caracteres se sustituyen por `_`.
+pub fn account_window_label(username: &str) -> String {
+    let sanitized: String = username
+        .trim_start_matches('@')
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+
+    format!("{}{}", ACCOUNT_WINDOW_PREFIX, sanitized)
+}
+
+/// Ventana sobre la que actúan los menús: la que tiene el foco o, si ninguna
+/// lo tiene, la principal
+///
+/// El gestor de cuentas no muestra X, así que las acciones sobre la página
+/// (navegar, zoom, buscar...) nunca se aplican a él.
+pub fn focused_or_main<R: Runtime>(app: &AppHandle<R>) -> Option<WebviewWindow<R>> {
+    focused_matching(app, |label| !is_accounts_manager_window(label))
+}
+
+/// Ventana con foco (de cualquier tipo) o, si ninguna lo tiene, la principal
+pub fn focused_window<R: Runtime>(app: &AppHandle<R>) -> Option<WebviewWindow<R>> {
+    focused_matching(app, |_| true)
+}
+
+/// Ventana con foco entre las que cumplen `filter` o, si ninguna lo tiene, la principal
+fn focused_matching<R: Runtime>(app: &AppHandle<R>, filter: impl Fn(&str) -> bool) -> Option<WebviewWindow<R>> {
+    pick_focused_or_main(
+        app.webview_windows()
+            .into_values()
+            .filter(|window| filter(window.label()))
+            .map(|window| (window.is_focused().unwrap_or(false), window)),
+        || app.get_webview_window(MAIN_WINDOW),
+    )
+}
+
+/// Indica si `label` es la ventana de incógnito
+///
+/// Esa ventana no tiene sesión de ninguna cuenta, así que queda fuera de todo
+/// lo que lee o cambia la sesión de la cuenta activa.
+pub fn is_incognito_window(label: &str) -> bool {
+    label == INCOGNITO_WINDOW
+}
+
+/// Indica si `label` es la ventana del gestor de cuentas
+pub fn is_accounts_manager_window(label: &str) -> bool {
+    label == ACCOUNTS_MANAGER_WINDOW
+}
+
+/// Indica si `label` es una ventana con la sesión de la cuenta activa (la
+/// principal o una ventana de cuenta)
+pub fn is_account_window(label: &str) -> bool {
+    label == MAIN_WINDOW || label.starts_with(ACCOUNT_WINDOW_PREFIX)
+}
+
+/// Título de la ventana principal: "X – @usuario (Nombre)" con la cuenta
+/// activa (sin el nombre si no se conoce), o solo "X" si no hay ninguna
+pub fn main_window_title(active: Option<&AccountInfo>) -> String {
+    let Some(account) = active else {
+        return "X".to_string();
+    };
+
+    match account.display_name.as_deref().filter(|name| !name.is_empty()) {
+        Some(name) => format!("X – @{} ({})", account.username, name),
+        None => format!("X – @{}", account.username),
+    }
+}
+
+/// Texto del badge del icono del Dock: el handle, recortado para que quepa
+#[cfg(any(test, target_os = "macos"))]
+pub fn dock_badge_label(active: Option<&AccountInfo>) -> Option<String> {
+    let username = &active?.username;
+
+    if username.chars().count() <= DOCK_BADGE_MAX_CHARS {
+        return Some(format!("@{}", username));
+    }
+
+    let short: String = username.chars().take(DOCK_BADGE_MAX_CHARS - 1).collect();
+    Some(format!("@{}…", short))
+}
+
+/// Texto del badge con `count` notificaciones (`99+` a partir de 100;
+/// ninguno con 0)
+#[cfg(any(test, target_os = "macos"))]
+pub fn badge_count_label(count: u32) -> Option<String> {
+    match count {
+        0 => None,
+        1..=MAX_BADGE_COUNT => Some(count.to_string()),
+        _ => Some(format!("{}+", MAX_BADGE_COUNT)),
+    }
+}
+
+/// Badge del Dock: las notificaciones pendientes si hay alguna y, si no, la
+/// cuenta activa
+#[cfg(any(test, target_os = "macos"))]
+fn dock_badge(count: u32, active: Option<&AccountInfo>) -> Option<String> {
+    badge_count_label(count).or_else(|| dock_badge_label(active))
+}
+
+/// Guarda las notificaciones pendientes y actualiza el badge del Dock
+pub fn set_badge_count<R: Runtime>(app: &AppHandle<R>, count: u32) {
+    if let Some(badge) = app.try_state::<BadgeCount>() {
+        badge.0.store(count, Ordering::Relaxed);
+    }
+
+    update_window_identity(app);
+}
+
+/// Muestra la cuenta activa en el título de la ventana principal y, en
+/// macOS, en el badge del icono del Dock (o las notificaciones pendientes,
+/// si hay)
+///
+/// No hace nada si la ventana principal ya no existe o las cuentas aún no
+/// están disponibles.
+pub fn update_window_identity<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+
+    let active = app.try_state::<AccountsManager>().and_then(|manager| {
+        let username = manager.get_active_account().ok()??;
+        manager.list_accounts(true).ok()?.into_iter().find(|a| a.username == username)
+    });
+
+    if let Err(e) = window.set_title(&main_window_title(active.as_ref())) {
+        tracing::warn!("Failed to update main window title: {}", e);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let count = app.try_state::<BadgeCount>().map_or(0, |badge| badge.0.load(Ordering::Relaxed));
+        if let Err(e) = window.set_badge_label(dock_badge(count, active.as_ref())) {
+            tracing::warn!("Failed to update Dock badge: {}", e);
+        }
+    }
+}
+
+/// Como `focused_or_main`, pero solo entre las ventanas con la sesión de la
+/// cuenta activa (sin incógnito ni gestor de cuentas)
+///
+/// Para las acciones que afectan a las cuentas (capturar la sesión, pedir
+/// confirmaciones al frontend...).
+pub fn focused_account_window<R: Runtime>(app: &AppHandle<R>) -> Option<WebviewWindow<R>> {
+    focused_matching(app, is_account_window)
+}
+
+/// Primera ventana con foco o, si no hay ninguna, la que devuelva `main`
+fn pick_focused_or_main<W>(
+    windows: impl IntoIterator<Item = (bool, W)>,
+    main: impl FnOnce() -> Option<W>,
+) -> Option<W> {
+    windows
+        .into_iter()
+        .find_map(|(focused, window)| focused.then_some(window))
+        .or_else(main)
+}
+
+/// Qué hacer con la ventana principal al pulsar el atajo global
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleAction {
+    /// Está al frente: ocultarla
+    Hide,
+    /// Existe pero está oculta, minimizada o detrás de otra: traerla al frente
+    Show,
+    /// Se cerró: volver a crearla
+    Recreate,
+}
+
+/// Decide la acción del atajo según el estado de la ventana principal
+/// (`None` si no existe)
+pub fn toggle_action(state: Option<(bool, bool, bool)>) -> ToggleAction {
+    match state {
+        None => ToggleAction::Recreate,
+        Some((visible, focused, minimized)) if visible && focused && !minimized => ToggleAction::Hide,
+        Some(_) => ToggleAction::Show,
+    }
+}
+
+/// User-agent de las ventanas de X elegido en las preferencias, si lo hay
+///
+/// Solo se aplica al crear la ventana: las que ya están abiertas mantienen el
+/// suyo hasta que se cierran y se vuelven a abrir.
+fn configured_user_agent<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.try_state::<AccountsManager>()?.settings().user_agent
+}
+
+/// Restaura el estado guardado de una ventana recién creada (todavía oculta),
+/// empieza a seguir sus cambios y la muestra
+fn show_with_saved_state<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    if let Err(e) = window_state::restore(window) {
+        tracing::warn!("Failed to restore state of window {}: {}", window.label(), e);
+    }
+    window_state::track(window);
+
+    window.show().map_err(|e| format!("Failed to show window: {}", e))
+}
+
+/// Crea la ventana principal con su configuración de tauri.conf.json
+///
+/// Allí lleva `create: false` para que no la cree Tauri al arrancar: se crea
+/// aquí para poder aplicar el user-agent de las preferencias, y oculta, para
+/// restaurar su tamaño y posición antes de mostrarla.
+pub fn build_main_window<R: Runtime>(app: &AppHandle<R>) -> Result<WebviewWindow<R>, String> {
+    let mut config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == MAIN_WINDOW)
+        .ok_or("Main window is not configured")?
+        .clone();
+    if let Some(user_agent) = configured_user_agent(app) {
+        config.user_agent = Some(user_agent);
+    }
+    config.visible = false;
+
+    let window = WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Failed to create main window: {}", e))?;
+    show_with_saved_state(&window)?;
+
+    Ok(window)
+}
+
+/// Devuelve la ventana principal, creándola de nuevo si se cerró
+///
+/// La ventana recreada recupera su estado, el zoom y el título de la cuenta
+/// activa, igual que al arrancar.
+pub fn main_window_or_recreate<R: Runtime>(app: &AppHandle<R>) -> Result<WebviewWindow<R>, String> {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        return Ok(window);
+    }
+
+    let window = build_main_window(app)?;
+
+    let manager = app.state::<AccountsManager>();
+    if let Err(e) = crate::zoom::set_window_zoom(app, &window, crate::zoom::load_zoom(&manager)) {
+        tracing::warn!("Failed to restore zoom: {}", e);
+    }
+    update_window_identity(app);
+
+    tracing::info!("Recreated main window");
+
+    Ok(window)
+}
+
+/// Oculta la ventana principal si está al frente; si no, la muestra y la
+/// enfoca (recreándola si se cerró)
+pub fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let existing = app.get_webview_window(MAIN_WINDOW);
+    let state = existing.as_ref().map(|window| {
+        (
+            window.is_visible().unwrap_or(false),
+            window.is_focused().unwrap_or(false),
+            window.is_minimized().unwrap_or(false),
+        )
+    });
+
+    let window = match (toggle_action(state), existing) {
+        (ToggleAction::Hide, Some(window)) => return window.hide().map_err(|e| e.to_string()),
+        (_, Some(window)) => window,
+        (_, None) => main_window_or_recreate(app)?,
+    };
+
+    window.unminimize().map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Abre (o enfoca, si ya existe) una ventana adicional para `username`
+///
+/// Todas las ventanas comparten el almacén de cookies, así que la ventana
+/// nueva muestra la sesión de la cuenta activa. Si la cuenta tiene un proxy
+/// configurado, solo esta ventana navega a través de él: la principal, con la
+/// misma sesión, sigue conectándose directamente.
+///
+/// # Returns
+/// Label de la ventana
+pub fn open_account_window<R: Runtime>(app: &AppHandle<R>, username: &str) -> Result<String, String> {
+    let label = account_window_label(username);
+
+    if let Some(window) = app.get_webview_window(&label) {
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(label);
+    }
+
+    let url = ACCOUNT_WINDOW_URL.parse().map_err(|e| format!("Invalid URL: {}", e))?;
+    let proxy = app.state::<AccountsManager>().account_proxy(username)?;
+
+    let mut builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::External(url))
+        .title(format!("@{}", username))
+        .inner_size(1280.0, 900.0)
+        .min_inner_size(800.0, 600.0);
+
+    if let Some(proxy) = &proxy {
+        builder = builder.proxy_url(proxy.clone());
+    }
+    if let Some(user_agent) = configured_user_agent(app) {
+        builder = builder.user_agent(&user_agent);
+    }
+
+    builder.build().map_err(|e| format!("Failed to open window: {}", e))?;
+
+    tracing::info!(
+        "Opened account window {} for {}{}",
+        label,
+        username,
+        if proxy.is_some() { " (proxied)" } else { "" }
+    );
+
+    Ok(label)
+}
+
+/// Abre (o enfoca, si ya existe) la ventana de incógnito
+///
+/// Usa un almacén de datos efímero: no ve las cookies de las cuentas y todo lo
+/// que guarde desaparece al cerrarla. No tiene acceso a los comandos de la
+/// aplicación (no está en `capabilities/default.json`).
+///
+/// # Returns
+/// Label de la ventana
+pub fn open_incognito_window<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    if let Some(window) = app.get_webview_window(INCOGNITO_WINDOW) {
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(INCOGNITO_WINDOW.to_string());
+    }
+
+    let url = INCOGNITO_WINDOW_URL.parse().map_err(|e| format!("Invalid URL: {}", e))?;
+
+    let mut builder = WebviewWindowBuilder::new(app, INCOGNITO_WINDOW, WebviewUrl::External(url))
+        .title("X (incógnito)")
+        .inner_size(1280.0, 900.0)
+        .min_inner_size(800.0, 600.0)
+        .incognito(true);
+    if let Some(user_agent) = configured_user_agent(app) {
+        builder = builder.user_agent(&user_agent);
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("Failed to open incognito window: {}", e))?;
+
+    tracing::info!("Opened incognito window");
+
+    Ok(INCOGNITO_WINDOW.to_string())
+}
+
+/// Abre (o enfoca, si ya existe) el gestor de cuentas
+///
+/// Es una página local que usa los mismos comandos que el resto de ventanas,
+/// así que opera sobre el mismo `AccountsManager`; se actualiza con el evento
+/// `accounts-changed`. Recuerda su tamaño y posición como la ventana principal.
+///
+/// # Returns
+/// Label de la ventana
+pub fn open_accounts_manager<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    if let Some(window) = app.get_webview_window(ACCOUNTS_MANAGER_WINDOW) {
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(ACCOUNTS_MANAGER_WINDOW.to_string());
+    }
+
+    let window = WebviewWindowBuilder::new(app, ACCOUNTS_MANAGER_WINDOW, WebviewUrl::App(ACCOUNTS_MANAGER_PAGE.into()))
+        .title("Gestionar cuentas")
+        .inner_size(720.0, 560.0)
+        .min_inner_size(520.0, 360.0)
+        .resizable(true)
+        .visible(false)
+        .build()
+        .map_err(|e| format!("Failed to open accounts manager: {}", e))?;
+    show_with_saved_state(&window)?;
+
+    tracing::info!("Opened accounts manager");
+
+    Ok(ACCOUNTS_MANAGER_WINDOW.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_window_label() {
+        assert_eq!(account_window_label("alice"), "account-alice");
+        assert_eq!(account_window_label("@Alice_99"), "account-alice_99");
+        assert_eq!(account_window_label("bad name.x"), "account-bad_name_x");
+    }
+
+    fn account(username: &str) -> AccountInfo {
+        AccountInfo {
+            username: username.to_string(),
+            uuid: format!("uuid-{}", username),
+            created_at: 0,
+            last_used: 0,
+            last_login: 0,
+            display_name: None,
+            avatar_url: None,
+            order: 0,
+            needs_relogin: false,
+            pinned: false,
+            archived: false,
+            label: None,
+            color: None,
+            proxy_url: None,
+            session_valid: None,
+            last_validated: 0,
+        }
+    }
+
+    #[test]
+    fn test_main_window_title() {
+        let mut account = account("alice");
+        assert_eq!(main_window_title(Some(&account)), "X – @alice");
+
+        account.display_name = Some(String::new());
+        assert_eq!(main_window_title(Some(&account)), "X – @alice");
+
+        account.display_name = Some("Alice & Co".to_string());
+        assert_eq!(main_window_title(Some(&account)), "X – @alice (Alice & Co)");
+
+        assert_eq!(main_window_title(None), "X");
+    }
+
+    #[test]
+    fn test_dock_badge_label() {
+        let mut account = account("alice");
+        assert_eq!(dock_badge_label(Some(&account)).as_deref(), Some("@alice"));
+
+        account.username = "alice_in_x".to_string();
+        assert_eq!(dock_badge_label(Some(&account)).as_deref(), Some("@alice_i…"));
+
+        assert_eq!(dock_badge_label(None), None);
+    }
+
+    #[test]
+    fn test_badge_count_label() {
+        assert_eq!(badge_count_label(0), None);
+        assert_eq!(badge_count_label(1).as_deref(), Some("1"));
+        assert_eq!(badge_count_label(99).as_deref(), Some("99"));
+        assert_eq!(badge_count_label(100).as_deref(), Some("99+"));
+        assert_eq!(badge_count_label(u32::MAX).as_deref(), Some("99+"));
+    }
+
+    #[test]
+    fn test_dock_badge_prefers_notifications() {
+        let account = account("alice");
+
+        assert_eq!(dock_badge(3, Some(&account)).as_deref(), Some("3"));
+        // Sin notificaciones vuelve a la cuenta activa, o a ningún badge
+        assert_eq!(dock_badge(0, Some(&account)).as_deref(), Some("@alice"));
+        assert_eq!(dock_badge(0, None), None);
+    }
+
+    #[test]
+    fn test_focus_falls_back_to_main() {
+        // Sin ninguna ventana con foco (p. ej. la app en segundo plano)
+        let unfocused = vec![(false, "account-alice"), (false, "main")];
+        assert_eq!(pick_focused_or_main(unfocused, || Some(MAIN_WINDOW)), Some(MAIN_WINDOW));
+        assert_eq!(pick_focused_or_main(Vec::<(bool, &str)>::new(), || Some(MAIN_WINDOW)), Some(MAIN_WINDOW));
+        assert_eq!(pick_focused_or_main(Vec::<(bool, &str)>::new(), || None), None);
+
+        let focused = vec![(false, "main"), (true, "account-alice")];
+        assert_eq!(pick_focused_or_main(focused, || Some(MAIN_WINDOW)), Some("account-alice"));
+    }
+
+    #[test]
+    fn test_incognito_window_detection() {
+        assert!(is_incognito_window(INCOGNITO_WINDOW));
+        assert!(!is_incognito_window(MAIN_WINDOW));
+        assert!(!is_incognito_window(&account_window_label("alice")));
+        // Una cuenta llamada "incognito" tiene su propia ventana de cuenta
+        assert!(!is_incognito_window(&account_window_label("incognito")));
+    }
+
+    #[test]
+    fn test_account_windows() {
+        assert!(is_account_window(MAIN_WINDOW));
+        assert!(is_account_window(&account_window_label("alice")));
+        assert!(!is_account_window(INCOGNITO_WINDOW));
+        // Una cuenta llamada "incognito" tiene su propia ventana de cuenta
+        assert!(is_account_window(&account_window_label("incognito")));
+        assert!(!is_account_window(ACCOUNTS_MANAGER_WINDOW));
+        assert!(is_accounts_manager_window(ACCOUNTS_MANAGER_WINDOW));
+        // La ventana de la cuenta @manager no es el gestor
+        assert!(!is_accounts_manager_window(&account_window_label("manager")));
+    }
+
+    #[test]
+    fn test_account_window_label_is_stable() {
+        // Mismo username con distinta capitalización abre la misma ventana
+        assert_eq!(account_window_label("Bob"), account_window_label("bob"));
+        assert!(account_window_label("bob").starts_with(ACCOUNT_WINDOW_PREFIX));
+    }
+
+    #[test]
+    fn test_toggle_action() {
+        // (visible, enfocada, minimizada)
+        assert_eq!(toggle_action(Some((true, true, false))), ToggleAction::Hide);
+        assert_eq!(toggle_action(Some((true, false, false))), ToggleAction::Show);
+        assert_eq!(toggle_action(Some((false, false, false))), ToggleAction::Show);
+        assert_eq!(toggle_action(Some((true, true, true))), ToggleAction::Show);
+        assert_eq!(toggle_action(None), ToggleAction::Recreate);
+    }
+}
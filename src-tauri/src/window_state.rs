@@ -0,0 +1,538 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Tamaño, posición y estado (maximizada, pantalla completa) de las ventanas
+//
+// Se guarda en `window_state.json`, en el directorio de datos de la app
+// (Application Support), con una entrada por label de ventana. Al crear una
+// ventana se restaura su estado antes de mostrarla, así que no se ve saltar
+// desde la posición por defecto. Si la pantalla en la que estaba ya no existe,
+// se recoloca dentro del área visible de la más cercana.
+//
+// Las versiones anteriores guardaban solo el tamaño y la posición, cifrados en
+// el Keychain; `migrate_legacy` los pasa al archivo la primera vez.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, WindowEvent};
+
+use crate::accounts::{derive_master_key, load_key_secret};
+use crate::hash_key;
+use crate::kdf;
+use crate::menu::debounce::Debouncer;
+use crate::secrets::SecretStore;
+use crate::window::{ACCOUNTS_MANAGER_WINDOW, MAIN_WINDOW};
+
+/// Archivo (en el directorio de datos) con el estado de las ventanas
+pub const WINDOW_STATE_FILE: &str = "window_state.json";
+/// Entrada del Keychain donde versiones anteriores guardaban la geometría de
+/// la ventana principal; la del resto lleva su label como sufijo (ver
+/// `legacy_geometry_key`)
+pub(crate) const LEGACY_GEOMETRY_KEY: &str = "window_geometry";
+/// Espera tras el último movimiento/redimensionado antes de guardar
+const STATE_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Porción mínima (en píxeles) de la barra de título que debe quedar dentro
+/// de un monitor para mantener la posición tal cual
+const MIN_VISIBLE_WIDTH: i32 = 100;
+const MIN_VISIBLE_HEIGHT: i32 = 40;
+
+/// Estado de una ventana
+///
+/// El tamaño y la posición (píxeles físicos) son los de la ventana "normal":
+/// mientras está maximizada o a pantalla completa se conservan los de antes,
+/// para volver a ellos al salir de ese modo.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+/// Área visible de un monitor, sin barra de menús ni Dock (píxeles físicos)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorArea {
+    /// Distancia (al cuadrado) de un punto al área; 0 si está dentro
+    fn distance_to(&self, x: i32, y: i32) -> i64 {
+        let dx = (self.x - x).max(x - (self.x + self.width as i32)).max(0) as i64;
+        let dy = (self.y - y).max(y - (self.y + self.height as i32)).max(0) as i64;
+        dx * dx + dy * dy
+    }
+}
+
+impl WindowState {
+    /// Indica si la barra de título queda lo bastante dentro de algún monitor
+    /// como para poder arrastrar la ventana
+    pub fn is_visible_on(&self, monitors: &[MonitorArea]) -> bool {
+        let title_right = self.x + (self.width as i32).min(MIN_VISIBLE_WIDTH * 2);
+        let title_bottom = self.y + MIN_VISIBLE_HEIGHT;
+
+        monitors.iter().any(|monitor| {
+            let overlap_x = title_right.min(monitor.x + monitor.width as i32) - self.x.max(monitor.x);
+            let overlap_y = title_bottom.min(monitor.y + monitor.height as i32) - self.y.max(monitor.y);
+
+            overlap_x >= MIN_VISIBLE_WIDTH && overlap_y >= MIN_VISIBLE_HEIGHT
+        })
+    }
+
+    /// Estado ajustado a los monitores conectados
+    ///
+    /// Si la ventana se puede arrastrar desde algún monitor se deja como está
+    /// (puede estar entre dos pantallas); si no, se mete entera en el área
+    /// visible del monitor más cercano a su centro, reduciéndola si no cabe.
+    /// `None` si no hay ningún monitor.
+    pub fn fit_to(&self, monitors: &[MonitorArea]) -> Option<Self> {
+        if self.is_visible_on(monitors) {
+            return Some(*self);
+        }
+
+        let center_x = self.x + (self.width / 2) as i32;
+        let center_y = self.y + (self.height / 2) as i32;
+        let monitor = monitors.iter().min_by_key(|monitor| monitor.distance_to(center_x, center_y))?;
+
+        let width = self.width.min(monitor.width);
+        let height = self.height.min(monitor.height);
+
+        Some(Self {
+            x: self.x.clamp(monitor.x, monitor.x + (monitor.width - width) as i32),
+            y: self.y.clamp(monitor.y, monitor.y + (monitor.height - height) as i32),
+            width,
+            height,
+            ..*self
+        })
+    }
+}
+
+/// Estado guardado de todas las ventanas (estado de Tauri)
+///
+/// Cada cambio se escribe enseguida en el archivo; sin ruta (no se pudo
+/// resolver el directorio de datos) solo dura mientras la app está abierta.
+pub struct WindowStates {
+    path: Option<PathBuf>,
+    states: Mutex<BTreeMap<String, WindowState>>,
+}
+
+/// Ruta de `WINDOW_STATE_FILE` para `app`, si se puede resolver su directorio
+/// de datos
+pub fn state_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    match app.path().app_data_dir() {
+        Ok(dir) => Some(dir.join(WINDOW_STATE_FILE)),
+        Err(e) => {
+            tracing::warn!("Cannot resolve the data directory, window state will not be saved: {}", e);
+            None
+        }
+    }
+}
+
+/// Lee el estado de las ventanas de `path`; sin archivo, o si no es válido,
+/// no hay ninguno
+fn load_states(path: &Path) -> BTreeMap<String, WindowState> {
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return BTreeMap::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read {}: {}", path.display(), e);
+            return BTreeMap::new();
+        }
+    };
+
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid window state {}: {}", path.display(), e);
+        BTreeMap::new()
+    })
+}
+
+impl WindowStates {
+    /// Abre el estado guardado en `path`
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let states = path.as_deref().map(load_states).unwrap_or_default();
+
+        Self {
+            path,
+            states: Mutex::new(states),
+        }
+    }
+
+    /// Estado guardado de la ventana `label`
+    pub fn get(&self, label: &str) -> Option<WindowState> {
+        self.states.lock().unwrap().get(label).copied()
+    }
+
+    /// Guarda el estado de la ventana `label`
+    pub fn set(&self, label: &str, state: WindowState) -> Result<(), String> {
+        let mut states = self.states.lock().unwrap();
+        states.insert(label.to_string(), state);
+        self.write(&states)
+    }
+
+    /// Olvida el estado de todas las ventanas y borra el archivo
+    pub fn clear(&self) -> Result<(), String> {
+        self.states.lock().unwrap().clear();
+
+        match self.path.as_deref().map(std::fs::remove_file) {
+            Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove window state: {}", e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Escribe `states` en el archivo (temporal y renombrado, como `settings`)
+    fn write(&self, states: &BTreeMap<String, WindowState>) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        let data = serde_json::to_vec_pretty(states).map_err(|e| format!("Failed to serialize window state: {}", e))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, data).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Entrada (sin hashear) del Keychain con la geometría antigua de `label`
+pub(crate) fn legacy_geometry_key(label: &str) -> String {
+    if label == MAIN_WINDOW {
+        LEGACY_GEOMETRY_KEY.to_string()
+    } else {
+        format!("{}:{}", LEGACY_GEOMETRY_KEY, label)
+    }
+}
+
+/// Geometría guardada en el Keychain por versiones anteriores (sin los flags
+/// de maximizada/pantalla completa, que no se guardaban)
+#[derive(Deserialize)]
+struct LegacyGeometry {
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+}
+
+/// Lee y borra la geometría antigua de `label`; `None` si no hay o no se
+/// puede descifrar
+fn take_legacy_geometry(store: &dyn SecretStore, label: &str) -> Option<WindowState> {
+    let key = hash_key(&legacy_geometry_key(label));
+    let encrypted = store.get(&key)?;
+    store.delete(&key);
+
+    let secret = load_key_secret(store);
+    let json = kdf::decrypt_with(std::str::from_utf8(&encrypted).ok()?, |params| {
+        derive_master_key(secret.as_deref(), params)
+    })
+    .ok()?;
+    let legacy: LegacyGeometry = serde_json::from_str(&json).ok()?;
+
+    Some(WindowState {
+        x: legacy.x,
+        y: legacy.y,
+        width: legacy.width,
+        height: legacy.height,
+        maximized: false,
+        fullscreen: false,
+    })
+}
+
+/// Pasa la geometría guardada en el Keychain por versiones anteriores a
+/// `states` (si la ventana no tiene ya un estado) y la borra del Keychain
+pub fn migrate_legacy(store: &dyn SecretStore, states: &WindowStates) {
+    for label in [MAIN_WINDOW, ACCOUNTS_MANAGER_WINDOW] {
+        let Some(legacy) = take_legacy_geometry(store, label) else {
+            continue;
+        };
+
+        if states.get(label).is_none() {
+            match states.set(label, legacy) {
+                Ok(()) => tracing::info!("Migrated window geometry of {} from the Keychain", label),
+                Err(e) => tracing::warn!("Failed to migrate window geometry: {}", e),
+            }
+        }
+    }
+}
+
+/// Áreas visibles de los monitores conectados
+fn monitor_areas<R: Runtime>(window: &WebviewWindow<R>) -> Result<Vec<MonitorArea>, String> {
+    Ok(window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?
+        .iter()
+        .map(|monitor| {
+            let area = monitor.work_area();
+            MonitorArea {
+                x: area.position.x,
+                y: area.position.y,
+                width: area.size.width,
+                height: area.size.height,
+            }
+        })
+        .collect())
+}
+
+/// Restaura el estado guardado de la ventana
+///
+/// Hay que llamarla antes de mostrarla. Si no hay ningún monitor del que
+/// fiarse solo se restaura el tamaño.
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    let Some(saved) = window
+        .try_state::<WindowStates>()
+        .and_then(|states| states.get(window.label()))
+    else {
+        return Ok(());
+    };
+
+    let state = match saved.fit_to(&monitor_areas(window)?) {
+        Some(state) => {
+            if state != saved {
+                tracing::warn!("Saved window position is off-screen, moving it to the nearest display");
+            }
+            window
+                .set_position(PhysicalPosition::new(state.x, state.y))
+                .map_err(|e| format!("Failed to set window position: {}", e))?;
+            state
+        }
+        None => saved,
+    };
+
+    window
+        .set_size(PhysicalSize::new(state.width, state.height))
+        .map_err(|e| format!("Failed to set window size: {}", e))?;
+
+    if state.fullscreen {
+        window
+            .set_fullscreen(true)
+            .map_err(|e| format!("Failed to restore fullscreen: {}", e))?;
+    } else if state.maximized {
+        window.maximize().map_err(|e| format!("Failed to maximize window: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Estado actual de la ventana, conservando el tamaño y la posición de
+/// `previous` si está maximizada o a pantalla completa; `None` si está
+/// minimizada
+fn capture<R: Runtime>(window: &WebviewWindow<R>, previous: Option<WindowState>) -> Result<Option<WindowState>, String> {
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+
+    if let Some(previous) = previous.filter(|_| maximized || fullscreen) {
+        return Ok(Some(WindowState {
+            maximized,
+            fullscreen,
+            ..previous
+        }));
+    }
+
+    let size = window.outer_size().map_err(|e| format!("Failed to read window size: {}", e))?;
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to read window position: {}", e))?;
+
+    Ok(Some(WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized,
+        fullscreen,
+    }))
+}
+
+/// Guarda el estado de la ventana al moverla, redimensionarla o cerrarla
+pub fn track<R: Runtime>(window: &WebviewWindow<R>) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    let debouncer = Debouncer::new(STATE_SAVE_DEBOUNCE);
+
+    let save = move |app: &AppHandle<R>, label: &str| {
+        let (Some(window), Some(states)) = (app.get_webview_window(label), app.try_state::<WindowStates>()) else {
+            return;
+        };
+
+        match capture(&window, states.get(label)) {
+            Ok(Some(state)) => {
+                if let Err(e) = states.set(label, state) {
+                    tracing::warn!("Failed to save window state: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read window state: {}", e),
+        }
+    };
+
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            let label = label.clone();
+            debouncer.schedule(&app, move |app| save(app, &label));
+        }
+        WindowEvent::CloseRequested { .. } => save(&app, &label),
+        _ => {}
+    });
+}
+
+/// Olvida el estado guardado de todas las ventanas y devuelve la principal a
+/// su tamaño de tauri.conf.json, centrada
+///
+/// El resto de ventanas recuperan su tamaño por defecto la próxima vez que se
+/// abran.
+pub fn reset<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if let Some(states) = app.try_state::<WindowStates>() {
+        states.clear()?;
+    }
+
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return Ok(());
+    };
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == MAIN_WINDOW)
+        .ok_or("Main window is not configured")?;
+
+    window.set_fullscreen(false).map_err(|e| e.to_string())?;
+    window.unmaximize().map_err(|e| e.to_string())?;
+    window
+        .set_size(LogicalSize::new(config.width, config.height))
+        .map_err(|e| format!("Failed to set window size: {}", e))?;
+    window.center().map_err(|e| format!("Failed to center window: {}", e))?;
+
+    tracing::info!("Reset window state");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kdf::KdfParams;
+    use crate::secrets::InMemoryStore;
+
+    const MAIN: MonitorArea = MonitorArea { x: 0, y: 25, width: 2560, height: 1575 };
+    const LEFT: MonitorArea = MonitorArea { x: -1440, y: 0, width: 1440, height: 900 };
+
+    fn state(x: i32, y: i32) -> WindowState {
+        WindowState { x, y, width: 1280, height: 900, maximized: false, fullscreen: false }
+    }
+
+    #[test]
+    fn test_window_visibility() {
+        assert!(state(100, 100).is_visible_on(&[MAIN]));
+        assert!(state(-1400, 25).is_visible_on(&[MAIN, LEFT]));
+
+        // Monitor externo desconectado
+        assert!(!state(-1400, 25).is_visible_on(&[MAIN]));
+        // Barra de título fuera de la pantalla
+        assert!(!state(2540, 100).is_visible_on(&[MAIN]));
+        assert!(!state(100, 1590).is_visible_on(&[MAIN]));
+        assert!(!state(100, 100).is_visible_on(&[]));
+    }
+
+    #[test]
+    fn test_fit_to_monitors() {
+        // Visible: se deja como está, aunque sobresalga
+        assert_eq!(state(2000, 100).fit_to(&[MAIN]), Some(state(2000, 100)));
+        assert_eq!(state(-1400, 25).fit_to(&[MAIN, LEFT]), Some(state(-1400, 25)));
+
+        // Monitor izquierdo desconectado: al borde izquierdo del principal
+        assert_eq!(state(-1400, 25).fit_to(&[MAIN]), Some(state(0, 25)));
+        // Por debajo de la pantalla: se sube hasta que cabe entera
+        assert_eq!(state(100, 1590).fit_to(&[MAIN]), Some(state(100, 700)));
+        // Se mueve al monitor más cercano, reduciéndola si no cabe
+        let fitted = state(-3000, 200).fit_to(&[MAIN, LEFT]).unwrap();
+        assert_eq!((fitted.x, fitted.y, fitted.width, fitted.height), (-1440, 0, 1280, 900));
+        let big = WindowState { width: 3000, height: 2000, ..state(5000, 5000) };
+        let fitted = big.fit_to(&[MAIN]).unwrap();
+        assert_eq!((fitted.x, fitted.y, fitted.width, fitted.height), (0, 25, 2560, 1575));
+
+        // Los flags se conservan
+        let maximized = WindowState { maximized: true, ..state(-1400, 25) };
+        assert!(maximized.fit_to(&[MAIN]).unwrap().maximized);
+
+        assert_eq!(state(100, 100).fit_to(&[]), None);
+    }
+
+    #[test]
+    fn test_state_persistence() {
+        let dir = std::env::temp_dir().join(format!("xmac-window-state-test-{}", std::process::id()));
+        let path = dir.join(WINDOW_STATE_FILE);
+
+        let states = WindowStates::open(Some(path.clone()));
+        assert_eq!(states.get(MAIN_WINDOW), None);
+
+        let main = WindowState { fullscreen: true, ..state(-1440, 25) };
+        let manager = WindowState { width: 720, height: 560, ..state(40, 40) };
+        states.set(MAIN_WINDOW, main).unwrap();
+        states.set(ACCOUNTS_MANAGER_WINDOW, manager).unwrap();
+
+        // Cada ventana tiene su propia entrada
+        let reopened = WindowStates::open(Some(path.clone()));
+        assert_eq!(reopened.get(MAIN_WINDOW), Some(main));
+        assert_eq!(reopened.get(ACCOUNTS_MANAGER_WINDOW), Some(manager));
+
+        // Sin los flags (archivo escrito a mano) no está maximizada
+        std::fs::write(&path, r#"{"main": {"x": 1, "y": 2, "width": 800, "height": 600}}"#).unwrap();
+        let manual = WindowStates::open(Some(path.clone())).get(MAIN_WINDOW);
+        assert_eq!(manual, Some(WindowState { width: 800, height: 600, ..state(1, 2) }));
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(WindowStates::open(Some(path.clone())).get(MAIN_WINDOW), None);
+
+        reopened.clear().unwrap();
+        assert!(!path.exists());
+        assert_eq!(reopened.get(MAIN_WINDOW), None);
+        // Sin archivo que borrar tampoco es un error
+        reopened.clear().unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_legacy_geometry() {
+        let store = InMemoryStore::new();
+        let secret = load_key_secret(&store);
+        let json = r#"{"width":1280,"height":900,"x":-1440,"y":25}"#;
+        let encrypted =
+            kdf::encrypt_with(&KdfParams::configured(), json, |params| derive_master_key(secret.as_deref(), params))
+                .unwrap();
+        store.set(&hash_key(LEGACY_GEOMETRY_KEY), encrypted.as_bytes()).unwrap();
+
+        let states = WindowStates::open(None);
+        migrate_legacy(&store, &states);
+
+        assert_eq!(states.get(MAIN_WINDOW), Some(state(-1440, 25)));
+        assert_eq!(states.get(ACCOUNTS_MANAGER_WINDOW), None);
+        // La entrada del Keychain se borra
+        assert_eq!(store.get(&hash_key(LEGACY_GEOMETRY_KEY)), None);
+
+        assert_eq!(legacy_geometry_key(MAIN_WINDOW), LEGACY_GEOMETRY_KEY);
+        assert_ne!(legacy_geometry_key(ACCOUNTS_MANAGER_WINDOW), LEGACY_GEOMETRY_KEY);
+    }
+}
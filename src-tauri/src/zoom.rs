@@ -0,0 +1,197 @@
+// X - Cliente no oficial de X (Twitter) para macOS
+// Copyright © 2024 686f6c61
+//
+// Author: 686f6c61 (https://github.com/686f6c61)
+// Repository: https://github.com/686f6c61/Xcom-mac-silicon
+//
+// Zoom de las ventanas mediante la API nativa del WebView (`set_zoom`)
+//
+// A diferencia del enfoque anterior (inyectar JS en X.com), el zoom nativo no
+// depende del origen de la página. Cada ventana tiene su propio factor; el
+// último aplicado se guarda en las preferencias para restaurarlo al arrancar.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::accounts::AccountsManager;
+use crate::error::AppError;
+use crate::settings::SettingsUpdate;
+use crate::window::is_incognito_window;
+
+/// Factor de zoom inicial
+pub const DEFAULT_ZOOM: f64 = 1.0;
+/// Incremento por cada paso de zoom
+pub const ZOOM_STEP: f64 = 0.1;
+/// Zoom mínimo permitido
+pub const MIN_ZOOM: f64 = 0.5;
+/// Zoom máximo permitido
+pub const MAX_ZOOM: f64 = 3.0;
+
+/// Cambio de zoom solicitado desde el menú
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoomChange {
+    In,
+    Out,
+    Reset,
+}
+
+/// Factor de zoom actual de cada ventana (por label)
+#[derive(Default)]
+pub struct ZoomLevels(Mutex<HashMap<String, f64>>);
+
+/// Calcula el siguiente factor de zoom, limitado a `MIN_ZOOM..=MAX_ZOOM`
+///
+/// El resultado se redondea a un decimal para que los pasos sucesivos no
+/// acumulen error de coma flotante (1.0 + 0.1 + 0.1 ≠ 1.2).
+pub fn next_zoom(current: f64, change: ZoomChange) -> f64 {
+    let next = match change {
+        ZoomChange::In => current + ZOOM_STEP,
+        ZoomChange::Out => current - ZOOM_STEP,
+        ZoomChange::Reset => DEFAULT_ZOOM,
+    };
+
+    normalize_zoom(next)
+}
+
+/// Ajusta un factor cualquiera a un paso de `ZOOM_STEP` dentro de
+/// `MIN_ZOOM..=MAX_ZOOM` (`DEFAULT_ZOOM` si no es un número finito)
+pub fn normalize_zoom(zoom: f64) -> f64 {
+    if !zoom.is_finite() {
+        return DEFAULT_ZOOM;
+    }
+
+    ((zoom * 10.0).round() / 10.0).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Último zoom guardado (normalizado: el archivo se puede editar a mano)
+pub fn load_zoom(manager: &AccountsManager) -> f64 {
+    normalize_zoom(manager.settings().zoom)
+}
+
+/// Guarda el zoom para restaurarlo en el próximo arranque
+pub fn save_zoom(manager: &AccountsManager, zoom: f64) -> Result<(), AppError> {
+    manager.update_settings(&SettingsUpdate {
+        zoom: Some(zoom),
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// Aplica `zoom` a la ventana y lo registra como su factor actual
+pub fn set_window_zoom<R: Runtime>(app: &AppHandle<R>, window: &WebviewWindow<R>, zoom: f64) -> Result<(), String> {
+    window
+        .set_zoom(zoom)
+        .map_err(|e| format!("Failed to set zoom: {}", e))?;
+
+    app.state::<ZoomLevels>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), zoom);
+
+    Ok(())
+}
+
+/// Factor de zoom actual de la ventana (`DEFAULT_ZOOM` si nunca se ha cambiado)
+pub fn window_zoom<R: Runtime>(app: &AppHandle<R>, window: &WebviewWindow<R>) -> f64 {
+    app.state::<ZoomLevels>()
+        .0
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .copied()
+        .unwrap_or(DEFAULT_ZOOM)
+}
+
+/// Aplica un cambio de zoom a la ventana y persiste el resultado
+///
+/// # Returns
+/// Nuevo factor de zoom
+pub fn change_zoom<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &WebviewWindow<R>,
+    manager: &AccountsManager,
+    change: ZoomChange,
+) -> Result<f64, String> {
+    let zoom = next_zoom(window_zoom(app, window), change);
+    apply_zoom(app, window, manager, zoom)
+}
+
+/// Aplica un factor concreto (normalizado con `normalize_zoom`) a la ventana
+/// y lo persiste
+///
+/// El zoom de la ventana de incógnito no se guarda: no deja rastro al cerrarla.
+///
+/// # Returns
+/// Factor aplicado
+pub fn apply_zoom<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &WebviewWindow<R>,
+    manager: &AccountsManager,
+    zoom: f64,
+) -> Result<f64, String> {
+    let zoom = normalize_zoom(zoom);
+    set_window_zoom(app, window, zoom)?;
+
+    if is_incognito_window(window.label()) {
+        return Ok(zoom);
+    }
+
+    if let Err(e) = save_zoom(manager, zoom) {
+        tracing::warn!("Failed to persist zoom level: {}", e);
+    }
+
+    Ok(zoom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::InMemoryStore;
+
+    #[test]
+    fn test_next_zoom_steps_and_reset() {
+        assert_eq!(next_zoom(1.0, ZoomChange::In), 1.1);
+        assert_eq!(next_zoom(1.1, ZoomChange::In), 1.2);
+        assert_eq!(next_zoom(1.0, ZoomChange::Out), 0.9);
+        assert_eq!(next_zoom(2.4, ZoomChange::Reset), DEFAULT_ZOOM);
+
+        // Sin error acumulado tras varios pasos
+        let zoom = (0..5).fold(1.0, |zoom, _| next_zoom(zoom, ZoomChange::In));
+        assert_eq!(zoom, 1.5);
+    }
+
+    #[test]
+    fn test_next_zoom_clamps_to_limits() {
+        assert_eq!(next_zoom(MAX_ZOOM, ZoomChange::In), MAX_ZOOM);
+        assert_eq!(next_zoom(MIN_ZOOM, ZoomChange::Out), MIN_ZOOM);
+        assert_eq!(next_zoom(10.0, ZoomChange::Out), MAX_ZOOM);
+        assert_eq!(next_zoom(0.1, ZoomChange::In), MIN_ZOOM);
+    }
+
+    #[test]
+    fn test_normalize_zoom() {
+        assert_eq!(normalize_zoom(1.25), 1.3);
+        assert_eq!(normalize_zoom(0.1), MIN_ZOOM);
+        assert_eq!(normalize_zoom(7.0), MAX_ZOOM);
+        assert_eq!(normalize_zoom(f64::NAN), DEFAULT_ZOOM);
+        assert_eq!(normalize_zoom(f64::INFINITY), DEFAULT_ZOOM);
+    }
+
+    #[test]
+    fn test_zoom_persistence() {
+        let manager = AccountsManager::new(Box::leak(Box::new(InMemoryStore::new())));
+        assert_eq!(load_zoom(&manager), DEFAULT_ZOOM);
+
+        save_zoom(&manager, 1.3).unwrap();
+        assert_eq!(load_zoom(&manager), 1.3);
+
+        // Valores fuera de rango no rompen el arranque
+        save_zoom(&manager, 42.0).unwrap();
+        assert_eq!(load_zoom(&manager), MAX_ZOOM);
+        save_zoom(&manager, f64::NAN).unwrap();
+        assert_eq!(load_zoom(&manager), DEFAULT_ZOOM);
+    }
+}
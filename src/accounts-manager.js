@@ -0,0 +1,194 @@
+/**
+ * X - Cliente no oficial de X (Twitter) para macOS
+ * Copyright © 2024 686f6c61
+ *
+ * @author 686f6c61 (https://github.com/686f6c61)
+ * @repository https://github.com/686f6c61/Xcom-mac-silicon
+ * @description Accounts manager script - Lista las cuentas y permite cambiar,
+ * renombrar, archivar, etiquetar y eliminar cada una. Usa los mismos comandos
+ * que el resto de la aplicación y se actualiza con el evento accounts-changed.
+ */
+
+const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
+
+/**
+ * Muestra un error bajo la lista (o lo borra si no se pasa mensaje).
+ *
+ * @function showStatus
+ * @param {string} [message] - Texto del error
+ */
+function showStatus(message = '') {
+  document.getElementById('status').textContent = message;
+}
+
+/**
+ * Ejecuta un comando y vuelve a pintar la lista; los errores se muestran en
+ * la ventana en lugar de perderse en la consola.
+ *
+ * @async
+ * @function run
+ * @param {string} command - Comando de Tauri
+ * @param {Object} args - Argumentos del comando
+ * @returns {Promise<void>}
+ */
+async function run(command, args) {
+  try {
+    showStatus();
+    await invoke(command, args);
+    await loadAccounts();
+  } catch (error) {
+    console.error(`[Accounts Manager] ${command} failed:`, error);
+    showStatus(error?.message ?? String(error));
+  }
+}
+
+/**
+ * Crea un botón de acción.
+ *
+ * @function actionButton
+ * @param {string} text - Texto del botón
+ * @param {Function} onClick - Acción al pulsarlo
+ * @param {Object} [options] - `disabled` y `danger`
+ * @returns {HTMLButtonElement}
+ */
+function actionButton(text, onClick, { disabled = false, danger = false } = {}) {
+  const button = document.createElement('button');
+  button.textContent = text;
+  button.disabled = disabled;
+  if (danger) {
+    button.classList.add('danger');
+  }
+  button.addEventListener('click', onClick);
+  return button;
+}
+
+/**
+ * Pinta una fila con los datos y las acciones de una cuenta.
+ *
+ * @function renderAccount
+ * @param {Object} account - AccountDetails devuelto por get_account_details
+ * @param {boolean} active - Es la cuenta activa
+ * @returns {HTMLElement}
+ */
+function renderAccount(account, active) {
+  const { username } = account;
+
+  const row = document.createElement('div');
+  row.className = 'account';
+  row.classList.toggle('active', active);
+  row.classList.toggle('archived', account.archived);
+  if (account.color) {
+    row.style.borderLeftColor = account.color;
+  }
+
+  const info = document.createElement('div');
+  info.className = 'account-info';
+
+  const name = document.createElement('div');
+  name.className = 'account-name';
+  const label = account.label ? `${account.label} ` : '';
+  name.textContent = account.display_name
+    ? `${label}${account.display_name} (@${username})`
+    : `${label}@${username}`;
+
+  const meta = document.createElement('div');
+  meta.className = 'account-meta';
+  meta.textContent = [
+    active && 'Activa',
+    account.archived && 'Archivada',
+    account.pinned && 'Fijada',
+    account.needs_relogin && 'Requiere iniciar sesión',
+    account.has_session ? `Sesión guardada hace ${account.session_age_days} días` : 'Sin sesión guardada',
+  ].filter(Boolean).join(' · ');
+
+  info.append(name, meta);
+
+  const actions = document.createElement('div');
+  actions.className = 'actions';
+  actions.append(
+    actionButton('Cambiar', () => run('switch_account', { username }), {
+      disabled: active || account.archived,
+    }),
+    actionButton('Renombrar', () => {
+      const newUsername = window.prompt(`Nuevo nombre de usuario para @${username}`, username);
+      if (newUsername && newUsername !== username) {
+        run('rename_account', { oldUsername: username, newUsername: newUsername.replace(/^@/, '') });
+      }
+    }),
+    account.archived
+      ? actionButton('Desarchivar', () => run('unarchive_account', { username }))
+      : actionButton('Archivar', () => run('archive_account', { username }), { disabled: active }),
+    actionButton('Etiqueta', () => {
+      const newLabel = window.prompt(`Etiqueta para @${username} (texto corto o emoji, vacío para quitarla)`, account.label ?? '');
+      if (newLabel !== null) {
+        run('set_account_label', { username, label: newLabel, color: account.color ?? null });
+      }
+    }),
+    actionButton('Eliminar', () => {
+      const confirmed = window.confirm(
+        `¿Eliminar la cuenta @${username}? Sus credenciales se borrarán del Keychain. Esta acción no se puede deshacer.`
+      );
+      if (confirmed) {
+        run('delete_account', { username, confirm: true });
+      }
+    }, { danger: true }),
+  );
+
+  row.append(info, actions);
+  return row;
+}
+
+/**
+ * Pinta la lista de cuentas.
+ *
+ * @function renderAccounts
+ * @param {Object[]} accounts - Detalles de las cuentas (incluidas las archivadas)
+ * @param {string|null} activeUsername - Cuenta activa
+ */
+function renderAccounts(accounts, activeUsername) {
+  const container = document.getElementById('accounts');
+  container.replaceChildren();
+
+  if (accounts.length === 0) {
+    const empty = document.createElement('p');
+    empty.className = 'empty';
+    empty.textContent = 'No hay cuentas. Añade una desde Cuentas > Agregar Cuenta...';
+    container.append(empty);
+    return;
+  }
+
+  for (const account of accounts) {
+    container.append(renderAccount(account, account.username === activeUsername));
+  }
+}
+
+/**
+ * Carga las cuentas (con los detalles de sus credenciales) y las pinta.
+ *
+ * @async
+ * @function loadAccounts
+ * @returns {Promise<void>}
+ */
+async function loadAccounts() {
+  try {
+    const [accounts, activeUsername] = await Promise.all([
+      invoke('list_accounts', { includeArchived: true }),
+      invoke('get_active_account'),
+    ]);
+    const details = await Promise.all(
+      accounts.map(({ username }) => invoke('get_account_details', { username }))
+    );
+    renderAccounts(details, activeUsername);
+  } catch (error) {
+    console.error('[Accounts Manager] Failed to load accounts:', error);
+    showStatus('No se pudieron cargar las cuentas.');
+  }
+}
+
+document.addEventListener('DOMContentLoaded', () => {
+  loadAccounts();
+
+  // Cambios hechos desde el menú u otras ventanas
+  listen('accounts-changed', () => loadAccounts());
+});
@@ -20,6 +20,7 @@
     }
 
     let lastUsername = null;
+    let lastBadgeCount = null;
 
     /**
      * Extrae el username del usuario actualmente logueado
@@ -62,6 +63,41 @@
         return null;
     }
 
+    /**
+     * Extrae nombre visible y avatar del usuario logueado
+     */
+    function extractProfile() {
+        let displayName = null;
+        let avatarUrl = null;
+
+        try {
+            // Estrategia 1: localStorage 'user'
+            const userDataStr = localStorage.getItem('user');
+            if (userDataStr) {
+                const userData = JSON.parse(userDataStr);
+                displayName = userData.name || null;
+                avatarUrl = userData.profile_image_url_https || null;
+            }
+
+            // Estrategia 2: Botón de cambio de cuenta en sidebar
+            const switcher = document.querySelector('[data-testid="SideNav_AccountSwitcher_Button"]');
+            if (switcher) {
+                if (!avatarUrl) {
+                    const img = switcher.querySelector('img');
+                    avatarUrl = img ? img.src : null;
+                }
+                if (!displayName) {
+                    const nameEl = switcher.querySelector('[dir="ltr"] span');
+                    displayName = nameEl ? nameEl.textContent.trim() : null;
+                }
+            }
+        } catch (e) {
+            console.error('[Login Detector] Failed to extract profile:', e);
+        }
+
+        return { displayName, avatarUrl };
+    }
+
     /**
      * Extrae el token de autenticación de las cookies
      */
@@ -105,6 +141,15 @@
 
                 console.log('[Login Detector] Account saved successfully:', currentUsername, 'UUID:', uuid);
 
+                const profile = extractProfile();
+                if (profile.displayName || profile.avatarUrl) {
+                    await TAURI_INVOKE('update_account_profile', {
+                        username: currentUsername,
+                        displayName: profile.displayName,
+                        avatarUrl: profile.avatarUrl
+                    });
+                }
+
                 // Notificar al padre (main.js) para reconstruir el menú
                 window.parent.postMessage({
                     type: 'account-added',
@@ -114,22 +159,292 @@
 
             } catch (error) {
                 console.error('[Login Detector] Failed to save account:', error);
+
+                if (error?.kind === 'account_limit_reached') {
+                    window.alert(`No se ha guardado @${currentUsername}: ${error.message}. Elimina alguna cuenta o aumenta el límite.`);
+                }
+            }
+        }
+    }
+
+    /**
+     * Lee el contador de la pestaña Notificaciones de la barra lateral
+     * X lo muestra como "3" o "20+"; sin contador devuelve 0
+     */
+    function extractNotificationCount() {
+        const link = document.querySelector('[data-testid="AppTabBar_Notifications_Link"]');
+        const badge = link ? link.querySelector('[aria-live="polite"]') : null;
+        const count = badge ? parseInt(badge.textContent, 10) : 0;
+        return Number.isFinite(count) && count > 0 ? count : 0;
+    }
+
+    /**
+     * Envía el contador de notificaciones al Dock cuando cambia
+     */
+    async function reportNotificationCount() {
+        const count = extractNotificationCount();
+        if (count === lastBadgeCount) {
+            return;
+        }
+        lastBadgeCount = count;
+
+        try {
+            await TAURI_INVOKE('set_badge_count', { count });
+        } catch (error) {
+            console.error('[Login Detector] Failed to update badge count:', error);
+        }
+    }
+
+    /**
+     * Pide confirmación antes de borrar entradas huérfanas del Keychain (menú Ayuda)
+     */
+    function listenForKeychainCleanup() {
+        const listen = window.__TAURI__?.event?.listen;
+        if (!listen) {
+            return;
+        }
+
+        listen('confirm-keychain-cleanup', async (event) => {
+            const count = event.payload;
+            if (count === 0) {
+                window.alert('No hay entradas huérfanas en el Llavero.');
+                return;
+            }
+
+            const confirmed = window.confirm(
+                `Se han encontrado ${count} entradas del Llavero que no pertenecen a ninguna cuenta. ¿Eliminarlas?`
+            );
+            if (!confirmed) {
+                return;
+            }
+
+            try {
+                const report = await TAURI_INVOKE('cleanup_keychain', { preview: false });
+                console.log('[Login Detector] Removed orphaned keychain entries:', report.removed);
+            } catch (error) {
+                console.error('[Login Detector] Failed to clean up keychain:', error);
+            }
+        });
+    }
+
+    /**
+     * Muestra el resultado del diagnóstico de credenciales (menú Ayuda)
+     */
+    function listenForCredentialsDiagnostic() {
+        const listen = window.__TAURI__?.event?.listen;
+        if (!listen) {
+            return;
+        }
+
+        listen('credentials-diagnostic', (event) => {
+            const report = event.payload || [];
+            if (report.length === 0) {
+                window.alert('No hay cuentas guardadas.');
+                return;
+            }
+
+            const lines = report.map(([username, valid]) => `${valid ? '✓' : '✗'} @${username}`);
+            const failed = report.filter(([, valid]) => !valid).length;
+            const summary = failed === 0
+                ? 'Todas las credenciales se descifran correctamente.'
+                : `${failed} cuenta(s) no se pueden descifrar. Vuelve a iniciar sesión en ellas.`;
+
+            window.alert(`${summary}\n\n${lines.join('\n')}`);
+        });
+    }
+
+    /**
+     * Exporta o importa las cuentas (menú Cuentas); la ruta llega ya elegida
+     * en un diálogo nativo y aquí solo se pide la passphrase
+     */
+    function listenForAccountsBackup() {
+        const listen = window.__TAURI__?.event?.listen;
+        if (!listen) {
+            return;
+        }
+
+        listen('accounts-export-requested', async (event) => {
+            const path = event.payload;
+            const passphrase = window.prompt('Passphrase para cifrar la copia de seguridad');
+            if (!passphrase) {
+                return;
+            }
+            if (window.prompt('Repite la passphrase') !== passphrase) {
+                window.alert('Las passphrases no coinciden. No se ha exportado nada.');
+                return;
+            }
+
+            try {
+                const count = await TAURI_INVOKE('export_accounts_backup', { path, passphrase });
+                showBanner(`${count} cuenta(s) exportadas`);
+            } catch (error) {
+                console.error('[Login Detector] Failed to export accounts:', error);
+                window.alert(`No se pudieron exportar las cuentas.\n\n${error?.message ?? error}`);
+            }
+        });
+
+        listen('accounts-import-requested', async (event) => {
+            const path = event.payload;
+            const passphrase = window.prompt('Passphrase de la copia de seguridad');
+            if (!passphrase) {
+                return;
             }
+            const overwrite = window.confirm(
+                '¿Sobrescribir las cuentas que ya existen con las de la copia?\n\nCancelar: conservar las actuales.'
+            );
+
+            try {
+                const summary = await TAURI_INVOKE('import_accounts_backup', { path, passphrase, overwrite });
+                const skipped = summary.skipped.length > 0 ? `, ${summary.skipped.length} omitidas` : '';
+                showBanner(`${summary.imported.length} cuenta(s) importadas${skipped}`);
+            } catch (error) {
+                console.error('[Login Detector] Failed to import accounts:', error);
+                window.alert(`No se pudieron importar las cuentas.\n\n${error?.message ?? error}`);
+            }
+        });
+    }
+
+    /**
+     * Muestra un aviso breve en la parte superior de la página
+     */
+    function showBanner(message) {
+        const banner = document.createElement('div');
+        banner.textContent = message;
+        banner.style.cssText = 'position:fixed;top:12px;left:50%;transform:translateX(-50%);z-index:99999;'
+            + 'padding:8px 16px;border-radius:8px;background:#1d9bf0;color:#fff;font:14px -apple-system,sans-serif;';
+        document.body.appendChild(banner);
+        setTimeout(() => banner.remove(), 6000);
+    }
+
+    /**
+     * Descarga el instalador de la actualización y lo abre; el progreso llega
+     * con `update-download-progress`
+     */
+    async function downloadUpdate(asset) {
+        showBanner(`Descargando ${asset.name}...`);
+        try {
+            await TAURI_INVOKE('download_update', { url: asset.url });
+            showBanner('Descarga completada: abriendo el instalador');
+        } catch (error) {
+            console.error('[Login Detector] Failed to download update:', error);
+            showBanner('No se pudo descargar la actualización');
         }
     }
 
+    /**
+     * Avisa de una versión nueva según la política de actualizaciones: con
+     * `notify_only` solo un aviso breve; si no, las novedades y la opción de
+     * descargar el instalador (o abrir la release si no lo hay)
+     */
+    function listenForUpdates() {
+        const listen = window.__TAURI__?.event?.listen;
+        if (!listen) {
+            return;
+        }
+
+        let lastPercent = 0;
+        listen('update-download-progress', (event) => {
+            const { bytes, total } = event.payload;
+            const percent = total > 0 ? Math.floor((bytes / total) * 100) : 0;
+            // Un aviso cada 25 %
+            if (percent >= lastPercent + 25 || percent < lastPercent) {
+                lastPercent = percent - (percent % 25);
+                showBanner(`Descargando actualización: ${percent} %`);
+            }
+        });
+
+        listen('update-available', async (event) => {
+            const info = event.payload;
+            const policy = await TAURI_INVOKE('get_update_policy').catch(() => 'open_page');
+            if (policy === 'notify_only') {
+                showBanner(`Hay una nueva versión disponible: ${info.latest}`);
+                return;
+            }
+
+            const notes = info.release_notes ? `\n\nNovedades:\n${info.release_notes}` : '';
+            const confirmed = window.confirm(
+                `Hay una nueva versión disponible: ${info.latest} (tienes ${info.current}).`
+                + `${notes}\n\n`
+                + (info.asset ? '¿Descargar e instalar la actualización?' : '¿Abrir la página de descarga?')
+            );
+            if (!confirmed) {
+                // Omitir esta versión o recordarlo mañana
+                const skip = window.confirm(
+                    `¿Omitir la versión ${info.latest}? No se volverá a avisar hasta la siguiente.`
+                    + '\n\nCancelar: recordármelo mañana.'
+                );
+                try {
+                    if (skip) {
+                        await TAURI_INVOKE('skip_update_version', { version: info.latest });
+                    } else {
+                        await TAURI_INVOKE('snooze_updates', { hours: 24 });
+                    }
+                } catch (error) {
+                    console.error('[Login Detector] Failed to dismiss update:', error);
+                }
+                return;
+            }
+
+            if (info.asset) {
+                await downloadUpdate(info.asset);
+                return;
+            }
+
+            try {
+                await TAURI_INVOKE('open_release_page');
+            } catch (error) {
+                console.error('[Login Detector] Failed to open release page:', error);
+            }
+        });
+    }
+
+    /**
+     * Muestra un aviso breve con el resultado de la migración de v0.3.0
+     */
+    function listenForMigration() {
+        const listen = window.__TAURI__?.event?.listen;
+        if (!listen) {
+            return;
+        }
+
+        listen('migration-complete', (event) => {
+            const report = event.payload;
+            let message = null;
+            if (report.errors.length > 0) {
+                message = 'No se pudo migrar la cuenta anterior: vuelve a iniciar sesión.';
+            } else if (report.accounts_migrated > 0) {
+                message = 'Tu cuenta anterior se ha migrado correctamente.';
+            }
+            if (!message) {
+                return;
+            }
+
+            showBanner(message);
+        });
+    }
+
     /**
      * Inicia el monitoreo de login
      */
     function startMonitoring() {
         console.log('[Login Detector] Started monitoring for login events');
 
+        listenForMigration();
+        listenForKeychainCleanup();
+        listenForCredentialsDiagnostic();
+        listenForAccountsBackup();
+        listenForUpdates();
+
         // Check inicial
         checkForLogin();
 
         // Check periódico cada 3 segundos
         setInterval(checkForLogin, 3000);
 
+        // Notificaciones pendientes en el Dock
+        reportNotificationCount();
+        setInterval(reportNotificationCount, 3000);
+
         // Escuchar cambios en localStorage
         window.addEventListener('storage', (e) => {
             if (e.key === 'user') {
@@ -146,6 +461,10 @@
                 lastUrl = url;
                 console.log('[Login Detector] Navigation detected:', url);
                 setTimeout(checkForLogin, 500); // Delay para que cargue el DOM
+                // La app lee la URL nueva (perfiles de cuentas aún no guardadas)
+                TAURI_INVOKE('detect_logged_in_user').catch((error) => {
+                    console.error('[Login Detector] Failed to detect account from URL:', error);
+                });
             }
         }).observe(document, { subtree: true, childList: true });
     }